@@ -83,6 +83,10 @@ impl<T> Slab<T> {
         next_key
     }
 
+    pub fn contains(&self, key: Key) -> bool {
+        self.keys.get(key).map_or(false, |index| index.is_some())
+    }
+
     pub fn get(&self, key: Key) -> Option<&T> {
         let index_data = self.keys[key].unwrap();
         self.data.inner.get(index_data)
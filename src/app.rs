@@ -1,5 +1,6 @@
 use crate::game::{Game, GameInfo};
-use crate::game_solver::GameSolver;
+use crate::game_solver::{Frontend, GameSolver};
+use crate::gui::Gui;
 use crate::window::Window;
 use crate::renderer::Renderer;
 use app_trait::AppRunnerInfos;
@@ -13,6 +14,7 @@ pub struct App {
     pub app_runner_infos: AppRunnerInfos, //TODO: Not very good. Find a better way
     pub window: Window,
     pub game: Game,
+    pub gui: Gui,
     pub renderer: Renderer,
     pub update_thread_running: AtomicBool,
 }
@@ -23,22 +25,24 @@ impl App {
 
         let window = Window::new();
         let game = Game::new(GameInfo {
-            window: &window,
             threads: info.threads,
         });
-        let renderer = Renderer::new(&window.window, &game);
+        let gui = Gui::new(&window);
+        let renderer = Renderer::new(&window.window, &game, &gui);
 
         App {
             app_runner_infos,
             window,
             game,
+            gui,
             renderer,
             update_thread_running: AtomicBool::new(true),
         }
     }
 
     pub fn run(&mut self) {
-        self.game.init();
+        if let Err(err) = self.game.init() { self.gui.show_message(err); }
+        self.renderer.camera_future.size = self.game.settings.camera_initial;
         
         self.app_runner_infos.add_function(|| {
             let app = crate::APP.get_mut();
@@ -58,11 +62,15 @@ impl App {
     }
 
     fn check_events(&mut self) {
-        self.window.poll_events(&self.game, &mut self.renderer);
+        self.window.poll_events(&self.game, &self.gui, &mut self.renderer);
     }
 
     fn update(&mut self) {
-        let mut game_solver = GameSolver::new(&mut self.game, &mut self.window.events, self.renderer.camera.clone());
+        let mut game_solver = GameSolver::with_frontend(&mut self.game, Frontend {
+            events: &mut self.window.events,
+            gui: &self.gui,
+            camera: self.renderer.camera.clone(),
+        });
         game_solver.solve();
 
         if !self.app_runner_infos.running() {
@@ -71,7 +79,7 @@ impl App {
     }
 
     fn draw(&mut self) {
-        self.renderer.draw(&self.game, &self.window);
+        self.renderer.draw(&self.game, &self.gui, &self.window);
     }
 
     fn handle_closing(&mut self) {
@@ -0,0 +1,500 @@
+// Headless runner: loads a map, runs it for some ticks and prints stats and timings.
+//
+// cargo run --release --bin cells-sim -- "black hole" --ticks 1000 --threads 4 --seed 42
+// cargo run --release --bin cells-sim -- maps/Map_test.binmap
+// cargo run --release --bin cells-sim -- replays/last.binreplay
+// cargo run --release --bin cells-sim -- "black hole" --ticks 500 --save saves/black_hole.binsave
+// cargo run --release --bin cells-sim -- --import-image "pixel art.png" --spacing 2000 --skip 1 --ticks 0 --save maps/pixel_art.binmap
+// cargo run --release --bin cells-sim -- classic --bots 10 --bot-difficulty hard --ticks 5000
+// cargo run --release --bin cells-sim -- classic --bots 6 --mode last-man-standing --rounds 5
+
+use cells::game::{Game, GameInfo};
+use cells::game::bot::{self, BotSettings, DIFFICULTIES};
+use cells::game::entity::RATIO_MASS;
+use cells::game::map::image_import::{ImageImport, ImageMass};
+use cells::game::map::premade::{create_premade_map, premade_maps};
+use cells::game::mode::{ModeSettings, Session, SessionBots, MODES};
+use cells::game::player::LEADERBOARD_SIZE;
+use cells::game_solver::GameSolver;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+struct SimArgs {
+    map: String,
+    ticks: Option<usize>,
+    threads: usize,
+    seed: Option<u64>,
+    thread_independent: bool,
+    hashes: bool,
+    check_invariants: bool,
+    save: Option<String>,
+    import: Option<ImageImport>, // The map is created from the image
+    bots: usize, // Added to the map
+    bot: BotSettings,
+    bot_team: Option<usize>, // Index in the teams of the map
+    mode: Option<ModeSettings>, // Rounds on the premade map, run until the last one without --ticks
+}
+
+const USAGE: &str = "usage: cells-sim <premade map name | file.ron | file.binmap | file.ronmap | file.binsave | file.binreplay> [--ticks N] [--threads N] [--seed N] [--thread-independent] [--hashes] [--check-invariants] [--bots N] [--bot-difficulty easy | normal | hard] [--bot-team N] [--mode free-for-all | last-man-standing | target-mass | king-of-the-hill] [--rounds N] [--time-limit N] [--save file.binmap | file.ronmap | file.binsave]\n       cells-sim --import-image <image> [--scale F] [--spacing N] [--skip N] [--alpha-threshold N] [--mass-brightness DARK BRIGHT] [--templates file.ron] [options above]\n       cells-sim --list\n       cells-sim --check-maps <folder>";
+
+fn parse_args() -> Result<SimArgs, String> {
+    let mut map = None;
+    let mut ticks = None;
+    let mut threads = 2;
+    let mut seed = None;
+    let mut thread_independent = false;
+    let mut hashes = false;
+    let mut check_invariants = false;
+    let mut save = None;
+    let mut import_image = None;
+    let mut import = ImageImport::default();
+    let mut bots = 0;
+    let mut bot = BotSettings::default();
+    let mut bot_team = None;
+    let mut mode = None;
+    let mut rounds = None;
+    let mut time_limit = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ticks" => {
+                let value = args.next().ok_or("--ticks needs a value")?;
+                ticks = Some(value.parse().map_err(|_| format!("invalid tick count: {}", value))?);
+            }
+            "--threads" => {
+                let value = args.next().ok_or("--threads needs a value")?;
+                threads = value.parse().map_err(|_| format!("invalid thread count: {}", value))?;
+            }
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a value")?;
+                seed = Some(value.parse().map_err(|_| format!("invalid seed: {}", value))?);
+            }
+            "--thread-independent" => {
+                thread_independent = true;
+            }
+            "--hashes" => {
+                hashes = true;
+            }
+            "--check-invariants" => {
+                check_invariants = true;
+            }
+            "--save" => {
+                save = Some(args.next().ok_or("--save needs a path")?);
+            }
+            "--bots" => {
+                let value = args.next().ok_or("--bots needs a value")?;
+                bots = value.parse().map_err(|_| format!("invalid bot count: {}", value))?;
+            }
+            "--bot-difficulty" => {
+                let value = args.next().ok_or("--bot-difficulty needs a value")?;
+                bot = BotSettings::difficulty(&value).ok_or_else(|| format!("unknown difficulty: {} ({})", value, DIFFICULTIES.join(", ")))?;
+            }
+            "--bot-team" => {
+                let value = args.next().ok_or("--bot-team needs a value")?;
+                bot_team = Some(value.parse().map_err(|_| format!("invalid team: {}", value))?);
+            }
+            "--mode" => {
+                let value = args.next().ok_or("--mode needs a value")?;
+                mode = Some(ModeSettings::from_name(&value).ok_or_else(|| format!("unknown mode: {} ({})", value, MODES.join(", ")))?);
+            }
+            "--rounds" => {
+                let value = args.next().ok_or("--rounds needs a value")?;
+                rounds = Some(value.parse().map_err(|_| format!("invalid round count: {}", value))?);
+            }
+            "--time-limit" => {
+                let value = args.next().ok_or("--time-limit needs a value")?;
+                time_limit = Some(value.parse().map_err(|_| format!("invalid time limit: {}", value))?);
+            }
+            "--import-image" => {
+                import_image = Some(args.next().ok_or("--import-image needs an image")?);
+            }
+            "--scale" => {
+                let value = args.next().ok_or("--scale needs a value")?;
+                import.scale = value.parse().map_err(|_| format!("invalid scale: {}", value))?;
+            }
+            "--spacing" => {
+                let value = args.next().ok_or("--spacing needs a value")?;
+                import.spacing = value.parse().map_err(|_| format!("invalid spacing: {}", value))?;
+            }
+            "--skip" => {
+                let value = args.next().ok_or("--skip needs a value")?;
+                import.skip = value.parse().map_err(|_| format!("invalid skip: {}", value))?;
+            }
+            "--alpha-threshold" => {
+                let value = args.next().ok_or("--alpha-threshold needs a value")?;
+                import.alpha_threshold = value.parse().map_err(|_| format!("invalid alpha threshold: {}", value))?;
+            }
+            "--mass-brightness" => {
+                let dark = args.next().ok_or("--mass-brightness needs two masses")?;
+                let bright = args.next().ok_or("--mass-brightness needs two masses")?;
+                import.mass = ImageMass::Brightness(
+                    dark.parse().map_err(|_| format!("invalid mass: {}", dark))?,
+                    bright.parse().map_err(|_| format!("invalid mass: {}", bright))?,
+                );
+            }
+            "--templates" => {
+                let path = args.next().ok_or("--templates needs a path")?;
+                import.templates = load_templates(&path)?;
+            }
+            "--check-maps" => {
+                let folder = args.next().ok_or("--check-maps needs a folder")?;
+                std::process::exit(check_maps(&folder));
+            }
+            "--list" => {
+                for map in premade_maps().iter() {
+                    println!("{}", map);
+                }
+                std::process::exit(0);
+            }
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ => {
+                if map.is_some() { return Err(format!("unexpected argument: {}", arg)) }
+                map = Some(arg);
+            }
+        }
+    }
+
+    let import = match import_image {
+        Some(path) => {
+            if map.is_some() { return Err("--import-image replaces the map".to_owned()) }
+            map = Some(path.clone());
+            import.path = path;
+            import.validate();
+            Some(import)
+        }
+        None => None,
+    };
+    if bots > 0 && map.as_ref().map_or(false, |map| map.ends_with(".binreplay")) {
+        return Err("--bots can't change a replay".to_owned())
+    }
+    if let Some(mode) = mode.as_mut() {
+        if import.is_some() { return Err("--mode needs a premade map".to_owned()) }
+        if let Some(rounds) = rounds { mode.rounds = rounds; }
+        if let Some(time_limit) = time_limit { mode.time_limit = if time_limit == 0 { None } else { Some(time_limit) }; }
+    } else if rounds.is_some() || time_limit.is_some() {
+        return Err("--rounds and --time-limit need --mode".to_owned())
+    }
+
+    Ok(SimArgs {
+        map: map.ok_or("no map given")?,
+        ticks,
+        threads,
+        seed,
+        thread_independent,
+        hashes,
+        check_invariants,
+        save,
+        import,
+        bots,
+        bot,
+        bot_team,
+        mode,
+    })
+}
+
+fn print_round(game: &Game, index: usize) {
+    let session = game.session.as_ref().unwrap();
+    let result = &session.results[index];
+    let winner = result.winner.map_or_else(|| "draw".to_owned(), |winner| winner.name(game));
+    println!("round {} ({}): {} after {} ticks", result.round, session.mode.name(), winner, result.ticks);
+    for standing in result.standings.iter() {
+        println!("  {}: mass {}, score {}{}", standing.side.name(game), standing.mass, standing.score, if standing.alive { "" } else { " (dead)" });
+    }
+}
+
+fn print_session(game: &Game) {
+    if let Some(err) = &game.step.session_error { println!("session: stopped, {}", err); }
+    let session = match game.session.as_ref() {
+        Some(session) => session,
+        None => return,
+    };
+    println!("mode: {}, {} rounds played", session.mode.name(), session.results.len());
+    let mut counted = Vec::new();
+    for winner in session.results.iter().filter_map(|result| result.winner) {
+        if counted.contains(&winner) { continue }
+        counted.push(winner);
+        println!("  {}: {} wins", winner.name(game), session.wins(winner));
+    }
+}
+
+fn print_leaderboard(game: &Game) {
+    let leaderboard = game.leaderboard(LEADERBOARD_SIZE);
+    if leaderboard.is_empty() { return }
+    println!("leaderboard:");
+    for (rank, entry) in leaderboard.iter().enumerate() {
+        let stats = &entry.stats;
+        println!("  {}. {}: mass {} (peak {}), eaten {} cells / {} players, {} deaths, alive {} ticks, decayed {}",
+            rank + 1, entry.name, stats.mass, stats.peak_mass, stats.cells_eaten, stats.players_eaten, stats.deaths, stats.ticks_alive, stats.mass_decayed);
+    }
+}
+
+fn load_map(game: &mut Game, map: &str, seed: Option<u64>) -> Result<(), String> {
+    if premade_maps().iter().any(|premade| premade == map) {
+        if let Some(seed) = seed { game.rng.reseed(seed) }
+        return create_premade_map(game, map)
+    }
+    load_map_file(game, map)?;
+    if let Some(seed) = seed { game.rng.reseed(seed) }
+    Ok(())
+}
+
+#[cfg(feature = "serialize")]
+fn load_map_file(game: &mut Game, path: &str) -> Result<(), String> {
+    if !std::path::Path::new(path).is_file() {
+        return Err(format!("unknown map: {} (see --list for premade maps)", path))
+    }
+    game.load_file(path)
+}
+
+// Loads every map of the folder (map definitions, and map files with the upgrades of old versions). Returns the exit code
+#[cfg(feature = "serialize")]
+fn check_maps(folder: &str) -> i32 {
+    let mut paths: Vec<std::path::PathBuf> = match std::fs::read_dir(folder) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+        Err(err) => {
+            eprintln!("can't read {}: {}", folder, err);
+            return 1
+        }
+    };
+    paths.sort();
+
+    let mut failed = 0;
+    for path in paths.iter() {
+        let path = path.to_string_lossy();
+        let mut game = Game::new(GameInfo { threads: 0 });
+        if path.ends_with(cells::game::map::definition::DEFINITION_EXTENSION) {
+            match game.load_map(&path) {
+                Ok(()) => {
+                    GameSolver::new(&mut game).solve();
+                    println!("ok: {} (map definition, {} entities)", path, game.entities.len());
+                }
+                Err(err) => {
+                    println!("failed: {}", err);
+                    failed += 1;
+                }
+            }
+            continue
+        }
+        if !path.ends_with(".binmap") && !path.ends_with(".ronmap") { continue }
+        let result = cells::game::read_map(&path).and_then(|(mut map, version)| {
+            map.resolve_textures(&game.textures).map_err(|err| format!("can't load {}: {}", path, err))?;
+            Ok((map, version))
+        });
+        match result {
+            Ok((map, version)) => {
+                map.to_game(&mut game);
+                GameSolver::new(&mut game).solve();
+                println!("ok: {} (version {} -> {}, {} entities)", path, version, cells::game::format::VERSION, game.entities.len());
+            }
+            Err(err) => {
+                println!("failed: {}", err);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 { 1 } else { 0 }
+}
+
+// Vec<PixelTemplate> in RON: [(pixels: Color((255, 0, 0), 10), characteristics: Some((killer: true))), ...]
+#[cfg(feature = "serialize")]
+fn load_templates(path: &str) -> Result<Vec<cells::game::map::image_import::PixelTemplate>, String> {
+    let data = std::fs::read(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+    ron::de::from_bytes(&data).map_err(|err| format!("can't load {}: {}", path, err))
+}
+
+#[cfg(not(feature = "serialize"))]
+fn load_templates(path: &str) -> Result<Vec<cells::game::map::image_import::PixelTemplate>, String> {
+    Err(format!("can't load {}: templates need the \"serialize\" feature", path))
+}
+
+#[cfg(not(feature = "serialize"))]
+fn check_maps(_folder: &str) -> i32 {
+    eprintln!("checking maps needs the \"serialize\" feature");
+    2
+}
+
+#[cfg(feature = "serialize")]
+fn save_file(game: &Game, path: &str) -> Result<(), String> {
+    game.save_file(path)
+}
+
+#[cfg(not(feature = "serialize"))]
+fn save_file(_game: &Game, path: &str) -> Result<(), String> {
+    Err(format!("can't save {}: saving needs the \"serialize\" feature", path))
+}
+
+#[cfg(feature = "serialize")]
+fn print_replay_check(game: &Game) {
+    use cells::game::replay::ReplayState;
+
+    if let ReplayState::Playing(replay, _) = &game.replay {
+        match replay.divergence {
+            Some(tick) => println!("replay: diverged at tick {}", tick),
+            None if replay.hashes.is_empty() => println!("replay: no hashes recorded"),
+            None => println!("replay: same hashes as recorded"),
+        }
+    }
+}
+
+#[cfg(not(feature = "serialize"))]
+fn print_replay_check(_game: &Game) {
+}
+
+// Returns the amount of ticks recorded
+#[cfg(feature = "serialize")]
+fn load_replay(game: &mut Game, path: &str) -> Result<usize, String> {
+    use cells::game::replay::{Replay, ReplayState};
+
+    let replay = Replay::from_file(path, &game.textures)?;
+    let ticks = replay.inputs.len();
+    replay.restore(game);
+    game.replay = ReplayState::Playing(replay, 0);
+    Ok(ticks)
+}
+
+#[cfg(not(feature = "serialize"))]
+fn load_replay(_game: &mut Game, path: &str) -> Result<usize, String> {
+    Err(format!("can't play {}: replays need the \"serialize\" feature", path))
+}
+
+#[cfg(not(feature = "serialize"))]
+fn load_map_file(_game: &mut Game, path: &str) -> Result<(), String> {
+    Err(format!("unknown map: {} (loading map files needs the \"serialize\" feature)", path))
+}
+
+#[cfg(not(feature = "shipping"))]
+fn enable_invariants() -> Result<(), String> {
+    cells::DEBUG_SETTINGS.get_mut().check_invariants = true;
+    Ok(())
+}
+
+#[cfg(feature = "shipping")]
+fn enable_invariants() -> Result<(), String> {
+    Err("--check-invariants isn't available in shipping builds".to_owned())
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut game = Game::new(GameInfo {
+        threads: args.threads,
+    });
+    let loaded = if let Some(mode) = &args.mode {
+        if let Some(seed) = args.seed { game.rng.reseed(seed) }
+        let bots = vec![SessionBots { amount: args.bots, settings: args.bot.clone(), team: args.bot_team }];
+        Session::start(&mut game, mode.clone(), &args.map, bots).map(|_| None)
+    } else if let Some(import) = &args.import {
+        import.create_map(&mut game).map(|_| {
+            if let Some(seed) = args.seed { game.rng.reseed(seed) }
+            None
+        })
+    } else if args.map.ends_with(".binreplay") {
+        load_replay(&mut game, &args.map).map(Some)
+    } else {
+        load_map(&mut game, &args.map, args.seed).map(|_| None)
+    };
+    let replay_ticks = match loaded {
+        Ok(replay_ticks) => replay_ticks,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let ticks = match &args.mode {
+        Some(mode) if mode.rounds != 0 => args.ticks.unwrap_or(usize::MAX),
+        _ => args.ticks.or(replay_ticks).unwrap_or(1_000),
+    };
+    if let Some(team) = args.bot_team {
+        if team >= game.settings.teams.len() {
+            eprintln!("team {} but the map has {} teams", team, game.settings.teams.len());
+            std::process::exit(1);
+        }
+    }
+    if args.mode.is_none() { bot::add_bots(&mut game, args.bots, &args.bot, RATIO_MASS * 10, args.bot_team); }
+
+    if args.thread_independent { game.step.thread_independent = true; }
+    if args.check_invariants {
+        if let Err(err) = enable_invariants() {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    }
+
+    let entities_start = game.entities.len();
+    let total_mass_start = game.total_mass();
+
+    let mut timers: HashMap<String, (Duration, usize, usize)> = HashMap::new();
+    let instant = Instant::now();
+    let mut ticks_done = 0;
+    let mut rounds_printed = game.session.as_ref().map_or(0, |session| session.results.len()); // A save file can have played rounds
+    for tick in 0..ticks {
+        GameSolver::new(&mut game).solve();
+        ticks_done += 1;
+        if args.hashes {
+            println!("tick {}: {:016x}", tick, game.step.world_hash);
+        }
+        for (name, timer) in game.benchmark.get() {
+            let entry = timers.entry(name).or_insert((Duration::from_nanos(0), timer.level, timer.count));
+            entry.0 += timer.duration;
+        }
+        if game.step.invariant_error.is_some() || game.step.session_error.is_some() { break }
+        if let Some(session) = game.session.as_ref() {
+            if session.results.len() > rounds_printed {
+                print_round(&game, session.results.len() - 1);
+                rounds_printed = session.results.len();
+            }
+            if session.finished() { break }
+        }
+    }
+    let ticks = ticks_done;
+    let elapsed = instant.elapsed();
+
+    println!("map: {}", args.map);
+    println!("threads: {}", game.threadpool.num_threads());
+    println!("seed: {}", game.rng.seed);
+    println!("thread_independent: {}", game.step.thread_independent);
+    println!("ticks: {} in {:.3} s ({:.1} ticks/s)", ticks, elapsed.as_secs_f64(), ticks as f64 / elapsed.as_secs_f64());
+    println!("entities: {} -> {}", entities_start, game.entities.len());
+    for (index, player) in game.players.iter().enumerate() {
+        println!("  player {} ({:?}): {}", index, player.kind, player.entities.len());
+    }
+    println!("total_mass: {} -> {}", total_mass_start, game.total_mass());
+    print_leaderboard(&game);
+    println!("world_hash: {:016x}", game.step.world_hash);
+    print_replay_check(&game);
+    print_session(&game);
+    if let Some(path) = &args.save {
+        match save_file(&game, path) {
+            Ok(()) => println!("saved: {}", path),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+    if args.check_invariants {
+        match &game.step.invariant_error {
+            Some(err) => println!("invariants: broken {}", err),
+            None => println!("invariants: ok"),
+        }
+    }
+
+    let mut timers: Vec<(String, (Duration, usize, usize))> = timers.into_iter().collect();
+    timers.sort_unstable_by(|a, b| { (a.1).2.cmp(&(b.1).2) });
+    println!("benchmark (average per tick):");
+    for (name, (duration, level, _)) in timers {
+        let average = duration.as_secs_f64() * 1_000.0 / ticks.max(1) as f64;
+        println!("{}{} {:.3} ms", "  ".repeat(level + 1), name, average);
+    }
+}
@@ -2,6 +2,7 @@ pub struct DebugSettings {
     pub draw_matrix_simple: bool,
     pub draw_matrix_physics: bool,
     pub draw_color_pression: bool,
+    pub check_invariants: bool, // Errors in GameStep::invariant_error
 }
 
 impl DebugSettings {
@@ -10,6 +11,7 @@ impl DebugSettings {
             draw_matrix_simple: false,
             draw_matrix_physics: false,
             draw_color_pression: false,
+            check_invariants: false,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+// Computer-controlled players (PlayerKind::Bot), moved each tick by BotSolver.
+// Bots split and throw with the same code as the local player, with their own PlayerSettings.
+
+use crate::game::Game;
+use crate::game::entity::{EntityInfo, EntityTimer, RATIO_MASS};
+use crate::game::player::{PlayerInfo, PlayerKind};
+use crate::game::rng::RngStream;
+
+use euclid::default::{Point2D, Vector2D};
+use rand::Rng;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+pub const DIFFICULTIES: [&str; 3] = ["easy", "normal", "hard"];
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BotSettings {
+    pub reaction: u64, // Ticks between two decisions
+    pub view: i32, // Distance seen around the biggest cell, added to its radius
+    pub aim: i32, // Random error on the position targeted, on each axis
+    pub chase: bool, // Chase the smaller cells of the other players
+    pub flee: bool, // Flee the bigger cells
+    pub split_chance: f32, // At each decision, with a prey in reach
+    pub throw_chance: f32, // At each decision, while fleeing: mass thrown at the threat to be lighter
+    pub respawn: Option<i64>, // Mass of the new cell when the bot has no cell anymore, None: stays dead
+}
+
+impl Default for BotSettings {
+    fn default() -> BotSettings {
+        BotSettings {
+            reaction: 10,
+            view: 40_000,
+            aim: 2_000,
+            chase: true,
+            flee: true,
+            split_chance: 0.2,
+            throw_chance: 0.05,
+            respawn: Some(RATIO_MASS * 10),
+        }
+    }
+}
+
+impl BotSettings {
+    // Presets of DIFFICULTIES
+    pub fn difficulty(name: &str) -> Option<BotSettings> {
+        match name {
+            "easy" => Some(BotSettings {
+                reaction: 30,
+                view: 20_000,
+                aim: 8_000,
+                chase: false,
+                split_chance: 0.0,
+                throw_chance: 0.0,
+                ..Default::default()
+            }),
+            "normal" => Some(BotSettings::default()),
+            "hard" => Some(BotSettings {
+                reaction: 3,
+                view: 80_000,
+                aim: 0,
+                split_chance: 0.5,
+                throw_chance: 0.1,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&mut self) {
+        self.reaction = self.reaction.max(1);
+        self.view = self.view.max(0);
+        self.aim = self.aim.max(0);
+        self.split_chance = self.split_chance.max(0.0).min(1.0);
+        self.throw_chance = self.throw_chance.max(0.0).min(1.0);
+    }
+}
+
+// Adds `amount` bot players of `team`, each with one cell of `mass` at a random position.
+// Cells have the characteristics of the local player, colors (unless the team has one) and skins go through the default ones
+pub fn add_bots(game: &mut Game, amount: usize, settings: &BotSettings, mass: i64, team: Option<usize>) {
+    let mut rng = game.rng.stream(RngStream::Map, 2 + game.players.len() as u64); // Other positions for each call
+    let size = game.map.size * game.map.size_field;
+    for i in 0..amount {
+        let skin = 1 + i % 13;
+        let player = game.players.len();
+        game.new_player(PlayerInfo {
+            kind: PlayerKind::Bot(settings.clone()),
+            cell_default_color: crate::game::settings::DEFAULT_COLOR[skin],
+            cell_default_texture: game.textures.index(&format!("skin_{}", skin)).unwrap_or(0),
+            team,
+            ..Default::default()
+        });
+        game.new_entity(EntityInfo {
+            player,
+            position: Point2D::new(rng.gen_range(0..size.width), rng.gen_range(0..size.height)),
+            speed: Vector2D::zero(),
+            mass,
+            color: game.players[player].cell_default_color,
+            texture: game.players[player].cell_default_texture,
+            timer: EntityTimer::default(),
+            characteristics: game.settings.local_player_characteristics.clone(),
+        });
+    }
+}
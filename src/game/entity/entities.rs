@@ -102,9 +102,9 @@ pub struct Entities {
     pub lifetime: Vec<i32>,
     pub index_matrix_simple: Vec<MatrixIndex>,
     pub special: Vec<Vec<EntitySpecial>>,
-    buffer: Vec<Buffer<EntityAction>>, // TODO: make it better
+    buffer: Vec<Buffer<(usize, EntityAction)>>, // Index of the entity sending the action, TODO: make it better
     buffer_is_some: Vec<AtomicBool>, // TODO: make it better
-    buffer2: Vec<Buffer<EntityAction>>, // TODO: make it better
+    buffer2: Vec<Buffer<(usize, EntityAction)>>, // TODO: make it better
     buffer2_is_some: Vec<AtomicBool>, // TODO: make it better
     pub drawable_entities: Vec<DrawableEntity>,
 }
@@ -137,14 +137,7 @@ impl Entities {
         entity_core.index.main = index;
         entity_core.index.main_ptr.store(index, Ordering::Relaxed);
 
-        let position = {
-            let mut position = info.position;
-            if position.x <= 0 { position.x = 0 }
-            if position.x > map.max().width { position.x = map.max().width } // TODO: Not here
-            if position.y <= 0 { position.y = 0 }
-            if position.y > map.max().height { position.y = map.max().height } // TODO: Not here
-            position
-        };
+        let position = map.confine(info.position); // TODO: Not here
 
         let mass = info.mass.min(info.characteristics.mass_max).max(info.characteristics.mass_min);
 
@@ -192,23 +185,23 @@ impl Entities {
         self.buffer2_is_some[entity_index].load(Ordering::Relaxed)
     }
 
-    pub fn receive_buffer(&self, entity_index: usize) -> buffer::BufferIterator<EntityAction> {
+    pub fn receive_buffer(&self, entity_index: usize) -> buffer::BufferIterator<(usize, EntityAction)> {
         self.buffer_is_some[entity_index].store(false, Ordering::Relaxed);
         self.buffer[entity_index].receive()
     }
     
-    pub fn receive_buffer2(&self, entity_index: usize) -> buffer::BufferIterator<EntityAction> {
+    pub fn receive_buffer2(&self, entity_index: usize) -> buffer::BufferIterator<(usize, EntityAction)> {
         self.buffer2_is_some[entity_index].store(false, Ordering::Relaxed);
         self.buffer2[entity_index].receive()
     }
 
-    pub fn send_buffer(&self, entity_index: usize, action: EntityAction) {
-        self.buffer[entity_index].send(action);
+    pub fn send_buffer(&self, sender: usize, entity_index: usize, action: EntityAction) {
+        self.buffer[entity_index].send((sender, action));
         self.buffer_is_some[entity_index].store(true, Ordering::Relaxed);
     }
     
-    pub fn send_buffer2(&self, entity_index: usize, action: EntityAction) {
-        self.buffer2[entity_index].send(action);
+    pub fn send_buffer2(&self, sender: usize, entity_index: usize, action: EntityAction) {
+        self.buffer2[entity_index].send((sender, action));
         self.buffer2_is_some[entity_index].store(true, Ordering::Relaxed);
     }
 
@@ -232,24 +225,14 @@ impl Entities {
         let entity_mass_evolution = &mut self.mass_evolution[entity_index];
         let entity_drawable = &mut self.drawable_entities[entity_index];
 
-        if entity_core.player != info.player {
-            let game = &mut crate::APP.get_mut().game;
-            game.players[entity_core.player].entities.swap_remove(entity_core.index.player);
-            if game.players[entity_core.player].entities.len() != entity_core.index.player {
-                game.entities.core[game.players[entity_core.player].entities[entity_core.index.player]].index.player = entity_core.index.player;
-            }
-            entity_core.player = info.player;
-            entity_core.index.player = game.players[entity_core.player].entities.len();
-            game.players[entity_core.player].entities.push(entity_core.index.main);
-        }
-
         let mut flags = EntityFlags::empty();
         if info.characteristics.gravity.is_some() { flags.insert(EntityFlags::GRAVITY) }
         if info.characteristics.throw_entity.is_some() { flags.insert(EntityFlags::THROW) }
         if info.characteristics.killer { flags.insert(EntityFlags::EATER) }
-        if info.characteristics.collide { flags.insert(EntityFlags::COLLIDE) } if !info.characteristics.collide && entity_flags.contains(EntityFlags::COLLIDE) { crate::APP.get_mut().game.map.matrix_physics.delete_entity_multithread(&crate::APP.get().game.entities, entity_core); }
+        if info.characteristics.collide { flags.insert(EntityFlags::COLLIDE) }
         if info.characteristics.affected_by_gravity { flags.insert(EntityFlags::MOVABLE) }
         if info.characteristics.bounce { flags.insert(EntityFlags::BOUNCE) }
+        flags.insert(*entity_flags & EntityFlags::THROWN);
 
         *entity_position = info.position;
         *entity_speed = info.speed;
@@ -19,7 +19,7 @@ use serde::{Deserialize, Serialize};
 pub const RATIO_MASS: i64 = 1_000_000;
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub struct EntityColor {
     pub center: [u8;4],
     pub edge: [u8;4]
@@ -35,7 +35,8 @@ impl Default for EntityColor {
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone, Default)]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, Default, PartialEq)]
 pub struct EntityInfo {
     pub player: usize,
     pub position: Point2D<i32>,
@@ -51,8 +52,7 @@ impl EntityInfo {
     pub fn validate(&mut self, game: &Game) {
         if self.player >= game.players.len() { self.player = game.players.len() - 1; }
 
-        self.position.x = self.position.x.max(0).min(game.map.max().width);
-        self.position.y = self.position.y.max(0).min(game.map.max().height);
+        self.position = game.map.confine(self.position);
 
         self.characteristics.validate();
         self.mass = self.mass.max(self.characteristics.mass_min).min(self.characteristics.mass_max);
@@ -88,12 +88,14 @@ bitflags::bitflags! {
         const BOUNCE = 0b0000_0000_0000_0000_0000_0000_1000_0000; //TODO: update when changed
 
         const MATRIX_SIMPLE_TO_CHANGE = 0b0000_0000_0000_0000_0000_0001_0000_0000; //TODO: update when changed
+        const THROWN = 0b0000_0000_0000_0000_0000_0010_0000_0000; // Thrown by a cell, only these feed a virus
     }
 }
 
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, PartialEq)]
 pub struct ThrowEntityInfo {
     pub mass_minimum_to_throw: i64,
     pub mass_self_added: i64,
@@ -116,8 +118,16 @@ impl ThrowEntityInfo {
         self.power.end = self.power.end.max(self.power.start + 1);
 
         self.color.validate();
+        self.texture.validate();
         self.characteristics_entity_thrown.validate();
     }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        self.texture.map_textures(f);
+        if let ThrownEntityCharacteristics::Custom(characteristics) = &mut self.characteristics_entity_thrown {
+            characteristics.map_textures(f);
+        }
+    }
 }
 
 impl Default for ThrowEntityInfo {
@@ -138,7 +148,7 @@ impl Default for ThrowEntityInfo {
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum ThrownEntityCharacteristics {
     Same,
     Custom(Box<EntityCharacteristics>),
@@ -154,15 +164,39 @@ impl ThrownEntityCharacteristics {
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum ThrownEntityTexture {
     Same,
     CustomIndex(usize),
     Random(Vec<usize>),
 }
 
+impl ThrownEntityTexture {
+    pub fn validate(&mut self) {
+        if let ThrownEntityTexture::Random(vec) = self {
+            if vec.len() == 0 { vec.push(0); }
+        }
+    }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        match self {
+            ThrownEntityTexture::Same => {}
+            ThrownEntityTexture::CustomIndex(index) => *index = f(*index),
+            ThrownEntityTexture::Random(vec) => vec.iter_mut().for_each(|index| *index = f(*index)),
+        }
+    }
+
+    pub fn pick(&self, same: usize, rng: &mut impl rand::Rng) -> usize {
+        match self {
+            ThrownEntityTexture::Same => same,
+            ThrownEntityTexture::CustomIndex(index) => *index,
+            ThrownEntityTexture::Random(vec) => vec[rng.gen_range(0..vec.len())],
+        }
+    }
+}
+
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum ThrownEntityColor {
     Same,
     Custom(EntityColor),
@@ -175,23 +209,218 @@ impl ThrownEntityColor {
             if vec.len() == 0 { vec.push(EntityColor::default()); }
         }
     }
+
+    pub fn pick(&self, same: EntityColor, rng: &mut impl rand::Rng) -> EntityColor {
+        match self {
+            ThrownEntityColor::Same => same,
+            ThrownEntityColor::Custom(color) => *color,
+            ThrownEntityColor::Random(vec) => vec[rng.gen_range(0..vec.len())],
+        }
+    }
 }
 
+// Applied when the entity is eaten, or at the end of its lifetime_left (without killer)
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum OnDeathEffect {
-    Split(usize),
+    Split(usize), // Split of the killer, if it can_split_on_kill
+    Spawn(SpawnOnDeathInfo),
+    Explode(ExplodeOnDeathInfo),
+    TransferMass(TransferMassOnDeathInfo),
+    Respawn(RespawnOnDeathInfo),
+    Multiple(Vec<OnDeathEffect>),
+}
+
+impl OnDeathEffect {
+    pub fn validate(&mut self) {
+        match self {
+            OnDeathEffect::Split(_) => {}
+            OnDeathEffect::Spawn(info) => {
+                for template in info.entities.iter_mut() {
+                    template.characteristics.validate();
+                    template.mass = template.mass.max(template.characteristics.mass_min).min(template.characteristics.mass_max);
+                }
+            }
+            OnDeathEffect::Explode(info) => {
+                info.mass_ratio = info.mass_ratio.max(0.0);
+                info.speed.start = info.speed.start.max(0.0);
+                info.speed.end = info.speed.end.max(info.speed.start + 0.1);
+                info.lifetime_left = info.lifetime_left.max(0);
+                info.color.validate();
+                if info.texture == ThrownEntityTexture::Random(Vec::new()) { info.texture = ThrownEntityTexture::Same; }
+                info.characteristics.validate();
+            }
+            OnDeathEffect::TransferMass(info) => {
+                info.ratio = info.ratio.max(0.0);
+                if let MassTransferTarget::Nearby(radius) = &mut info.target { *radius = (*radius).max(0); }
+            }
+            OnDeathEffect::Respawn(_) => {}
+            OnDeathEffect::Multiple(effects) => effects.iter_mut().for_each(OnDeathEffect::validate),
+        }
+    }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        match self {
+            OnDeathEffect::Spawn(info) => {
+                for template in info.entities.iter_mut() {
+                    template.texture = f(template.texture);
+                    template.characteristics.map_textures(f);
+                }
+            }
+            OnDeathEffect::Explode(info) => {
+                info.texture.map_textures(f);
+                if let ThrownEntityCharacteristics::Custom(characteristics) = &mut info.characteristics {
+                    characteristics.map_textures(f);
+                }
+            }
+            OnDeathEffect::Multiple(effects) => effects.iter_mut().for_each(|effect| effect.map_textures(f)),
+            _ => {}
+        }
+    }
+
+    // What is left for a respawned entity: one life less, None when nothing is left
+    pub fn respawned(&self) -> Option<OnDeathEffect> {
+        match self {
+            OnDeathEffect::Respawn(info) => match info.lives {
+                Some(lives) if lives <= 1 => None,
+                Some(lives) => Some(OnDeathEffect::Respawn(RespawnOnDeathInfo {
+                    lives: Some(lives - 1),
+                    ..info.clone()
+                })),
+                None => Some(self.clone()),
+            },
+            OnDeathEffect::Multiple(effects) => {
+                let effects: Vec<OnDeathEffect> = effects.iter().filter_map(OnDeathEffect::respawned).collect();
+                if effects.is_empty() { None } else { Some(OnDeathEffect::Multiple(effects)) }
+            }
+            _ => Some(self.clone()),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, PartialEq)]
+pub enum DeathSpeed {
+    Radial(f32), // Away from the dead entity, spread evenly around it
+    Directional(f32, f32), // Angle in degrees, speed
+    Same, // Speed of the dead entity
+}
+
+// Loot: the position of the templates is relative to the dead entity, their speed is added to DeathSpeed
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, PartialEq)]
+pub struct SpawnOnDeathInfo {
+    pub entities: Vec<EntityInfo>,
+    pub amount: usize, // Copies of the templates
+    pub speed: DeathSpeed,
+}
+
+impl Default for SpawnOnDeathInfo {
+    fn default() -> SpawnOnDeathInfo {
+        SpawnOnDeathInfo {
+            entities: Vec::new(),
+            amount: 1,
+            speed: DeathSpeed::Radial(1_000.0),
+        }
+    }
+}
+
+// Particles of the player of the dead entity thrown all around, they die at the end of their lifetime_left
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, PartialEq)]
+pub struct ExplodeOnDeathInfo {
+    pub particles: usize,
+    pub mass_ratio: f32, // Part of the mass of the dead entity shared by the particles
+    pub speed: Range<f32>,
+    pub lifetime_left: i32,
+    pub color: ThrownEntityColor,
+    pub texture: ThrownEntityTexture,
+    pub characteristics: ThrownEntityCharacteristics, // Same: without the on_death of the dead entity
+}
+
+impl Default for ExplodeOnDeathInfo {
+    fn default() -> ExplodeOnDeathInfo {
+        ExplodeOnDeathInfo {
+            particles: 32,
+            mass_ratio: 0.5,
+            speed: 1_000.0..3_000.0,
+            lifetime_left: 100,
+            color: ThrownEntityColor::Same,
+            texture: ThrownEntityTexture::Same,
+            characteristics: ThrownEntityCharacteristics::Custom(Box::new(EntityCharacteristics::default())),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, PartialEq)]
+pub enum MassTransferTarget {
+    Killer,
+    Nearby(i32), // Cells of the players (not neutral) in this radius, sharing it equally
+}
+
+// Given on top of the mass eaten by the killer
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, PartialEq)]
+pub struct TransferMassOnDeathInfo {
+    pub ratio: f32, // Of the mass of the dead entity
+    pub target: MassTransferTarget,
+}
+
+impl Default for TransferMassOnDeathInfo {
+    fn default() -> TransferMassOnDeathInfo {
+        TransferMassOnDeathInfo {
+            ratio: 0.5,
+            target: MassTransferTarget::Killer,
+        }
+    }
 }
 
+// Same entity back after the delay, with the same characteristics
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, PartialEq)]
+pub struct RespawnOnDeathInfo {
+    pub delay: u64, // Ticks
+    pub position: Option<Point2D<i32>>, // None: where it died
+    pub mass: Option<i64>, // None: its mass when it died
+    pub timer: EntityTimer,
+    pub lives: Option<usize>, // Respawns left, None: endless
+}
+
+impl Default for RespawnOnDeathInfo {
+    fn default() -> RespawnOnDeathInfo {
+        RespawnOnDeathInfo {
+            delay: 500,
+            position: None,
+            mass: None,
+            timer: EntityTimer::default(),
+            lives: None,
+        }
+    }
+}
+
+// Entity waiting for its OnDeathEffect::Respawn
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
 #[derive(Clone)]
+pub struct PendingRespawn {
+    pub tick: u64,
+    pub info: EntityInfo,
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, PartialEq)]
 pub enum DistanceRatio {
     Linear,
     Squared,
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, PartialEq)]
 pub struct EntityGravityInfo {
     pub power: f32,
     pub distance_ratio: DistanceRatio,
@@ -228,8 +457,45 @@ impl Default for EntityGravityInfo {
     }
 }
 
+// Eaten only by cells big enough to eat it (Settings::eat_ratio), which are split in pieces.
+// Grows with the mass thrown into it and fires half of itself in the direction of the last feed
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, PartialEq)]
+pub struct VirusInfo {
+    pub pieces: usize, // Split of the eater, up to the max_split of its player
+    pub feed_mass_max: i64, // Moving cells of the same player (thrown food) up to this mass feed it, other cells are never eaten
+    pub fire_mass: i64, // Mass at which a new virus is fired
+    pub fire_speed: f32,
+}
+
+impl Default for VirusInfo {
+    fn default() -> VirusInfo {
+        VirusInfo {
+            pieces: 16,
+            feed_mass_max: RATIO_MASS * 10,
+            fire_mass: RATIO_MASS * 200,
+            fire_speed: 3_000.0,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum EdgePolicy {
+    Map, // Bounce, stop or wrap depending on the topology of the map and bounce
+    Despawn, // Killed when going out of the map, its on_death effect applies
+}
+
+impl Default for EdgePolicy {
+    fn default() -> Self {
+        EdgePolicy::Map
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))] // Fields not given in map definitions are the default ones
+#[derive(Clone, PartialEq)]
 pub struct EntityCharacteristics {
     pub killer: bool,
     pub collide: bool,
@@ -237,6 +503,7 @@ pub struct EntityCharacteristics {
     pub mergeable: bool,
     pub affected_by_gravity: bool, //TODO: implement it better
     pub bounce: bool,
+    pub edge: EdgePolicy,
     pub can_split_on_kill: bool,
     pub invincible: bool,
     pub inertia: i32, //TODO: Better
@@ -246,6 +513,7 @@ pub struct EntityCharacteristics {
     pub on_death: Option<OnDeathEffect>,
     pub gravity: Option<EntityGravityInfo>, //TODO: Better
     pub throw_entity: Option<ThrowEntityInfo>,
+    pub virus: Option<VirusInfo>,
     pub special: Vec<EntitySpecial>,
 }
 
@@ -264,6 +532,24 @@ impl EntityCharacteristics {
         if let Some(info) = self.throw_entity.as_mut() {
             info.validate();
         }
+        if let Some(on_death) = self.on_death.as_mut() {
+            on_death.validate();
+        }
+        if let Some(virus) = self.virus.as_mut() {
+            virus.pieces = virus.pieces.max(1);
+            virus.fire_mass = virus.fire_mass.max(self.mass_min * 2).min(self.mass_max);
+            virus.fire_speed = virus.fire_speed.max(0.0);
+        }
+    }
+
+    // Calls f on every texture index used by the entities thrown or spawned on death
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        if let Some(info) = self.throw_entity.as_mut() {
+            info.map_textures(f);
+        }
+        if let Some(on_death) = self.on_death.as_mut() {
+            on_death.map_textures(f);
+        }
     }
 }
 
@@ -277,6 +563,7 @@ impl Default for EntityCharacteristics {
             gravity: None,
             affected_by_gravity: true,
             bounce: true,
+            edge: EdgePolicy::Map,
             invincible: false,
             mass_evolution: None,
             mass_min: 1_000_000,
@@ -285,6 +572,7 @@ impl Default for EntityCharacteristics {
             can_split_on_kill: false,
             on_death: None,
             throw_entity: None,
+            virus: None,
             special: Vec::new(),
             //special: vec![EntitySpecial::WASM(String::new())],
         }
@@ -292,7 +580,8 @@ impl Default for EntityCharacteristics {
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone, Default)]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, Default, PartialEq)]
 pub struct EntityTimer {
     pub collision: Option<i32>,
     pub collision_ratio: Option<i32>,
@@ -331,6 +620,12 @@ impl EntityCollidingInfo {
     pub fn entities_colliding(&self) -> MutexGuard<FxHashSet<usize>> {
         self.buffer_colliding_solver.lock()
     }
+
+    pub fn entities_colliding_sorted(&self) -> Vec<usize> {
+        let mut entities: Vec<usize> = self.buffer_colliding_solver.lock().iter().copied().collect();
+        entities.sort_unstable();
+        entities
+    }
 }
 
 impl Default for EntityCollidingInfo {
@@ -356,6 +651,9 @@ pub struct EntityCore {
     pub index: EntityIndex,
     pub killed: Option<usize>,
     pub origin: Option<std::sync::Weak<usize>>,
+    pub fed: Option<Vector2D<f32>>, // Direction of the last feed of a virus
+    pub spawn_rule: Option<usize>, // Rule of Settings::auto_spawn that added it, counted in Game::spawned
+    pub added: (u64, usize), // Tick, and entities added by this one during it (AddEntityKey::sequence)
 }
 
 impl EntityCore {
@@ -386,6 +684,9 @@ impl EntityCore {
             index,
             killed,
             origin: None,
+            fed: None,
+            spawn_rule: None,
+            added: (0, 0),
         }
     }
 }
@@ -404,10 +705,36 @@ pub enum EntityAction {
     MulSpeed(f32, f32),
     SetColor(EntityColor),
     Split(usize),
+    Fed(f32, f32), // Direction of the feed of a virus
+}
+
+impl EntityAction {
+    // Total order on the actions of one sender, used to apply a buffer the same way whatever thread sent what first.
+    // Signed values keep their order (sign bit flipped), floats are ordered by their bits.
+    pub fn canonical_key(&self) -> (u8, u64, u64) {
+        use EntityAction::*;
+        let signed = |value: i64| (value as u64) ^ (1 << 63);
+        match *self {
+            AddCollisionRatioTime(time) => (0, signed(time as i64), 0),
+            AddCollisionTime(time) => (1, signed(time as i64), 0),
+            AddPosition(x, y) => (2, signed(x as i64), signed(y as i64)),
+            AddMass(mass) => (3, signed(mass), 0),
+            AddMergeableTime(time) => (4, signed(time as i64), 0),
+            AddInertiaTime(time) => (5, signed(time as i64), 0),
+            AddLifetimeLeftTime(time) => (6, signed(time as i64), 0),
+            AddSpeed(x, y) => (7, x.to_bits() as u64, y.to_bits() as u64),
+            Killed(killer_index) => (8, killer_index as u64, 0),
+            KilledConfirmed(killer_index) => (9, killer_index as u64, 0),
+            MulSpeed(x, y) => (10, x.to_bits() as u64, y.to_bits() as u64),
+            SetColor(color) => (11, u32::from_le_bytes(color.center) as u64, u32::from_le_bytes(color.edge) as u64),
+            Split(count) => (12, count as u64, 0),
+            Fed(x, y) => (13, x.to_bits() as u64, y.to_bits() as u64),
+        }
+    }
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum EntitySpecial {
     WASM(String)
 }
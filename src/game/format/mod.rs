@@ -0,0 +1,214 @@
+// Versioned map files (.binmap and .ronmap).
+//
+// Binary: MAGIC, version (u32 little endian), then bincode of GameSerialize.
+// RON: (version: N, map: GameSerialize).
+// Files without header are version 0.
+// Version 2 adds the texture table (GameSerialize::textures), resolved by GameSerialize::resolve_textures.
+// Version 3 turns Settings::auto_spawn into a list of spawn rules.
+// Version 4 moves the speed, split and throw settings to each player (PlayerInfo::settings).
+// Version 5 adds the teams (Settings::teams, PlayerInfo::team) and Settings::eat_ratio.
+// Version 6 adds the viruses (EntityCharacteristics::virus).
+// Version 7 adds the walls (MapInfo::walls) and Settings::wall_color.
+// Version 8 adds the map topology (MapInfo::topology) and EntityCharacteristics::edge.
+//
+// When a struct saved inside GameSerialize changes:
+// - copy the structs as they were in a new frozen module vN.rs, with From impls to the structs of the game,
+// - make the From impls of the previous version target vN instead,
+// - bump VERSION and read vN in read_binary and read_ron (with an upgrade_vN chaining the From impls),
+// - save a map of the old version in maps/legacy (checked by tests/legacy_maps.rs and `cells-sim --check-maps maps/legacy`).
+// A variant added at the end of an enum keeps the old files readable, it doesn't need a new version.
+//
+// Save files of a game in progress (.binsave): SNAPSHOT_MAGIC, version (u32 little endian), then bincode of WorldSnapshot.
+// They aren't upgraded, bump SNAPSHOT_VERSION when WorldSnapshot changes: other versions are rejected.
+//
+// Replay files (.binreplay): REPLAY_MAGIC, version (u32 little endian), then bincode of Replay.
+// Files without header are version 1. Not upgraded either, bump REPLAY_VERSION when Replay or WorldSnapshot changes.
+// Version 2 keeps the ticks without input (Replay::inputs is a list of Option).
+
+pub mod v0;
+pub mod v2;
+pub mod v3;
+pub mod v4;
+pub mod v5;
+pub mod v6;
+pub mod v7;
+
+use crate::game::GameSerialize;
+use crate::game::replay::Replay;
+use crate::game::rewind::WorldSnapshot;
+
+use serde::{Deserialize, Serialize};
+
+pub const VERSION: u32 = 8;
+const MAGIC: &[u8; 8] = b"CELLSMAP";
+pub const SNAPSHOT_VERSION: u32 = 3;
+const SNAPSHOT_MAGIC: &[u8; 8] = b"CELLSAVE";
+pub const REPLAY_VERSION: u32 = 2;
+const REPLAY_MAGIC: &[u8; 8] = b"CELLREPL";
+
+#[derive(Serialize)]
+struct MapFileRef<'a> {
+    version: u32,
+    map: &'a GameSerialize,
+}
+
+#[derive(Deserialize)]
+struct MapFile<T> {
+    map: T, // version already read by ron_version
+}
+
+
+pub fn write_binary(map: &GameSerialize) -> Result<Vec<u8>, String> {
+    let mut data = MAGIC.to_vec();
+    data.extend_from_slice(&VERSION.to_le_bytes());
+    bincode::serialize_into(&mut data, map).map_err(|err| format!("can't serialize map: {}", err))?;
+    Ok(data)
+}
+
+pub fn write_ron(map: &GameSerialize) -> Result<Vec<u8>, String> {
+    let file = MapFileRef {
+        version: VERSION,
+        map,
+    };
+    let text = ron::ser::to_string_pretty(&file, Default::default()).map_err(|err| format!("can't serialize map: {}", err))?;
+    Ok(text.into_bytes())
+}
+
+// Returns the map upgraded to the last version, and the version of the file
+pub fn read_binary(data: &[u8]) -> Result<(GameSerialize, u32), String> {
+    if !data.starts_with(MAGIC) {
+        let old = match bincode::deserialize::<v0::GameSerialize>(data) {
+            Ok(old) => old,
+            Err(_) => bincode::deserialize::<v0::GameSerializeWithoutRng>(data).map_err(|err| format!("version 0: {}", err))?.into(),
+        };
+        return Ok((upgrade_v0(old), 0))
+    }
+    if data.len() < MAGIC.len() + 4 { return Err("truncated header".to_owned()) }
+    let mut version = [0; 4];
+    version.copy_from_slice(&data[MAGIC.len()..MAGIC.len() + 4]);
+    let version = u32::from_le_bytes(version);
+    let payload = &data[MAGIC.len() + 4..];
+    let map = match version {
+        1 => upgrade_v0(bincode::deserialize::<v0::GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?),
+        2 => upgrade_v2(bincode::deserialize::<v2::GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?),
+        3 => upgrade_v3(bincode::deserialize::<v3::GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?),
+        4 => upgrade_v4(bincode::deserialize::<v4::GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?),
+        5 => upgrade_v5(bincode::deserialize::<v5::GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?),
+        6 => upgrade_v6(bincode::deserialize::<v6::GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?),
+        7 => upgrade_v7(bincode::deserialize::<v7::GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?),
+        8 => bincode::deserialize::<GameSerialize>(payload).map_err(|err| format!("version {}: {}", version, err))?,
+        _ => return Err(unknown_version(version)),
+    };
+    Ok((map, version))
+}
+
+pub fn read_ron(data: &[u8]) -> Result<(GameSerialize, u32), String> {
+    let version = ron_version(data)?;
+    let map = match version {
+        0 => upgrade_v0(ron::de::from_bytes::<v0::GameSerialize>(data).map_err(|err| format!("version 0: {}", err))?),
+        1 => upgrade_v0(ron::de::from_bytes::<MapFile<v0::GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map),
+        2 => upgrade_v2(ron::de::from_bytes::<MapFile<v2::GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map),
+        3 => upgrade_v3(ron::de::from_bytes::<MapFile<v3::GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map),
+        4 => upgrade_v4(ron::de::from_bytes::<MapFile<v4::GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map),
+        5 => upgrade_v5(ron::de::from_bytes::<MapFile<v5::GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map),
+        6 => upgrade_v6(ron::de::from_bytes::<MapFile<v6::GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map),
+        7 => upgrade_v7(ron::de::from_bytes::<MapFile<v7::GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map),
+        8 => ron::de::from_bytes::<MapFile<GameSerialize>>(data).map_err(|err| format!("version {}: {}", version, err))?.map,
+        _ => return Err(unknown_version(version)),
+    };
+    Ok((map, version))
+}
+
+pub fn write_snapshot(snapshot: &WorldSnapshot) -> Result<Vec<u8>, String> {
+    let mut data = SNAPSHOT_MAGIC.to_vec();
+    data.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut data, snapshot).map_err(|err| format!("can't serialize snapshot: {}", err))?;
+    Ok(data)
+}
+
+pub fn read_snapshot(data: &[u8]) -> Result<WorldSnapshot, String> {
+    if !data.starts_with(SNAPSHOT_MAGIC) { return Err("not a save file, or saved before the save files had a version".to_owned()) }
+    if data.len() < SNAPSHOT_MAGIC.len() + 4 { return Err("truncated header".to_owned()) }
+    let mut version = [0; 4];
+    version.copy_from_slice(&data[SNAPSHOT_MAGIC.len()..SNAPSHOT_MAGIC.len() + 4]);
+    let version = u32::from_le_bytes(version);
+    if version > SNAPSHOT_VERSION {
+        return Err(format!("save version {} is newer than this game (version {})", version, SNAPSHOT_VERSION))
+    } else if version != SNAPSHOT_VERSION {
+        return Err(format!("save version {} isn't supported (version {}), only maps are upgraded", version, SNAPSHOT_VERSION))
+    }
+    bincode::deserialize(&data[SNAPSHOT_MAGIC.len() + 4..]).map_err(|err| format!("version {}: {}", version, err))
+}
+
+pub fn write_replay(replay: &Replay) -> Result<Vec<u8>, String> {
+    let mut data = REPLAY_MAGIC.to_vec();
+    data.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut data, replay).map_err(|err| format!("can't serialize replay: {}", err))?;
+    Ok(data)
+}
+
+pub fn read_replay(data: &[u8]) -> Result<Replay, String> {
+    if !data.starts_with(REPLAY_MAGIC) { return Err(format!("replay version 1 isn't supported (version {}), record it again", REPLAY_VERSION)) }
+    if data.len() < REPLAY_MAGIC.len() + 4 { return Err("truncated header".to_owned()) }
+    let mut version = [0; 4];
+    version.copy_from_slice(&data[REPLAY_MAGIC.len()..REPLAY_MAGIC.len() + 4]);
+    let version = u32::from_le_bytes(version);
+    if version > REPLAY_VERSION {
+        return Err(format!("replay version {} is newer than this game (version {})", version, REPLAY_VERSION))
+    } else if version != REPLAY_VERSION {
+        return Err(format!("replay version {} isn't supported (version {}), record it again", version, REPLAY_VERSION))
+    }
+    bincode::deserialize(&data[REPLAY_MAGIC.len() + 4..]).map_err(|err| format!("version {}: {}", version, err))
+}
+
+// Each version is upgraded to the next one until the last
+fn upgrade_v0(old: v0::GameSerialize) -> GameSerialize {
+    upgrade_v2(old.into())
+}
+
+fn upgrade_v2(old: v2::GameSerialize) -> GameSerialize {
+    upgrade_v3(old.into())
+}
+
+fn upgrade_v3(old: v3::GameSerialize) -> GameSerialize {
+    upgrade_v4(old.into())
+}
+
+fn upgrade_v4(old: v4::GameSerialize) -> GameSerialize {
+    upgrade_v5(old.into())
+}
+
+fn upgrade_v5(old: v5::GameSerialize) -> GameSerialize {
+    upgrade_v6(old.into())
+}
+
+fn upgrade_v6(old: v6::GameSerialize) -> GameSerialize {
+    upgrade_v7(old.into())
+}
+
+fn upgrade_v7(old: v7::GameSerialize) -> GameSerialize {
+    old.into()
+}
+
+// Version files start with `(version: N,`, old ones directly with the fields of GameSerialize
+fn ron_version(data: &[u8]) -> Result<u32, String> {
+    let mut text = std::str::from_utf8(data).map_err(|err| err.to_string())?.trim_start();
+    while text.starts_with("//") {
+        text = text.find('\n').map_or("", |end| &text[end..]).trim_start();
+    }
+    let rest = match text.strip_prefix('(').map(str::trim_start).and_then(|rest| rest.strip_prefix("version")) {
+        Some(rest) => rest.trim_start(),
+        None => return Ok(0),
+    };
+    let rest = rest.strip_prefix(':').ok_or("expected `:` after version")?.trim_start();
+    let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    rest[..digits].parse().map_err(|_| "invalid version".to_owned())
+}
+
+fn unknown_version(version: u32) -> String {
+    if version > VERSION {
+        format!("version {} is newer than this game (version {})", version, VERSION)
+    } else {
+        format!("unknown version {}", version)
+    }
+}
@@ -0,0 +1,474 @@
+// Version 0: maps saved before the version header, as plain bincode/RON of GameSerialize.
+// Version 1: same structs after the header.
+// Texture indices are the ones of the default textures (no texture table).
+// Frozen copy of the structs of that time, don't change it.
+
+use super::v2;
+use super::v3;
+use super::v5;
+use super::v6;
+use super::v7;
+
+use euclid::default::{Point2D, Size2D, Vector2D};
+use serde::Deserialize;
+use std::ops::{Range, RangeInclusive};
+
+#[derive(Deserialize)]
+pub struct GameSerialize {
+    pub settings: Settings,
+    pub players_info: Vec<PlayerInfo>,
+    pub entities_info: Vec<EntityInfo>,
+    pub entities_characteristics: Vec<EntityCharacteristics>,
+    pub map_info: MapInfo,
+    #[serde(default)]
+    pub rng: GameRng,
+}
+
+// Binary maps saved before the rng was added. RON ones are read by GameSerialize, with a random seed
+#[derive(Deserialize)]
+pub struct GameSerializeWithoutRng {
+    pub settings: Settings,
+    pub players_info: Vec<PlayerInfo>,
+    pub entities_info: Vec<EntityInfo>,
+    pub entities_characteristics: Vec<EntityCharacteristics>,
+    pub map_info: MapInfo,
+}
+
+impl From<GameSerializeWithoutRng> for GameSerialize {
+    fn from(old: GameSerializeWithoutRng) -> GameSerialize {
+        GameSerialize {
+            settings: old.settings,
+            players_info: old.players_info,
+            entities_info: old.entities_info,
+            entities_characteristics: old.entities_characteristics,
+            map_info: old.map_info,
+            rng: GameRng::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub background_color: [f32;4],
+    pub matrix_color: [f32;4],
+    pub local_player: usize,
+    pub local_player_characteristics: EntityCharacteristics,
+    pub local_player_food_settings: ThrownFoodInfo,
+    pub unit_speed: f32,
+    pub unit_speed_split: f32,
+    pub collision_speed: f32,
+    pub max_split: usize,
+    pub max_cells_spawn: usize,
+    pub auto_spawn: AutoSpawnSettings,
+    pub camera_initial: f32,
+    pub max_camera: f32,
+    pub special: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ThrownFoodInfo {
+    pub mass_minimum_to_throw: i64,
+    pub mass_self_added: i64,
+    pub mass_entity_thrown: i64,
+    pub throw_ratio: i32,
+    pub power: RangeInclusive<i32>,
+    pub angle: RangeInclusive<f32>,
+    pub color: ThrownEntityColor,
+    pub texture: ThrownEntityTexture,
+    pub timer: EntityTimer,
+    pub characteristics_entity_thrown: EntityCharacteristics,
+}
+
+#[derive(Deserialize)]
+pub struct AutoSpawnSettings {
+    pub amount: usize,
+    pub position: SpawnPosition,
+    pub mass: AutoSpawnMass,
+    pub color: AutoSpawnEntityColor,
+    pub texture: AutoSpawnEntityTexture,
+    pub timer: EntityTimer,
+    pub characteristics: EntityCharacteristics,
+}
+
+#[derive(Deserialize)]
+pub enum SpawnPosition {
+    Exact(i32, i32),
+    Random,
+    RandomRange(Range<i32>, Range<i32>),
+}
+
+#[derive(Deserialize)]
+pub enum AutoSpawnMass {
+    Exact(i64),
+    Random(Vec<i64>),
+    RandomRange(Range<i64>),
+}
+
+#[derive(Deserialize)]
+pub enum AutoSpawnEntityTexture {
+    CustomIndex(usize),
+    Random(Vec<usize>),
+}
+
+#[derive(Deserialize)]
+pub enum AutoSpawnEntityColor {
+    Custom(EntityColor),
+    Random(Vec<EntityColor>),
+}
+
+#[derive(Deserialize)]
+pub struct PlayerInfo {
+    pub kind: PlayerKind,
+    pub entities: Vec<usize>,
+    pub cell_default_color: EntityColor,
+    pub cell_default_texture: usize,
+}
+
+#[derive(Deserialize)]
+pub enum PlayerKind {
+    Player,
+    Neutral,
+}
+
+#[derive(Deserialize)]
+pub struct EntityInfo {
+    pub player: usize,
+    pub position: Point2D<i32>,
+    pub speed: Vector2D<f32>,
+    pub mass: i64,
+    pub color: EntityColor,
+    pub texture: usize,
+    pub timer: EntityTimer,
+    pub characteristics: EntityCharacteristics,
+}
+
+#[derive(Deserialize)]
+pub struct EntityColor {
+    pub center: [u8;4],
+    pub edge: [u8;4],
+}
+
+#[derive(Deserialize)]
+pub struct EntityTimer {
+    pub collision: Option<i32>,
+    pub collision_ratio: Option<i32>,
+    pub mergeable: Option<i32>,
+    pub inertia: Option<i32>,
+    pub lifetime_left: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct EntityCharacteristics {
+    pub killer: bool,
+    pub collide: bool,
+    pub collide_when_mergeable: bool,
+    pub mergeable: bool,
+    pub affected_by_gravity: bool,
+    pub bounce: bool,
+    pub can_split_on_kill: bool,
+    pub invincible: bool,
+    pub inertia: i32,
+    pub mass_min: i64,
+    pub mass_max: i64,
+    pub mass_evolution: Option<f32>,
+    pub on_death: Option<OnDeathEffect>,
+    pub gravity: Option<EntityGravityInfo>,
+    pub throw_entity: Option<ThrowEntityInfo>,
+    pub special: Vec<EntitySpecial>,
+}
+
+#[derive(Deserialize)]
+pub enum OnDeathEffect {
+    Split(usize),
+}
+
+#[derive(Deserialize)]
+pub enum DistanceRatio {
+    Linear,
+    Squared,
+}
+
+#[derive(Deserialize)]
+pub struct EntityGravityInfo {
+    pub power: f32,
+    pub distance_ratio: DistanceRatio,
+    pub distance_limit: Range<f32>,
+    pub distance_clamp: Range<f32>,
+    pub speed_limit: Range<f32>,
+    pub speed_clamp: Range<f32>,
+}
+
+#[derive(Deserialize)]
+pub struct ThrowEntityInfo {
+    pub mass_minimum_to_throw: i64,
+    pub mass_self_added: i64,
+    pub mass_entity_thrown: i64,
+    pub throw_ratio: f32,
+    pub direction: Range<f32>,
+    pub power: Range<i32>,
+    pub color: ThrownEntityColor,
+    pub texture: ThrownEntityTexture,
+    pub timer_entity_thrown: EntityTimer,
+    pub characteristics_entity_thrown: ThrownEntityCharacteristics,
+}
+
+#[derive(Deserialize)]
+pub enum ThrownEntityCharacteristics {
+    Same,
+    Custom(Box<EntityCharacteristics>),
+    CustomIndex(usize),
+}
+
+#[derive(Deserialize)]
+pub enum ThrownEntityTexture {
+    Same,
+    CustomIndex(usize),
+    Random(Vec<usize>),
+}
+
+#[derive(Deserialize)]
+pub enum ThrownEntityColor {
+    Same,
+    Custom(EntityColor),
+    Random(Vec<EntityColor>),
+}
+
+#[derive(Deserialize)]
+pub enum EntitySpecial {
+    WASM(String),
+}
+
+#[derive(Deserialize)]
+pub struct MapInfo {
+    pub size: Size2D<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct GameRng {
+    pub seed: u64,
+    pub tick: u64,
+}
+
+impl Default for GameRng {
+    fn default() -> GameRng {
+        GameRng {
+            seed: rand::random(),
+            tick: 0,
+        }
+    }
+}
+
+// Upgrade to the next versions (v2, v3, v5 and v6, and v7 for the ones unchanged since)
+
+impl From<GameSerialize> for v2::GameSerialize {
+    fn from(old: GameSerialize) -> v2::GameSerialize {
+        v2::GameSerialize {
+            settings: old.settings.into(),
+            players_info: old.players_info.into_iter().map(Into::into).collect(),
+            entities_info: old.entities_info.into_iter().map(Into::into).collect(),
+            entities_characteristics: old.entities_characteristics.into_iter().map(Into::into).collect(),
+            map_info: v6::MapInfo {
+                size: old.map_info.size,
+            },
+            rng: v7::GameRng {
+                seed: old.rng.seed,
+                tick: old.rng.tick,
+            },
+            textures: Vec::new(),
+        }
+    }
+}
+
+impl From<Settings> for v2::Settings {
+    fn from(old: Settings) -> v2::Settings {
+        v2::Settings {
+            background_color: old.background_color,
+            matrix_color: old.matrix_color,
+            local_player: old.local_player,
+            local_player_characteristics: old.local_player_characteristics.into(),
+            local_player_food_settings: old.local_player_food_settings.into(),
+            unit_speed: old.unit_speed,
+            unit_speed_split: old.unit_speed_split,
+            collision_speed: old.collision_speed,
+            max_split: old.max_split,
+            max_cells_spawn: old.max_cells_spawn,
+            auto_spawn: old.auto_spawn.into(),
+            camera_initial: old.camera_initial,
+            max_camera: old.max_camera,
+            special: old.special,
+        }
+    }
+}
+
+impl From<ThrownFoodInfo> for v5::ThrownFoodInfo {
+    fn from(old: ThrownFoodInfo) -> v5::ThrownFoodInfo {
+        v5::ThrownFoodInfo {
+            mass_minimum_to_throw: old.mass_minimum_to_throw,
+            mass_self_added: old.mass_self_added,
+            mass_entity_thrown: old.mass_entity_thrown,
+            throw_ratio: old.throw_ratio,
+            power: old.power,
+            angle: old.angle,
+            color: old.color.into(),
+            texture: old.texture.into(),
+            timer: old.timer.into(),
+            characteristics_entity_thrown: old.characteristics_entity_thrown.into(),
+        }
+    }
+}
+
+impl From<AutoSpawnSettings> for v2::AutoSpawnSettings {
+    fn from(old: AutoSpawnSettings) -> v2::AutoSpawnSettings {
+        v2::AutoSpawnSettings {
+            amount: old.amount,
+            position: match old.position {
+                SpawnPosition::Exact(x, y) => v7::SpawnPosition::Exact(x, y),
+                SpawnPosition::Random => v7::SpawnPosition::Random,
+                SpawnPosition::RandomRange(x, y) => v7::SpawnPosition::RandomRange(x, y),
+            },
+            mass: match old.mass {
+                AutoSpawnMass::Exact(mass) => v7::AutoSpawnMass::Exact(mass),
+                AutoSpawnMass::Random(mass) => v7::AutoSpawnMass::Random(mass),
+                AutoSpawnMass::RandomRange(mass) => v7::AutoSpawnMass::RandomRange(mass),
+            },
+            color: match old.color {
+                AutoSpawnEntityColor::Custom(color) => v7::AutoSpawnEntityColor::Custom(color.into()),
+                AutoSpawnEntityColor::Random(colors) => v7::AutoSpawnEntityColor::Random(colors.into_iter().map(Into::into).collect()),
+            },
+            texture: match old.texture {
+                AutoSpawnEntityTexture::CustomIndex(texture) => v7::AutoSpawnEntityTexture::CustomIndex(texture),
+                AutoSpawnEntityTexture::Random(textures) => v7::AutoSpawnEntityTexture::Random(textures),
+            },
+            timer: old.timer.into(),
+            characteristics: old.characteristics.into(),
+        }
+    }
+}
+
+impl From<PlayerInfo> for v3::PlayerInfo {
+    fn from(old: PlayerInfo) -> v3::PlayerInfo {
+        v3::PlayerInfo {
+            kind: match old.kind {
+                PlayerKind::Player => v7::PlayerKind::Player,
+                PlayerKind::Neutral => v7::PlayerKind::Neutral,
+            },
+            entities: old.entities,
+            cell_default_color: old.cell_default_color.into(),
+            cell_default_texture: old.cell_default_texture,
+        }
+    }
+}
+
+impl From<EntityInfo> for v5::EntityInfo {
+    fn from(old: EntityInfo) -> v5::EntityInfo {
+        v5::EntityInfo {
+            player: old.player,
+            position: old.position,
+            speed: old.speed,
+            mass: old.mass,
+            color: old.color.into(),
+            texture: old.texture,
+            timer: old.timer.into(),
+            characteristics: old.characteristics.into(),
+        }
+    }
+}
+
+impl From<EntityColor> for v7::EntityColor {
+    fn from(old: EntityColor) -> v7::EntityColor {
+        v7::EntityColor {
+            center: old.center,
+            edge: old.edge,
+        }
+    }
+}
+
+impl From<EntityTimer> for v7::EntityTimer {
+    fn from(old: EntityTimer) -> v7::EntityTimer {
+        v7::EntityTimer {
+            collision: old.collision,
+            collision_ratio: old.collision_ratio,
+            mergeable: old.mergeable,
+            inertia: old.inertia,
+            lifetime_left: old.lifetime_left,
+        }
+    }
+}
+
+impl From<EntityCharacteristics> for v5::EntityCharacteristics {
+    fn from(old: EntityCharacteristics) -> v5::EntityCharacteristics {
+        v5::EntityCharacteristics {
+            killer: old.killer,
+            collide: old.collide,
+            collide_when_mergeable: old.collide_when_mergeable,
+            mergeable: old.mergeable,
+            affected_by_gravity: old.affected_by_gravity,
+            bounce: old.bounce,
+            can_split_on_kill: old.can_split_on_kill,
+            invincible: old.invincible,
+            inertia: old.inertia,
+            mass_min: old.mass_min,
+            mass_max: old.mass_max,
+            mass_evolution: old.mass_evolution,
+            on_death: old.on_death.map(|on_death| match on_death {
+                OnDeathEffect::Split(count) => v7::OnDeathEffect::Split(count),
+            }),
+            gravity: old.gravity.map(|gravity| v7::EntityGravityInfo {
+                power: gravity.power,
+                distance_ratio: match gravity.distance_ratio {
+                    DistanceRatio::Linear => v7::DistanceRatio::Linear,
+                    DistanceRatio::Squared => v7::DistanceRatio::Squared,
+                },
+                distance_limit: gravity.distance_limit,
+                distance_clamp: gravity.distance_clamp,
+                speed_limit: gravity.speed_limit,
+                speed_clamp: gravity.speed_clamp,
+            }),
+            throw_entity: old.throw_entity.map(Into::into),
+            special: old.special.into_iter().map(|special| match special {
+                EntitySpecial::WASM(path) => v7::EntitySpecial::WASM(path),
+            }).collect(),
+        }
+    }
+}
+
+impl From<ThrowEntityInfo> for v5::ThrowEntityInfo {
+    fn from(old: ThrowEntityInfo) -> v5::ThrowEntityInfo {
+        v5::ThrowEntityInfo {
+            mass_minimum_to_throw: old.mass_minimum_to_throw,
+            mass_self_added: old.mass_self_added,
+            mass_entity_thrown: old.mass_entity_thrown,
+            throw_ratio: old.throw_ratio,
+            direction: old.direction,
+            power: old.power,
+            color: old.color.into(),
+            texture: old.texture.into(),
+            timer_entity_thrown: old.timer_entity_thrown.into(),
+            characteristics_entity_thrown: match old.characteristics_entity_thrown {
+                ThrownEntityCharacteristics::Same => v5::ThrownEntityCharacteristics::Same,
+                ThrownEntityCharacteristics::Custom(characteristics) => v5::ThrownEntityCharacteristics::Custom(Box::new((*characteristics).into())),
+                ThrownEntityCharacteristics::CustomIndex(index) => v5::ThrownEntityCharacteristics::CustomIndex(index),
+            },
+        }
+    }
+}
+
+impl From<ThrownEntityTexture> for v7::ThrownEntityTexture {
+    fn from(old: ThrownEntityTexture) -> v7::ThrownEntityTexture {
+        match old {
+            ThrownEntityTexture::Same => v7::ThrownEntityTexture::Same,
+            ThrownEntityTexture::CustomIndex(texture) => v7::ThrownEntityTexture::CustomIndex(texture),
+            ThrownEntityTexture::Random(textures) => v7::ThrownEntityTexture::Random(textures),
+        }
+    }
+}
+
+impl From<ThrownEntityColor> for v7::ThrownEntityColor {
+    fn from(old: ThrownEntityColor) -> v7::ThrownEntityColor {
+        match old {
+            ThrownEntityColor::Same => v7::ThrownEntityColor::Same,
+            ThrownEntityColor::Custom(color) => v7::ThrownEntityColor::Custom(color.into()),
+            ThrownEntityColor::Random(colors) => v7::ThrownEntityColor::Random(colors.into_iter().map(Into::into).collect()),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// Version 2: texture table, Settings::auto_spawn is a single rule without every/max/schedule/condition.
+// Frozen copy of the structs changed since, the other ones are the ones of the next versions (all frozen in v7).
+
+use super::v3;
+use super::v5;
+use super::v6;
+use super::v7;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GameSerialize {
+    pub settings: Settings,
+    pub players_info: Vec<v3::PlayerInfo>,
+    pub entities_info: Vec<v5::EntityInfo>,
+    pub entities_characteristics: Vec<v5::EntityCharacteristics>,
+    pub map_info: v6::MapInfo,
+    #[serde(default)]
+    pub rng: v7::GameRng,
+    #[serde(default)]
+    pub textures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub background_color: [f32;4],
+    pub matrix_color: [f32;4],
+    pub local_player: usize,
+    pub local_player_characteristics: v5::EntityCharacteristics,
+    pub local_player_food_settings: v5::ThrownFoodInfo,
+    pub unit_speed: f32,
+    pub unit_speed_split: f32,
+    pub collision_speed: f32,
+    pub max_split: usize,
+    pub max_cells_spawn: usize,
+    pub auto_spawn: AutoSpawnSettings,
+    pub camera_initial: f32,
+    pub max_camera: f32,
+    pub special: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AutoSpawnSettings {
+    pub amount: usize,
+    pub position: v7::SpawnPosition,
+    pub mass: v7::AutoSpawnMass,
+    pub color: v7::AutoSpawnEntityColor,
+    pub texture: v7::AutoSpawnEntityTexture,
+    pub timer: v7::EntityTimer,
+    pub characteristics: v5::EntityCharacteristics,
+}
+
+// Upgrade to the next version (v3, and v5 and v6 for the ones unchanged since)
+
+impl From<GameSerialize> for v3::GameSerialize {
+    fn from(old: GameSerialize) -> v3::GameSerialize {
+        v3::GameSerialize {
+            settings: old.settings.into(),
+            players_info: old.players_info,
+            entities_info: old.entities_info,
+            entities_characteristics: old.entities_characteristics,
+            map_info: old.map_info,
+            rng: old.rng,
+            textures: old.textures,
+        }
+    }
+}
+
+impl From<Settings> for v3::Settings {
+    fn from(old: Settings) -> v3::Settings {
+        v3::Settings {
+            background_color: old.background_color,
+            matrix_color: old.matrix_color,
+            local_player: old.local_player,
+            local_player_characteristics: old.local_player_characteristics,
+            local_player_food_settings: old.local_player_food_settings,
+            unit_speed: old.unit_speed,
+            unit_speed_split: old.unit_speed_split,
+            collision_speed: old.collision_speed,
+            max_split: old.max_split,
+            max_cells_spawn: old.max_cells_spawn,
+            auto_spawn: vec![old.auto_spawn.into()],
+            camera_initial: old.camera_initial,
+            max_camera: old.max_camera,
+            special: old.special,
+        }
+    }
+}
+
+impl From<AutoSpawnSettings> for v5::AutoSpawnSettings {
+    fn from(old: AutoSpawnSettings) -> v5::AutoSpawnSettings {
+        v5::AutoSpawnSettings {
+            amount: old.amount,
+            position: old.position,
+            mass: old.mass,
+            color: old.color,
+            texture: old.texture,
+            timer: old.timer,
+            characteristics: old.characteristics,
+            every: 1,
+            max: None,
+            schedule: None,
+            condition: None,
+        }
+    }
+}
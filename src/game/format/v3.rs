@@ -0,0 +1,94 @@
+// Version 3: spawn rules, the speed, split and throw settings are in Settings for every player.
+// Frozen copy of the structs changed since, the other ones are the ones of the next versions (all frozen in v7).
+
+use super::v4;
+use super::v5;
+use super::v6;
+use super::v7;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GameSerialize {
+    pub settings: Settings,
+    pub players_info: Vec<PlayerInfo>,
+    pub entities_info: Vec<v5::EntityInfo>,
+    pub entities_characteristics: Vec<v5::EntityCharacteristics>,
+    pub map_info: v6::MapInfo,
+    #[serde(default)]
+    pub rng: v7::GameRng,
+    #[serde(default)]
+    pub textures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub background_color: [f32;4],
+    pub matrix_color: [f32;4],
+    pub local_player: usize,
+    pub local_player_characteristics: v5::EntityCharacteristics,
+    pub local_player_food_settings: v5::ThrownFoodInfo,
+    pub unit_speed: f32,
+    pub unit_speed_split: f32,
+    pub collision_speed: f32,
+    pub max_split: usize,
+    pub max_cells_spawn: usize,
+    pub auto_spawn: Vec<v5::AutoSpawnSettings>,
+    pub camera_initial: f32,
+    pub max_camera: f32,
+    pub special: bool,
+}
+
+#[derive(Deserialize)]
+pub struct PlayerInfo {
+    pub kind: v7::PlayerKind,
+    pub entities: Vec<usize>,
+    pub cell_default_color: v7::EntityColor,
+    pub cell_default_texture: usize,
+}
+
+// Upgrade to the next version (v4, and v5 and v6 for the ones unchanged since)
+
+impl From<GameSerialize> for v4::GameSerialize {
+    fn from(old: GameSerialize) -> v4::GameSerialize {
+        // The settings applied to every player
+        let player_settings = v5::PlayerSettings {
+            unit_speed: old.settings.unit_speed,
+            unit_speed_split: old.settings.unit_speed_split,
+            max_split: old.settings.max_split,
+            food: old.settings.local_player_food_settings.clone(),
+        };
+        v4::GameSerialize {
+            settings: old.settings.into(),
+            players_info: old.players_info.into_iter().map(|player| v4::PlayerInfo {
+                kind: player.kind,
+                entities: player.entities,
+                cell_default_color: player.cell_default_color,
+                cell_default_texture: player.cell_default_texture,
+                settings: player_settings.clone(),
+            }).collect(),
+            entities_info: old.entities_info,
+            entities_characteristics: old.entities_characteristics,
+            map_info: old.map_info,
+            rng: old.rng,
+            textures: old.textures,
+        }
+    }
+}
+
+impl From<Settings> for v4::Settings {
+    fn from(old: Settings) -> v4::Settings {
+        v4::Settings {
+            background_color: old.background_color,
+            matrix_color: old.matrix_color,
+            local_player: old.local_player,
+            local_player_characteristics: old.local_player_characteristics,
+            collision_speed: old.collision_speed,
+            max_cells_spawn: old.max_cells_spawn,
+            auto_spawn: old.auto_spawn,
+            camera_initial: old.camera_initial,
+            max_camera: old.max_camera,
+            special: old.special,
+        }
+    }
+}
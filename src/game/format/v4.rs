@@ -0,0 +1,86 @@
+// Version 4: the speed, split and throw settings are in PlayerInfo::settings, no teams and a fixed eat ratio.
+// Frozen copy of the structs changed since, the other ones are the ones of the next versions (all frozen in v7).
+
+use super::v5;
+use super::v6;
+use super::v7;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GameSerialize {
+    pub settings: Settings,
+    pub players_info: Vec<PlayerInfo>,
+    pub entities_info: Vec<v5::EntityInfo>,
+    pub entities_characteristics: Vec<v5::EntityCharacteristics>,
+    pub map_info: v6::MapInfo,
+    #[serde(default)]
+    pub rng: v7::GameRng,
+    #[serde(default)]
+    pub textures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub background_color: [f32;4],
+    pub matrix_color: [f32;4],
+    pub local_player: usize,
+    pub local_player_characteristics: v5::EntityCharacteristics,
+    pub collision_speed: f32,
+    pub max_cells_spawn: usize,
+    pub auto_spawn: Vec<v5::AutoSpawnSettings>,
+    pub camera_initial: f32,
+    pub max_camera: f32,
+    pub special: bool,
+}
+
+#[derive(Deserialize)]
+pub struct PlayerInfo {
+    pub kind: v7::PlayerKind,
+    pub entities: Vec<usize>,
+    pub cell_default_color: v7::EntityColor,
+    pub cell_default_texture: usize,
+    pub settings: v5::PlayerSettings,
+}
+
+// Upgrade to the next version (v5, v6 for the map info, and v7 for the ones unchanged since)
+
+impl From<GameSerialize> for v5::GameSerialize {
+    fn from(old: GameSerialize) -> v5::GameSerialize {
+        v5::GameSerialize {
+            settings: old.settings.into(),
+            players_info: old.players_info.into_iter().map(|player| v5::PlayerInfo {
+                kind: player.kind,
+                entities: player.entities,
+                cell_default_color: player.cell_default_color,
+                cell_default_texture: player.cell_default_texture,
+                settings: player.settings,
+                team: None,
+            }).collect(),
+            entities_info: old.entities_info,
+            entities_characteristics: old.entities_characteristics,
+            map_info: old.map_info,
+            rng: old.rng,
+            textures: old.textures,
+        }
+    }
+}
+
+impl From<Settings> for v5::Settings {
+    fn from(old: Settings) -> v5::Settings {
+        v5::Settings {
+            background_color: old.background_color,
+            matrix_color: old.matrix_color,
+            local_player: old.local_player,
+            local_player_characteristics: old.local_player_characteristics,
+            collision_speed: old.collision_speed,
+            eat_ratio: 1.33,
+            teams: Vec::new(),
+            max_cells_spawn: old.max_cells_spawn,
+            auto_spawn: old.auto_spawn,
+            camera_initial: old.camera_initial,
+            max_camera: old.max_camera,
+            special: old.special,
+        }
+    }
+}
@@ -0,0 +1,292 @@
+// Version 5: teams and eat ratio, no virus in EntityCharacteristics.
+// Frozen copy of the structs changed since, the other ones are the ones of the next versions (all frozen in v7).
+
+use super::v6;
+use super::v7;
+
+use euclid::default::{Point2D, Vector2D};
+use serde::Deserialize;
+use std::ops::{Range, RangeInclusive};
+
+#[derive(Deserialize)]
+pub struct GameSerialize {
+    pub settings: Settings,
+    pub players_info: Vec<PlayerInfo>,
+    pub entities_info: Vec<EntityInfo>,
+    pub entities_characteristics: Vec<EntityCharacteristics>,
+    pub map_info: v6::MapInfo,
+    #[serde(default)]
+    pub rng: v7::GameRng,
+    #[serde(default)]
+    pub textures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub background_color: [f32;4],
+    pub matrix_color: [f32;4],
+    pub local_player: usize,
+    pub local_player_characteristics: EntityCharacteristics,
+    pub collision_speed: f32,
+    pub eat_ratio: f32,
+    pub teams: Vec<v7::TeamSettings>,
+    pub max_cells_spawn: usize,
+    pub auto_spawn: Vec<AutoSpawnSettings>,
+    pub camera_initial: f32,
+    pub max_camera: f32,
+    pub special: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AutoSpawnSettings {
+    pub amount: usize,
+    pub position: v7::SpawnPosition,
+    pub mass: v7::AutoSpawnMass,
+    pub color: v7::AutoSpawnEntityColor,
+    pub texture: v7::AutoSpawnEntityTexture,
+    pub timer: v7::EntityTimer,
+    pub characteristics: EntityCharacteristics,
+    pub every: u64,
+    pub max: Option<usize>,
+    pub schedule: Option<Range<u64>>,
+    pub condition: Option<v7::SpawnCondition>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ThrownFoodInfo {
+    pub mass_minimum_to_throw: i64,
+    pub mass_self_added: i64,
+    pub mass_entity_thrown: i64,
+    pub throw_ratio: i32,
+    pub power: RangeInclusive<i32>,
+    pub angle: RangeInclusive<f32>,
+    pub color: v7::ThrownEntityColor,
+    pub texture: v7::ThrownEntityTexture,
+    pub timer: v7::EntityTimer,
+    pub characteristics_entity_thrown: EntityCharacteristics,
+}
+
+#[derive(Deserialize)]
+pub struct PlayerInfo {
+    pub kind: v7::PlayerKind,
+    pub entities: Vec<usize>,
+    pub cell_default_color: v7::EntityColor,
+    pub cell_default_texture: usize,
+    pub settings: PlayerSettings,
+    pub team: Option<usize>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct PlayerSettings {
+    pub unit_speed: f32,
+    pub unit_speed_split: f32,
+    pub max_split: usize,
+    pub food: ThrownFoodInfo,
+}
+
+#[derive(Deserialize)]
+pub struct EntityInfo {
+    pub player: usize,
+    pub position: Point2D<i32>,
+    pub speed: Vector2D<f32>,
+    pub mass: i64,
+    pub color: v7::EntityColor,
+    pub texture: usize,
+    pub timer: v7::EntityTimer,
+    pub characteristics: EntityCharacteristics,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct EntityCharacteristics {
+    pub killer: bool,
+    pub collide: bool,
+    pub collide_when_mergeable: bool,
+    pub mergeable: bool,
+    pub affected_by_gravity: bool,
+    pub bounce: bool,
+    pub can_split_on_kill: bool,
+    pub invincible: bool,
+    pub inertia: i32,
+    pub mass_min: i64,
+    pub mass_max: i64,
+    pub mass_evolution: Option<f32>,
+    pub on_death: Option<v7::OnDeathEffect>,
+    pub gravity: Option<v7::EntityGravityInfo>,
+    pub throw_entity: Option<ThrowEntityInfo>,
+    pub special: Vec<v7::EntitySpecial>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ThrowEntityInfo {
+    pub mass_minimum_to_throw: i64,
+    pub mass_self_added: i64,
+    pub mass_entity_thrown: i64,
+    pub throw_ratio: f32,
+    pub direction: Range<f32>,
+    pub power: Range<i32>,
+    pub color: v7::ThrownEntityColor,
+    pub texture: v7::ThrownEntityTexture,
+    pub timer_entity_thrown: v7::EntityTimer,
+    pub characteristics_entity_thrown: ThrownEntityCharacteristics,
+}
+
+#[derive(Clone, Deserialize)]
+pub enum ThrownEntityCharacteristics {
+    Same,
+    Custom(Box<EntityCharacteristics>),
+    CustomIndex(usize),
+}
+
+// Upgrade to the next version (v6, and v7 for the ones unchanged in v6)
+
+impl From<GameSerialize> for v6::GameSerialize {
+    fn from(old: GameSerialize) -> v6::GameSerialize {
+        v6::GameSerialize {
+            settings: old.settings.into(),
+            players_info: old.players_info.into_iter().map(Into::into).collect(),
+            entities_info: old.entities_info.into_iter().map(Into::into).collect(),
+            entities_characteristics: old.entities_characteristics.into_iter().map(Into::into).collect(),
+            map_info: old.map_info,
+            rng: old.rng,
+            textures: old.textures,
+        }
+    }
+}
+
+impl From<Settings> for v6::Settings {
+    fn from(old: Settings) -> v6::Settings {
+        v6::Settings {
+            background_color: old.background_color,
+            matrix_color: old.matrix_color,
+            local_player: old.local_player,
+            local_player_characteristics: old.local_player_characteristics.into(),
+            collision_speed: old.collision_speed,
+            eat_ratio: old.eat_ratio,
+            teams: old.teams,
+            max_cells_spawn: old.max_cells_spawn,
+            auto_spawn: old.auto_spawn.into_iter().map(Into::into).collect(),
+            camera_initial: old.camera_initial,
+            max_camera: old.max_camera,
+            special: old.special,
+        }
+    }
+}
+
+impl From<AutoSpawnSettings> for v7::AutoSpawnSettings {
+    fn from(old: AutoSpawnSettings) -> v7::AutoSpawnSettings {
+        v7::AutoSpawnSettings {
+            amount: old.amount,
+            position: old.position,
+            mass: old.mass,
+            color: old.color,
+            texture: old.texture,
+            timer: old.timer,
+            characteristics: old.characteristics.into(),
+            every: old.every,
+            max: old.max,
+            schedule: old.schedule,
+            condition: old.condition,
+        }
+    }
+}
+
+impl From<ThrownFoodInfo> for v7::ThrownFoodInfo {
+    fn from(old: ThrownFoodInfo) -> v7::ThrownFoodInfo {
+        v7::ThrownFoodInfo {
+            mass_minimum_to_throw: old.mass_minimum_to_throw,
+            mass_self_added: old.mass_self_added,
+            mass_entity_thrown: old.mass_entity_thrown,
+            throw_ratio: old.throw_ratio,
+            power: old.power,
+            angle: old.angle,
+            color: old.color,
+            texture: old.texture,
+            timer: old.timer,
+            characteristics_entity_thrown: old.characteristics_entity_thrown.into(),
+        }
+    }
+}
+
+impl From<PlayerInfo> for v7::PlayerInfo {
+    fn from(old: PlayerInfo) -> v7::PlayerInfo {
+        v7::PlayerInfo {
+            kind: old.kind,
+            entities: old.entities,
+            cell_default_color: old.cell_default_color,
+            cell_default_texture: old.cell_default_texture,
+            settings: old.settings.into(),
+            team: old.team,
+        }
+    }
+}
+
+impl From<PlayerSettings> for v7::PlayerSettings {
+    fn from(old: PlayerSettings) -> v7::PlayerSettings {
+        v7::PlayerSettings {
+            unit_speed: old.unit_speed,
+            unit_speed_split: old.unit_speed_split,
+            max_split: old.max_split,
+            food: old.food.into(),
+        }
+    }
+}
+
+impl From<EntityInfo> for v7::EntityInfo {
+    fn from(old: EntityInfo) -> v7::EntityInfo {
+        v7::EntityInfo {
+            player: old.player,
+            position: old.position,
+            speed: old.speed,
+            mass: old.mass,
+            color: old.color,
+            texture: old.texture,
+            timer: old.timer,
+            characteristics: old.characteristics.into(),
+        }
+    }
+}
+
+impl From<EntityCharacteristics> for v7::EntityCharacteristics {
+    fn from(old: EntityCharacteristics) -> v7::EntityCharacteristics {
+        v7::EntityCharacteristics {
+            killer: old.killer,
+            collide: old.collide,
+            collide_when_mergeable: old.collide_when_mergeable,
+            mergeable: old.mergeable,
+            affected_by_gravity: old.affected_by_gravity,
+            bounce: old.bounce,
+            can_split_on_kill: old.can_split_on_kill,
+            invincible: old.invincible,
+            inertia: old.inertia,
+            mass_min: old.mass_min,
+            mass_max: old.mass_max,
+            mass_evolution: old.mass_evolution,
+            on_death: old.on_death,
+            gravity: old.gravity,
+            throw_entity: old.throw_entity.map(Into::into),
+            virus: None,
+            special: old.special,
+        }
+    }
+}
+
+impl From<ThrowEntityInfo> for v7::ThrowEntityInfo {
+    fn from(old: ThrowEntityInfo) -> v7::ThrowEntityInfo {
+        v7::ThrowEntityInfo {
+            mass_minimum_to_throw: old.mass_minimum_to_throw,
+            mass_self_added: old.mass_self_added,
+            mass_entity_thrown: old.mass_entity_thrown,
+            throw_ratio: old.throw_ratio,
+            direction: old.direction,
+            power: old.power,
+            color: old.color,
+            texture: old.texture,
+            timer_entity_thrown: old.timer_entity_thrown,
+            characteristics_entity_thrown: match old.characteristics_entity_thrown {
+                ThrownEntityCharacteristics::Same => v7::ThrownEntityCharacteristics::Same,
+                ThrownEntityCharacteristics::Custom(characteristics) => v7::ThrownEntityCharacteristics::Custom(Box::new((*characteristics).into())),
+                ThrownEntityCharacteristics::CustomIndex(index) => v7::ThrownEntityCharacteristics::CustomIndex(index),
+            },
+        }
+    }
+}
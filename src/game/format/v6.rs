@@ -0,0 +1,87 @@
+// Version 6: viruses, no walls in MapInfo and no Settings::wall_color.
+// Frozen copy of the structs changed since, the other ones are the ones of the next versions (all frozen in v7).
+
+use crate::game::settings;
+use super::v7;
+
+use euclid::default::Size2D;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GameSerialize {
+    pub settings: Settings,
+    pub players_info: Vec<v7::PlayerInfo>,
+    pub entities_info: Vec<v7::EntityInfo>,
+    pub entities_characteristics: Vec<v7::EntityCharacteristics>,
+    pub map_info: MapInfo,
+    #[serde(default)]
+    pub rng: v7::GameRng,
+    #[serde(default)]
+    pub textures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub background_color: [f32;4],
+    pub matrix_color: [f32;4],
+    pub local_player: usize,
+    pub local_player_characteristics: v7::EntityCharacteristics,
+    pub collision_speed: f32,
+    pub eat_ratio: f32,
+    pub teams: Vec<v7::TeamSettings>,
+    pub max_cells_spawn: usize,
+    pub auto_spawn: Vec<v7::AutoSpawnSettings>,
+    pub camera_initial: f32,
+    pub max_camera: f32,
+    pub special: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MapInfo {
+    pub size: Size2D<i32>,
+}
+
+// Upgrade to the next version (v7)
+
+impl From<GameSerialize> for v7::GameSerialize {
+    fn from(old: GameSerialize) -> v7::GameSerialize {
+        v7::GameSerialize {
+            settings: old.settings.into(),
+            players_info: old.players_info,
+            entities_info: old.entities_info,
+            entities_characteristics: old.entities_characteristics,
+            map_info: old.map_info.into(),
+            rng: old.rng,
+            textures: old.textures,
+        }
+    }
+}
+
+impl From<Settings> for v7::Settings {
+    fn from(old: Settings) -> v7::Settings {
+        v7::Settings {
+            background_color: old.background_color,
+            matrix_color: old.matrix_color,
+            wall_color: settings::Settings::default().wall_color,
+            local_player: old.local_player,
+            local_player_characteristics: old.local_player_characteristics,
+            collision_speed: old.collision_speed,
+            eat_ratio: old.eat_ratio,
+            teams: old.teams,
+            max_cells_spawn: old.max_cells_spawn,
+            auto_spawn: old.auto_spawn,
+            camera_initial: old.camera_initial,
+            max_camera: old.max_camera,
+            special: old.special,
+        }
+    }
+}
+
+impl From<MapInfo> for v7::MapInfo {
+    fn from(old: MapInfo) -> v7::MapInfo {
+        v7::MapInfo {
+            size: old.size,
+            walls: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,950 @@
+// Version 7: walls, no MapInfo::topology and no EntityCharacteristics::edge.
+// Frozen copy of the structs of that time, don't change it.
+
+use crate::game;
+use crate::game::entity;
+use crate::game::map;
+use crate::game::settings;
+
+use euclid::default::{Point2D, Size2D, Vector2D};
+use serde::Deserialize;
+use std::ops::{Range, RangeInclusive};
+
+#[derive(Deserialize)]
+pub struct GameSerialize {
+    pub settings: Settings,
+    pub players_info: Vec<PlayerInfo>,
+    pub entities_info: Vec<EntityInfo>,
+    pub entities_characteristics: Vec<EntityCharacteristics>,
+    pub map_info: MapInfo,
+    #[serde(default)]
+    pub rng: GameRng,
+    #[serde(default)]
+    pub textures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub background_color: [f32;4],
+    pub matrix_color: [f32;4],
+    pub wall_color: [f32;4],
+    pub local_player: usize,
+    pub local_player_characteristics: EntityCharacteristics,
+    pub collision_speed: f32,
+    pub eat_ratio: f32,
+    pub teams: Vec<TeamSettings>,
+    pub max_cells_spawn: usize,
+    pub auto_spawn: Vec<AutoSpawnSettings>,
+    pub camera_initial: f32,
+    pub max_camera: f32,
+    pub special: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AutoSpawnSettings {
+    pub amount: usize,
+    pub position: SpawnPosition,
+    pub mass: AutoSpawnMass,
+    pub color: AutoSpawnEntityColor,
+    pub texture: AutoSpawnEntityTexture,
+    pub timer: EntityTimer,
+    pub characteristics: EntityCharacteristics,
+    pub every: u64,
+    pub max: Option<usize>,
+    pub schedule: Option<Range<u64>>,
+    pub condition: Option<SpawnCondition>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ThrownFoodInfo {
+    pub mass_minimum_to_throw: i64,
+    pub mass_self_added: i64,
+    pub mass_entity_thrown: i64,
+    pub throw_ratio: i32,
+    pub power: RangeInclusive<i32>,
+    pub angle: RangeInclusive<f32>,
+    pub color: ThrownEntityColor,
+    pub texture: ThrownEntityTexture,
+    pub timer: EntityTimer,
+    pub characteristics_entity_thrown: EntityCharacteristics,
+}
+
+#[derive(Deserialize)]
+pub struct PlayerInfo {
+    pub kind: PlayerKind,
+    pub entities: Vec<usize>,
+    pub cell_default_color: EntityColor,
+    pub cell_default_texture: usize,
+    pub settings: PlayerSettings,
+    pub team: Option<usize>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct PlayerSettings {
+    pub unit_speed: f32,
+    pub unit_speed_split: f32,
+    pub max_split: usize,
+    pub food: ThrownFoodInfo,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct EntityInfo {
+    pub player: usize,
+    pub position: Point2D<i32>,
+    pub speed: Vector2D<f32>,
+    pub mass: i64,
+    pub color: EntityColor,
+    pub texture: usize,
+    pub timer: EntityTimer,
+    pub characteristics: EntityCharacteristics,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct EntityCharacteristics {
+    pub killer: bool,
+    pub collide: bool,
+    pub collide_when_mergeable: bool,
+    pub mergeable: bool,
+    pub affected_by_gravity: bool,
+    pub bounce: bool,
+    pub can_split_on_kill: bool,
+    pub invincible: bool,
+    pub inertia: i32,
+    pub mass_min: i64,
+    pub mass_max: i64,
+    pub mass_evolution: Option<f32>,
+    pub on_death: Option<OnDeathEffect>,
+    pub gravity: Option<EntityGravityInfo>,
+    pub throw_entity: Option<ThrowEntityInfo>,
+    pub virus: Option<VirusInfo>,
+    pub special: Vec<EntitySpecial>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ThrowEntityInfo {
+    pub mass_minimum_to_throw: i64,
+    pub mass_self_added: i64,
+    pub mass_entity_thrown: i64,
+    pub throw_ratio: f32,
+    pub direction: Range<f32>,
+    pub power: Range<i32>,
+    pub color: ThrownEntityColor,
+    pub texture: ThrownEntityTexture,
+    pub timer_entity_thrown: EntityTimer,
+    pub characteristics_entity_thrown: ThrownEntityCharacteristics,
+}
+
+#[derive(Clone, Deserialize)]
+pub enum ThrownEntityCharacteristics {
+    Same,
+    Custom(Box<EntityCharacteristics>),
+    CustomIndex(usize),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum OnDeathEffect {
+    Split(usize),
+    Spawn(SpawnOnDeathInfo),
+    Explode(ExplodeOnDeathInfo),
+    TransferMass(TransferMassOnDeathInfo),
+    Respawn(RespawnOnDeathInfo),
+    Multiple(Vec<OnDeathEffect>),
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SpawnOnDeathInfo {
+    pub entities: Vec<EntityInfo>,
+    pub amount: usize,
+    pub speed: DeathSpeed,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ExplodeOnDeathInfo {
+    pub particles: usize,
+    pub mass_ratio: f32,
+    pub speed: Range<f32>,
+    pub lifetime_left: i32,
+    pub color: ThrownEntityColor,
+    pub texture: ThrownEntityTexture,
+    pub characteristics: ThrownEntityCharacteristics,
+}
+
+#[derive(Deserialize)]
+pub struct MapInfo {
+    pub size: Size2D<i32>,
+    pub walls: Vec<Wall>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct GameRng {
+    pub seed: u64,
+    pub tick: u64,
+}
+
+impl Default for GameRng {
+    fn default() -> GameRng {
+        GameRng {
+            seed: rand::random(),
+            tick: 0,
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TeamSettings {
+    pub name: String,
+    pub color: Option<EntityColor>,
+    pub feed: bool,
+    pub merge: bool,
+}
+
+#[derive(Clone, Deserialize)]
+pub enum PlayerKind {
+    Player,
+    Neutral,
+    Bot(BotSettings),
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct BotSettings {
+    pub reaction: u64,
+    pub view: i32,
+    pub aim: i32,
+    pub chase: bool,
+    pub flee: bool,
+    pub split_chance: f32,
+    pub throw_chance: f32,
+    pub respawn: Option<i64>,
+}
+
+impl Default for BotSettings {
+    fn default() -> BotSettings {
+        BotSettings {
+            reaction: 10,
+            view: 40_000,
+            aim: 2_000,
+            chase: true,
+            flee: true,
+            split_chance: 0.2,
+            throw_chance: 0.05,
+            respawn: Some(entity::RATIO_MASS * 10),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub enum SpawnPosition {
+    Exact(i32, i32),
+    Random,
+    RandomRange(Range<i32>, Range<i32>),
+    Distribution(Distribution),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum Distribution {
+    Uniform,
+    Noise(NoiseField),
+    Rings(RingsField),
+    Spiral(SpiralField),
+    Gradient(GradientField),
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct NoiseField {
+    pub seed: u64,
+    pub scale: f32,
+    pub octaves: u32,
+    pub threshold: f32,
+}
+
+impl Default for NoiseField {
+    fn default() -> NoiseField {
+        NoiseField {
+            seed: 0,
+            scale: 0.1,
+            octaves: 3,
+            threshold: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct RingsField {
+    pub center: (f32, f32),
+    pub radius: f32,
+    pub gap: f32,
+    pub count: usize,
+    pub width: f32,
+}
+
+impl Default for RingsField {
+    fn default() -> RingsField {
+        RingsField {
+            center: (0.5, 0.5),
+            radius: 0.1,
+            gap: 0.1,
+            count: 3,
+            width: 0.02,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct SpiralField {
+    pub center: (f32, f32),
+    pub radius: f32,
+    pub arms: usize,
+    pub turns: f32,
+    pub width: f32,
+}
+
+impl Default for SpiralField {
+    fn default() -> SpiralField {
+        SpiralField {
+            center: (0.5, 0.5),
+            radius: 0.45,
+            arms: 2,
+            turns: 1.5,
+            width: 0.03,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct GradientField {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub density_from: f32,
+    pub density_to: f32,
+}
+
+impl Default for GradientField {
+    fn default() -> GradientField {
+        GradientField {
+            from: (0.0, 0.5),
+            to: (1.0, 0.5),
+            density_from: 1.0,
+            density_to: 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub enum AutoSpawnMass {
+    Exact(i64),
+    Random(Vec<i64>),
+    RandomRange(Range<i64>),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum AutoSpawnEntityTexture {
+    CustomIndex(usize),
+    Random(Vec<usize>),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum AutoSpawnEntityColor {
+    Custom(EntityColor),
+    Random(Vec<EntityColor>),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum SpawnCondition {
+    EntitiesBelow(usize),
+    EntitiesAbove(usize),
+    RuleBelow(usize, usize),
+    RuleAbove(usize, usize),
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub struct EntityColor {
+    pub center: [u8;4],
+    pub edge: [u8;4],
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EntityTimer {
+    pub collision: Option<i32>,
+    pub collision_ratio: Option<i32>,
+    pub mergeable: Option<i32>,
+    pub inertia: Option<i32>,
+    pub lifetime_left: Option<i32>,
+}
+
+#[derive(Clone, Deserialize)]
+pub enum ThrownEntityTexture {
+    Same,
+    CustomIndex(usize),
+    Random(Vec<usize>),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum ThrownEntityColor {
+    Same,
+    Custom(EntityColor),
+    Random(Vec<EntityColor>),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum DistanceRatio {
+    Linear,
+    Squared,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct EntityGravityInfo {
+    pub power: f32,
+    pub distance_ratio: DistanceRatio,
+    pub distance_limit: Range<f32>,
+    pub distance_clamp: Range<f32>,
+    pub speed_limit: Range<f32>,
+    pub speed_clamp: Range<f32>,
+}
+
+impl Default for EntityGravityInfo {
+    fn default() -> EntityGravityInfo {
+        EntityGravityInfo {
+            power: 1.0,
+            distance_ratio: DistanceRatio::Squared,
+            distance_limit: 0.0..f32::MAX,
+            distance_clamp: 0.0..f32::MAX,
+            speed_limit: 0.0..f32::MAX,
+            speed_clamp: 0.0..f32::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct VirusInfo {
+    pub pieces: usize,
+    pub feed_mass_max: i64,
+    pub fire_mass: i64,
+    pub fire_speed: f32,
+}
+
+impl Default for VirusInfo {
+    fn default() -> VirusInfo {
+        VirusInfo {
+            pieces: 16,
+            feed_mass_max: entity::RATIO_MASS * 10,
+            fire_mass: entity::RATIO_MASS * 200,
+            fire_speed: 3_000.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub enum EntitySpecial {
+    WASM(String),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum DeathSpeed {
+    Radial(f32),
+    Directional(f32, f32),
+    Same,
+}
+
+#[derive(Clone, Deserialize)]
+pub enum MassTransferTarget {
+    Killer,
+    Nearby(i32),
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TransferMassOnDeathInfo {
+    pub ratio: f32,
+    pub target: MassTransferTarget,
+}
+
+impl Default for TransferMassOnDeathInfo {
+    fn default() -> TransferMassOnDeathInfo {
+        TransferMassOnDeathInfo {
+            ratio: 0.5,
+            target: MassTransferTarget::Killer,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct RespawnOnDeathInfo {
+    pub delay: u64,
+    pub position: Option<Point2D<i32>>,
+    pub mass: Option<i64>,
+    pub timer: EntityTimer,
+    pub lives: Option<usize>,
+}
+
+impl Default for RespawnOnDeathInfo {
+    fn default() -> RespawnOnDeathInfo {
+        RespawnOnDeathInfo {
+            delay: 500,
+            position: None,
+            mass: None,
+            timer: EntityTimer::default(),
+            lives: None,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub enum Wall {
+    Segment(Point2D<i32>, Point2D<i32>, i32),
+    Polygon(Vec<Point2D<i32>>),
+}
+
+// Upgrade to the structs of the game
+
+impl From<GameSerialize> for game::GameSerialize {
+    fn from(old: GameSerialize) -> game::GameSerialize {
+        game::GameSerialize {
+            settings: old.settings.into(),
+            players_info: old.players_info.into_iter().map(Into::into).collect(),
+            entities_info: old.entities_info.into_iter().map(Into::into).collect(),
+            entities_characteristics: old.entities_characteristics.into_iter().map(Into::into).collect(),
+            map_info: old.map_info.into(),
+            rng: old.rng.into(),
+            textures: old.textures,
+        }
+    }
+}
+
+impl From<Settings> for settings::Settings {
+    fn from(old: Settings) -> settings::Settings {
+        settings::Settings {
+            background_color: old.background_color,
+            matrix_color: old.matrix_color,
+            wall_color: old.wall_color,
+            local_player: old.local_player,
+            local_player_characteristics: old.local_player_characteristics.into(),
+            collision_speed: old.collision_speed,
+            eat_ratio: old.eat_ratio,
+            teams: old.teams.into_iter().map(Into::into).collect(),
+            max_cells_spawn: old.max_cells_spawn,
+            auto_spawn: old.auto_spawn.into_iter().map(Into::into).collect(),
+            camera_initial: old.camera_initial,
+            max_camera: old.max_camera,
+            special: old.special,
+        }
+    }
+}
+
+impl From<AutoSpawnSettings> for settings::AutoSpawnSettings {
+    fn from(old: AutoSpawnSettings) -> settings::AutoSpawnSettings {
+        settings::AutoSpawnSettings {
+            amount: old.amount,
+            position: old.position.into(),
+            mass: old.mass.into(),
+            color: old.color.into(),
+            texture: old.texture.into(),
+            timer: old.timer.into(),
+            characteristics: old.characteristics.into(),
+            every: old.every,
+            max: old.max,
+            schedule: old.schedule,
+            condition: old.condition.map(Into::into),
+        }
+    }
+}
+
+impl From<ThrownFoodInfo> for settings::ThrownFoodInfo {
+    fn from(old: ThrownFoodInfo) -> settings::ThrownFoodInfo {
+        settings::ThrownFoodInfo {
+            mass_minimum_to_throw: old.mass_minimum_to_throw,
+            mass_self_added: old.mass_self_added,
+            mass_entity_thrown: old.mass_entity_thrown,
+            throw_ratio: old.throw_ratio,
+            power: old.power,
+            angle: old.angle,
+            color: old.color.into(),
+            texture: old.texture.into(),
+            timer: old.timer.into(),
+            characteristics_entity_thrown: old.characteristics_entity_thrown.into(),
+        }
+    }
+}
+
+impl From<PlayerInfo> for game::player::PlayerInfo {
+    fn from(old: PlayerInfo) -> game::player::PlayerInfo {
+        game::player::PlayerInfo {
+            kind: old.kind.into(),
+            entities: old.entities,
+            cell_default_color: old.cell_default_color.into(),
+            cell_default_texture: old.cell_default_texture,
+            settings: old.settings.into(),
+            team: old.team,
+        }
+    }
+}
+
+impl From<PlayerSettings> for game::player::PlayerSettings {
+    fn from(old: PlayerSettings) -> game::player::PlayerSettings {
+        game::player::PlayerSettings {
+            unit_speed: old.unit_speed,
+            unit_speed_split: old.unit_speed_split,
+            max_split: old.max_split,
+            food: old.food.into(),
+        }
+    }
+}
+
+impl From<EntityInfo> for entity::EntityInfo {
+    fn from(old: EntityInfo) -> entity::EntityInfo {
+        entity::EntityInfo {
+            player: old.player,
+            position: old.position,
+            speed: old.speed,
+            mass: old.mass,
+            color: old.color.into(),
+            texture: old.texture,
+            timer: old.timer.into(),
+            characteristics: old.characteristics.into(),
+        }
+    }
+}
+
+impl From<EntityCharacteristics> for entity::EntityCharacteristics {
+    fn from(old: EntityCharacteristics) -> entity::EntityCharacteristics {
+        entity::EntityCharacteristics {
+            killer: old.killer,
+            collide: old.collide,
+            collide_when_mergeable: old.collide_when_mergeable,
+            mergeable: old.mergeable,
+            affected_by_gravity: old.affected_by_gravity,
+            bounce: old.bounce,
+            edge: entity::EdgePolicy::Map,
+            can_split_on_kill: old.can_split_on_kill,
+            invincible: old.invincible,
+            inertia: old.inertia,
+            mass_min: old.mass_min,
+            mass_max: old.mass_max,
+            mass_evolution: old.mass_evolution,
+            on_death: old.on_death.map(Into::into),
+            gravity: old.gravity.map(Into::into),
+            throw_entity: old.throw_entity.map(Into::into),
+            virus: old.virus.map(Into::into),
+            special: old.special.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ThrowEntityInfo> for entity::ThrowEntityInfo {
+    fn from(old: ThrowEntityInfo) -> entity::ThrowEntityInfo {
+        entity::ThrowEntityInfo {
+            mass_minimum_to_throw: old.mass_minimum_to_throw,
+            mass_self_added: old.mass_self_added,
+            mass_entity_thrown: old.mass_entity_thrown,
+            throw_ratio: old.throw_ratio,
+            direction: old.direction,
+            power: old.power,
+            color: old.color.into(),
+            texture: old.texture.into(),
+            timer_entity_thrown: old.timer_entity_thrown.into(),
+            characteristics_entity_thrown: old.characteristics_entity_thrown.into(),
+        }
+    }
+}
+
+impl From<ThrownEntityCharacteristics> for entity::ThrownEntityCharacteristics {
+    fn from(old: ThrownEntityCharacteristics) -> entity::ThrownEntityCharacteristics {
+        match old {
+            ThrownEntityCharacteristics::Same => entity::ThrownEntityCharacteristics::Same,
+            ThrownEntityCharacteristics::Custom(characteristics) => entity::ThrownEntityCharacteristics::Custom(Box::new((*characteristics).into())),
+            ThrownEntityCharacteristics::CustomIndex(index) => entity::ThrownEntityCharacteristics::CustomIndex(index),
+        }
+    }
+}
+
+impl From<OnDeathEffect> for entity::OnDeathEffect {
+    fn from(old: OnDeathEffect) -> entity::OnDeathEffect {
+        match old {
+            OnDeathEffect::Split(count) => entity::OnDeathEffect::Split(count),
+            OnDeathEffect::Spawn(info) => entity::OnDeathEffect::Spawn(entity::SpawnOnDeathInfo {
+                entities: info.entities.into_iter().map(Into::into).collect(),
+                amount: info.amount,
+                speed: info.speed.into(),
+            }),
+            OnDeathEffect::Explode(info) => entity::OnDeathEffect::Explode(entity::ExplodeOnDeathInfo {
+                particles: info.particles,
+                mass_ratio: info.mass_ratio,
+                speed: info.speed,
+                lifetime_left: info.lifetime_left,
+                color: info.color.into(),
+                texture: info.texture.into(),
+                characteristics: info.characteristics.into(),
+            }),
+            OnDeathEffect::TransferMass(info) => entity::OnDeathEffect::TransferMass(info.into()),
+            OnDeathEffect::Respawn(info) => entity::OnDeathEffect::Respawn(info.into()),
+            OnDeathEffect::Multiple(effects) => entity::OnDeathEffect::Multiple(effects.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<MapInfo> for map::MapInfo {
+    fn from(old: MapInfo) -> map::MapInfo {
+        map::MapInfo {
+            size: old.size,
+            walls: old.walls.into_iter().map(Into::into).collect(),
+            topology: map::MapTopology::Rectangle,
+        }
+    }
+}
+
+impl From<GameRng> for game::rng::GameRng {
+    fn from(old: GameRng) -> game::rng::GameRng {
+        game::rng::GameRng {
+            seed: old.seed,
+            tick: old.tick,
+        }
+    }
+}
+
+impl From<TeamSettings> for game::player::TeamSettings {
+    fn from(old: TeamSettings) -> game::player::TeamSettings {
+        game::player::TeamSettings {
+            name: old.name,
+            color: old.color.map(Into::into),
+            feed: old.feed,
+            merge: old.merge,
+        }
+    }
+}
+
+impl From<PlayerKind> for game::player::PlayerKind {
+    fn from(old: PlayerKind) -> game::player::PlayerKind {
+        match old {
+            PlayerKind::Player => game::player::PlayerKind::Player,
+            PlayerKind::Neutral => game::player::PlayerKind::Neutral,
+            PlayerKind::Bot(bot) => game::player::PlayerKind::Bot(bot.into()),
+        }
+    }
+}
+
+impl From<BotSettings> for game::bot::BotSettings {
+    fn from(old: BotSettings) -> game::bot::BotSettings {
+        game::bot::BotSettings {
+            reaction: old.reaction,
+            view: old.view,
+            aim: old.aim,
+            chase: old.chase,
+            flee: old.flee,
+            split_chance: old.split_chance,
+            throw_chance: old.throw_chance,
+            respawn: old.respawn,
+        }
+    }
+}
+
+impl From<SpawnPosition> for settings::SpawnPosition {
+    fn from(old: SpawnPosition) -> settings::SpawnPosition {
+        match old {
+            SpawnPosition::Exact(x, y) => settings::SpawnPosition::Exact(x, y),
+            SpawnPosition::Random => settings::SpawnPosition::Random,
+            SpawnPosition::RandomRange(x, y) => settings::SpawnPosition::RandomRange(x, y),
+            SpawnPosition::Distribution(distribution) => settings::SpawnPosition::Distribution(distribution.into()),
+        }
+    }
+}
+
+impl From<Distribution> for map::generator::Distribution {
+    fn from(old: Distribution) -> map::generator::Distribution {
+        match old {
+            Distribution::Uniform => map::generator::Distribution::Uniform,
+            Distribution::Noise(field) => map::generator::Distribution::Noise(map::generator::NoiseField {
+                seed: field.seed,
+                scale: field.scale,
+                octaves: field.octaves,
+                threshold: field.threshold,
+            }),
+            Distribution::Rings(field) => map::generator::Distribution::Rings(map::generator::RingsField {
+                center: field.center,
+                radius: field.radius,
+                gap: field.gap,
+                count: field.count,
+                width: field.width,
+            }),
+            Distribution::Spiral(field) => map::generator::Distribution::Spiral(map::generator::SpiralField {
+                center: field.center,
+                radius: field.radius,
+                arms: field.arms,
+                turns: field.turns,
+                width: field.width,
+            }),
+            Distribution::Gradient(field) => map::generator::Distribution::Gradient(map::generator::GradientField {
+                from: field.from,
+                to: field.to,
+                density_from: field.density_from,
+                density_to: field.density_to,
+            }),
+        }
+    }
+}
+
+impl From<AutoSpawnMass> for settings::AutoSpawnMass {
+    fn from(old: AutoSpawnMass) -> settings::AutoSpawnMass {
+        match old {
+            AutoSpawnMass::Exact(mass) => settings::AutoSpawnMass::Exact(mass),
+            AutoSpawnMass::Random(mass) => settings::AutoSpawnMass::Random(mass),
+            AutoSpawnMass::RandomRange(mass) => settings::AutoSpawnMass::RandomRange(mass),
+        }
+    }
+}
+
+impl From<AutoSpawnEntityTexture> for settings::AutoSpawnEntityTexture {
+    fn from(old: AutoSpawnEntityTexture) -> settings::AutoSpawnEntityTexture {
+        match old {
+            AutoSpawnEntityTexture::CustomIndex(texture) => settings::AutoSpawnEntityTexture::CustomIndex(texture),
+            AutoSpawnEntityTexture::Random(textures) => settings::AutoSpawnEntityTexture::Random(textures),
+        }
+    }
+}
+
+impl From<AutoSpawnEntityColor> for settings::AutoSpawnEntityColor {
+    fn from(old: AutoSpawnEntityColor) -> settings::AutoSpawnEntityColor {
+        match old {
+            AutoSpawnEntityColor::Custom(color) => settings::AutoSpawnEntityColor::Custom(color.into()),
+            AutoSpawnEntityColor::Random(colors) => settings::AutoSpawnEntityColor::Random(colors.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<SpawnCondition> for settings::SpawnCondition {
+    fn from(old: SpawnCondition) -> settings::SpawnCondition {
+        match old {
+            SpawnCondition::EntitiesBelow(amount) => settings::SpawnCondition::EntitiesBelow(amount),
+            SpawnCondition::EntitiesAbove(amount) => settings::SpawnCondition::EntitiesAbove(amount),
+            SpawnCondition::RuleBelow(rule, amount) => settings::SpawnCondition::RuleBelow(rule, amount),
+            SpawnCondition::RuleAbove(rule, amount) => settings::SpawnCondition::RuleAbove(rule, amount),
+        }
+    }
+}
+
+impl From<EntityColor> for entity::EntityColor {
+    fn from(old: EntityColor) -> entity::EntityColor {
+        entity::EntityColor {
+            center: old.center,
+            edge: old.edge,
+        }
+    }
+}
+
+impl From<EntityTimer> for entity::EntityTimer {
+    fn from(old: EntityTimer) -> entity::EntityTimer {
+        entity::EntityTimer {
+            collision: old.collision,
+            collision_ratio: old.collision_ratio,
+            mergeable: old.mergeable,
+            inertia: old.inertia,
+            lifetime_left: old.lifetime_left,
+        }
+    }
+}
+
+impl From<ThrownEntityTexture> for entity::ThrownEntityTexture {
+    fn from(old: ThrownEntityTexture) -> entity::ThrownEntityTexture {
+        match old {
+            ThrownEntityTexture::Same => entity::ThrownEntityTexture::Same,
+            ThrownEntityTexture::CustomIndex(texture) => entity::ThrownEntityTexture::CustomIndex(texture),
+            ThrownEntityTexture::Random(textures) => entity::ThrownEntityTexture::Random(textures),
+        }
+    }
+}
+
+impl From<ThrownEntityColor> for entity::ThrownEntityColor {
+    fn from(old: ThrownEntityColor) -> entity::ThrownEntityColor {
+        match old {
+            ThrownEntityColor::Same => entity::ThrownEntityColor::Same,
+            ThrownEntityColor::Custom(color) => entity::ThrownEntityColor::Custom(color.into()),
+            ThrownEntityColor::Random(colors) => entity::ThrownEntityColor::Random(colors.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<EntityGravityInfo> for entity::EntityGravityInfo {
+    fn from(old: EntityGravityInfo) -> entity::EntityGravityInfo {
+        entity::EntityGravityInfo {
+            power: old.power,
+            distance_ratio: match old.distance_ratio {
+                DistanceRatio::Linear => entity::DistanceRatio::Linear,
+                DistanceRatio::Squared => entity::DistanceRatio::Squared,
+            },
+            distance_limit: old.distance_limit,
+            distance_clamp: old.distance_clamp,
+            speed_limit: old.speed_limit,
+            speed_clamp: old.speed_clamp,
+        }
+    }
+}
+
+impl From<VirusInfo> for entity::VirusInfo {
+    fn from(old: VirusInfo) -> entity::VirusInfo {
+        entity::VirusInfo {
+            pieces: old.pieces,
+            feed_mass_max: old.feed_mass_max,
+            fire_mass: old.fire_mass,
+            fire_speed: old.fire_speed,
+        }
+    }
+}
+
+impl From<EntitySpecial> for entity::EntitySpecial {
+    fn from(old: EntitySpecial) -> entity::EntitySpecial {
+        match old {
+            EntitySpecial::WASM(path) => entity::EntitySpecial::WASM(path),
+        }
+    }
+}
+
+impl From<DeathSpeed> for entity::DeathSpeed {
+    fn from(old: DeathSpeed) -> entity::DeathSpeed {
+        match old {
+            DeathSpeed::Radial(speed) => entity::DeathSpeed::Radial(speed),
+            DeathSpeed::Directional(angle, speed) => entity::DeathSpeed::Directional(angle, speed),
+            DeathSpeed::Same => entity::DeathSpeed::Same,
+        }
+    }
+}
+
+impl From<TransferMassOnDeathInfo> for entity::TransferMassOnDeathInfo {
+    fn from(old: TransferMassOnDeathInfo) -> entity::TransferMassOnDeathInfo {
+        entity::TransferMassOnDeathInfo {
+            ratio: old.ratio,
+            target: match old.target {
+                MassTransferTarget::Killer => entity::MassTransferTarget::Killer,
+                MassTransferTarget::Nearby(radius) => entity::MassTransferTarget::Nearby(radius),
+            },
+        }
+    }
+}
+
+impl From<RespawnOnDeathInfo> for entity::RespawnOnDeathInfo {
+    fn from(old: RespawnOnDeathInfo) -> entity::RespawnOnDeathInfo {
+        entity::RespawnOnDeathInfo {
+            delay: old.delay,
+            position: old.position,
+            mass: old.mass,
+            timer: old.timer.into(),
+            lives: old.lives,
+        }
+    }
+}
+
+impl From<Wall> for map::walls::Wall {
+    fn from(old: Wall) -> map::walls::Wall {
+        match old {
+            Wall::Segment(start, end, thickness) => map::walls::Wall::Segment(start, end, thickness),
+            Wall::Polygon(points) => map::walls::Wall::Polygon(points),
+        }
+    }
+}
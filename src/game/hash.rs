@@ -0,0 +1,497 @@
+use crate::game::{Game, Settings};
+use crate::game::bot::BotSettings;
+use crate::game::entity::{DeathSpeed, DistanceRatio, EdgePolicy, EntityCharacteristics, EntityInfo, EntitySpecial, EntityTimer, MassTransferTarget, OnDeathEffect};
+use crate::game::entity::{ThrowEntityInfo, ThrownEntityCharacteristics, ThrownEntityColor, ThrownEntityTexture};
+use crate::game::map::generator::Distribution;
+use crate::game::map::walls::Wall;
+use crate::game::player::{PlayerKind, PlayerSettings};
+use crate::game::rng::mix;
+use crate::game::settings::{AutoSpawnEntityColor, AutoSpawnEntityTexture, AutoSpawnMass, AutoSpawnSettings, SpawnCondition, SpawnPosition};
+
+use threadpool::utils::ParallelIterator;
+
+const ENTITIES_PER_CHUNK: usize = 4_096;
+
+// Hash of the world that doesn't depend on the platform, the run or the thread count
+#[derive(Clone, Copy)]
+pub struct StateHasher {
+    state: u64,
+}
+
+impl StateHasher {
+    pub fn new() -> StateHasher {
+        StateHasher {
+            state: 0,
+        }
+    }
+
+    #[inline]
+    pub fn write_u64(&mut self, value: u64) {
+        self.state = mix(self.state ^ value);
+    }
+
+    #[inline]
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_u64(value as u32 as u64);
+    }
+
+    #[inline]
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u64(value.to_bits() as u64);
+    }
+
+    #[inline]
+    pub fn write_option_i32(&mut self, value: Option<i32>) {
+        match value {
+            Some(value) => self.write_u64(1 << 32 | value as u32 as u64),
+            None => self.write_u64(0),
+        }
+    }
+
+    pub fn write_option_u64(&mut self, value: Option<u64>) {
+        match value {
+            Some(value) => {
+                self.write_u64(1);
+                self.write_u64(value);
+            }
+            None => self.write_u64(0),
+        }
+    }
+
+    pub fn write_option_f32(&mut self, value: Option<f32>) {
+        self.write_option_u64(value.map(|value| value.to_bits() as u64));
+    }
+
+    // One bit per value, in order
+    pub fn write_bools(&mut self, values: &[bool]) {
+        self.write_u64(values.iter().enumerate().fold(0, |bits, (index, &value)| bits | (value as u64) << index));
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_u64(bytes.len() as u64);
+        for chunk in bytes.chunks(8) {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+pub fn world_hash(game: &Game) -> u64 {
+    let mut hasher = StateHasher::new();
+    hash_settings(&game.settings, &mut hasher);
+    hasher.write_u64(game.entities_characteristics.len() as u64);
+    for characteristics in game.entities_characteristics.iter() {
+        hash_characteristics(characteristics, &mut hasher);
+    }
+    hasher.write_i32(game.map.size.width);
+    hasher.write_i32(game.map.size.height);
+    hasher.write_u64(game.map.topology as u64);
+    hasher.write_u64(game.map.walls.list.len() as u64);
+    for wall in game.map.walls.list.iter() {
+        match wall {
+            Wall::Segment(a, b, thickness) => {
+                hasher.write_u64(0);
+                for value in [a.x, a.y, b.x, b.y, *thickness].iter() { hasher.write_i32(*value) }
+            }
+            Wall::Polygon(points) => {
+                hasher.write_u64(points.len() as u64);
+                for point in points.iter() {
+                    hasher.write_i32(point.x);
+                    hasher.write_i32(point.y);
+                }
+            }
+        }
+    }
+
+    for player in game.players.iter() {
+        match &player.kind {
+            PlayerKind::Player => hasher.write_u64(0),
+            PlayerKind::Neutral => hasher.write_u64(1),
+            PlayerKind::Bot(bot) => {
+                hasher.write_u64(2);
+                hash_bot_settings(bot, &mut hasher);
+            }
+        }
+        hash_player_settings(&player.settings, &mut hasher);
+        hasher.write_u64(u32::from_le_bytes(player.cell_default_color.center) as u64);
+        hasher.write_u64(u32::from_le_bytes(player.cell_default_color.edge) as u64);
+        hasher.write_u64(player.cell_default_texture as u64);
+        hasher.write_u64(player.team.map_or(0, |team| team as u64 + 1));
+        hasher.write_u64(player.entities.len() as u64);
+        for &entity_index in player.entities.iter() {
+            hasher.write_u64(entity_index as u64);
+        }
+    }
+
+    hasher.write_u64(game.spawned.len() as u64);
+    for &count in game.spawned.iter() {
+        hasher.write_u64(count as u64);
+    }
+
+    hasher.write_u64(game.respawns.len() as u64);
+    for respawn in game.respawns.iter() {
+        hasher.write_u64(respawn.tick);
+        hasher.write_u64(respawn.info.player as u64);
+        hasher.write_i32(respawn.info.position.x);
+        hasher.write_i32(respawn.info.position.y);
+        hasher.write_u64(respawn.info.mass as u64);
+    }
+
+    // Fixed chunks hashed in parallel, then combined in order
+    let entities = &game.entities;
+    let mut chunks = vec![0u64; (entities.len() + ENTITIES_PER_CHUNK - 1) / ENTITIES_PER_CHUNK];
+    let chunks_ptr = unsafe_ptr::UnsafePtr::new(chunks.as_mut_ptr());
+    game.threadpool.compute_range_each_thread_join(0..chunks.len(), 1, |chunk: usize| {
+        let mut hasher = StateHasher::new();
+        let end = ((chunk + 1) * ENTITIES_PER_CHUNK).min(entities.len());
+        for index in chunk * ENTITIES_PER_CHUNK..end {
+            hasher.write_i32(entities.position[index].x);
+            hasher.write_i32(entities.position[index].y);
+            hasher.write_f32(entities.speed[index].x);
+            hasher.write_f32(entities.speed[index].y);
+            // Written by the input of the player: a replay diverging there shows up at once, not ticks later by the position
+            match entities.direction[index] {
+                Some(direction) => {
+                    hasher.write_u64(1);
+                    hasher.write_i32(direction.x);
+                    hasher.write_i32(direction.y);
+                }
+                None => hasher.write_u64(0),
+            }
+            hasher.write_u64(entities.mass[index] as u64);
+            hash_timer(&entities.timer[index], &mut hasher);
+            hasher.write_u64(entities.flags[index].bits() as u64);
+            hasher.write_u64(entities.core[index].spawn_rule.map_or(0, |rule| rule as u64 + 1));
+        }
+        unsafe { *chunks_ptr.raw().add(chunk) = hasher.finish(); }
+    });
+    hasher.write_u64(entities.len() as u64);
+    for chunk in chunks {
+        hasher.write_u64(chunk);
+    }
+
+    hasher.finish()
+}
+
+fn hash_timer(timer: &EntityTimer, hasher: &mut StateHasher) {
+    hasher.write_option_i32(timer.collision);
+    hasher.write_option_i32(timer.collision_ratio);
+    hasher.write_option_i32(timer.mergeable);
+    hasher.write_option_i32(timer.inertia);
+    hasher.write_option_i32(timer.lifetime_left);
+}
+
+// Only what changes the simulation: colors, textures and the camera are left out, except the amount of random choices
+fn hash_settings(settings: &Settings, hasher: &mut StateHasher) {
+    hasher.write_u64(settings.local_player as u64);
+    hash_characteristics(&settings.local_player_characteristics, hasher);
+    hasher.write_f32(settings.collision_speed);
+    hasher.write_f32(settings.eat_ratio);
+    hasher.write_u64(settings.teams.len() as u64);
+    for team in settings.teams.iter() {
+        hasher.write_bools(&[team.feed, team.merge]);
+    }
+    hasher.write_u64(settings.max_cells_spawn as u64);
+    hasher.write_u64(settings.auto_spawn.len() as u64);
+    for rule in settings.auto_spawn.iter() {
+        hash_spawn_rule(rule, hasher);
+    }
+    hasher.write_bools(&[settings.special]);
+}
+
+fn hash_spawn_rule(rule: &AutoSpawnSettings, hasher: &mut StateHasher) {
+    hasher.write_u64(rule.amount as u64);
+    match &rule.position {
+        SpawnPosition::Exact(x, y) => {
+            hasher.write_u64(0);
+            hasher.write_i32(*x);
+            hasher.write_i32(*y);
+        }
+        SpawnPosition::Random => hasher.write_u64(1),
+        SpawnPosition::RandomRange(range_x, range_y) => {
+            hasher.write_u64(2);
+            for value in [range_x.start, range_x.end, range_y.start, range_y.end].iter() { hasher.write_i32(*value) }
+        }
+        SpawnPosition::Distribution(distribution) => {
+            hasher.write_u64(3);
+            hash_distribution(distribution, hasher);
+        }
+    }
+    match &rule.mass {
+        AutoSpawnMass::Exact(mass) => {
+            hasher.write_u64(0);
+            hasher.write_u64(*mass as u64);
+        }
+        AutoSpawnMass::Random(masses) => {
+            hasher.write_u64(1);
+            hasher.write_u64(masses.len() as u64);
+            for mass in masses.iter() { hasher.write_u64(*mass as u64) }
+        }
+        AutoSpawnMass::RandomRange(range) => {
+            hasher.write_u64(2);
+            hasher.write_u64(range.start as u64);
+            hasher.write_u64(range.end as u64);
+        }
+    }
+    hasher.write_u64(match &rule.color { AutoSpawnEntityColor::Custom(_) => 0, AutoSpawnEntityColor::Random(colors) => colors.len() as u64 + 1 });
+    hasher.write_u64(match &rule.texture { AutoSpawnEntityTexture::CustomIndex(_) => 0, AutoSpawnEntityTexture::Random(textures) => textures.len() as u64 + 1 });
+    hash_timer(&rule.timer, hasher);
+    hash_characteristics(&rule.characteristics, hasher);
+    hasher.write_u64(rule.every);
+    hasher.write_option_u64(rule.max.map(|max| max as u64));
+    match &rule.schedule {
+        Some(schedule) => {
+            hasher.write_u64(1);
+            hasher.write_u64(schedule.start);
+            hasher.write_u64(schedule.end);
+        }
+        None => hasher.write_u64(0),
+    }
+    let (kind, values) = match rule.condition {
+        None => (0, (0, 0)),
+        Some(SpawnCondition::EntitiesBelow(amount)) => (1, (0, amount)),
+        Some(SpawnCondition::EntitiesAbove(amount)) => (2, (0, amount)),
+        Some(SpawnCondition::RuleBelow(rule, amount)) => (3, (rule, amount)),
+        Some(SpawnCondition::RuleAbove(rule, amount)) => (4, (rule, amount)),
+    };
+    hasher.write_u64(kind);
+    hasher.write_u64(values.0 as u64);
+    hasher.write_u64(values.1 as u64);
+}
+
+fn hash_distribution(distribution: &Distribution, hasher: &mut StateHasher) {
+    match distribution {
+        Distribution::Uniform => hasher.write_u64(0),
+        Distribution::Noise(field) => {
+            hasher.write_u64(1);
+            hasher.write_u64(field.seed);
+            hasher.write_f32(field.scale);
+            hasher.write_u64(field.octaves as u64);
+            hasher.write_f32(field.threshold);
+        }
+        Distribution::Rings(field) => {
+            hasher.write_u64(2);
+            for value in [field.center.0, field.center.1, field.radius, field.gap, field.width].iter() { hasher.write_f32(*value) }
+            hasher.write_u64(field.count as u64);
+        }
+        Distribution::Spiral(field) => {
+            hasher.write_u64(3);
+            for value in [field.center.0, field.center.1, field.radius, field.turns, field.width].iter() { hasher.write_f32(*value) }
+            hasher.write_u64(field.arms as u64);
+        }
+        Distribution::Gradient(field) => {
+            hasher.write_u64(4);
+            for value in [field.from.0, field.from.1, field.to.0, field.to.1, field.density_from, field.density_to].iter() { hasher.write_f32(*value) }
+        }
+    }
+}
+
+fn hash_player_settings(settings: &PlayerSettings, hasher: &mut StateHasher) {
+    hasher.write_f32(settings.unit_speed);
+    hasher.write_f32(settings.unit_speed_split);
+    hasher.write_u64(settings.max_split as u64);
+    let food = &settings.food;
+    hasher.write_u64(food.mass_minimum_to_throw as u64);
+    hasher.write_u64(food.mass_self_added as u64);
+    hasher.write_u64(food.mass_entity_thrown as u64);
+    hasher.write_i32(food.throw_ratio);
+    hasher.write_i32(*food.power.start());
+    hasher.write_i32(*food.power.end());
+    hasher.write_f32(*food.angle.start());
+    hasher.write_f32(*food.angle.end());
+    hash_thrown_look(&food.color, &food.texture, hasher);
+    hash_timer(&food.timer, hasher);
+    hash_characteristics(&food.characteristics_entity_thrown, hasher);
+}
+
+fn hash_bot_settings(bot: &BotSettings, hasher: &mut StateHasher) {
+    hasher.write_u64(bot.reaction);
+    hasher.write_i32(bot.view);
+    hasher.write_i32(bot.aim);
+    hasher.write_bools(&[bot.chase, bot.flee]);
+    hasher.write_f32(bot.split_chance);
+    hasher.write_f32(bot.throw_chance);
+    hasher.write_option_u64(bot.respawn.map(|mass| mass as u64));
+}
+
+fn hash_characteristics(characteristics: &EntityCharacteristics, hasher: &mut StateHasher) {
+    hasher.write_bools(&[
+        characteristics.killer,
+        characteristics.collide,
+        characteristics.collide_when_mergeable,
+        characteristics.mergeable,
+        characteristics.affected_by_gravity,
+        characteristics.bounce,
+        characteristics.can_split_on_kill,
+        characteristics.invincible,
+    ]);
+    hasher.write_u64(match characteristics.edge { EdgePolicy::Map => 0, EdgePolicy::Despawn => 1 });
+    hasher.write_i32(characteristics.inertia);
+    hasher.write_u64(characteristics.mass_min as u64);
+    hasher.write_u64(characteristics.mass_max as u64);
+    hasher.write_option_f32(characteristics.mass_evolution);
+    match &characteristics.on_death {
+        Some(on_death) => {
+            hasher.write_u64(1);
+            hash_on_death(on_death, hasher);
+        }
+        None => hasher.write_u64(0),
+    }
+    match &characteristics.gravity {
+        Some(gravity) => {
+            hasher.write_u64(1);
+            hasher.write_f32(gravity.power);
+            hasher.write_u64(match gravity.distance_ratio { DistanceRatio::Linear => 0, DistanceRatio::Squared => 1 });
+            for range in [&gravity.distance_limit, &gravity.distance_clamp, &gravity.speed_limit, &gravity.speed_clamp].iter() {
+                hasher.write_f32(range.start);
+                hasher.write_f32(range.end);
+            }
+        }
+        None => hasher.write_u64(0),
+    }
+    match &characteristics.throw_entity {
+        Some(throw) => {
+            hasher.write_u64(1);
+            hash_throw(throw, hasher);
+        }
+        None => hasher.write_u64(0),
+    }
+    match &characteristics.virus {
+        Some(virus) => {
+            hasher.write_u64(1);
+            hasher.write_u64(virus.pieces as u64);
+            hasher.write_u64(virus.feed_mass_max as u64);
+            hasher.write_u64(virus.fire_mass as u64);
+            hasher.write_f32(virus.fire_speed);
+        }
+        None => hasher.write_u64(0),
+    }
+    hasher.write_u64(characteristics.special.len() as u64);
+    for special in characteristics.special.iter() {
+        match special {
+            EntitySpecial::WASM(name) => hasher.write_bytes(name.as_bytes()),
+        }
+    }
+}
+
+fn hash_throw(throw: &ThrowEntityInfo, hasher: &mut StateHasher) {
+    hasher.write_u64(throw.mass_minimum_to_throw as u64);
+    hasher.write_u64(throw.mass_self_added as u64);
+    hasher.write_u64(throw.mass_entity_thrown as u64);
+    hasher.write_f32(throw.throw_ratio);
+    hasher.write_f32(throw.direction.start);
+    hasher.write_f32(throw.direction.end);
+    hasher.write_i32(throw.power.start);
+    hasher.write_i32(throw.power.end);
+    hash_thrown_look(&throw.color, &throw.texture, hasher);
+    hash_timer(&throw.timer_entity_thrown, hasher);
+    hash_thrown_characteristics(&throw.characteristics_entity_thrown, hasher);
+}
+
+fn hash_thrown_characteristics(characteristics: &ThrownEntityCharacteristics, hasher: &mut StateHasher) {
+    match characteristics {
+        ThrownEntityCharacteristics::Same => hasher.write_u64(0),
+        ThrownEntityCharacteristics::Custom(characteristics) => {
+            hasher.write_u64(1);
+            hash_characteristics(characteristics, hasher);
+        }
+        ThrownEntityCharacteristics::CustomIndex(index) => {
+            hasher.write_u64(2);
+            hasher.write_u64(*index as u64);
+        }
+    }
+}
+
+// Colors and textures aren't simulated, only their random choices draw from the rng
+fn hash_thrown_look(color: &ThrownEntityColor, texture: &ThrownEntityTexture, hasher: &mut StateHasher) {
+    hasher.write_u64(match color { ThrownEntityColor::Same => 0, ThrownEntityColor::Custom(_) => 1, ThrownEntityColor::Random(colors) => colors.len() as u64 + 2 });
+    hasher.write_u64(match texture { ThrownEntityTexture::Same => 0, ThrownEntityTexture::CustomIndex(_) => 1, ThrownEntityTexture::Random(textures) => textures.len() as u64 + 2 });
+}
+
+fn hash_on_death(on_death: &OnDeathEffect, hasher: &mut StateHasher) {
+    match on_death {
+        OnDeathEffect::Split(pieces) => {
+            hasher.write_u64(0);
+            hasher.write_u64(*pieces as u64);
+        }
+        OnDeathEffect::Spawn(info) => {
+            hasher.write_u64(1);
+            hasher.write_u64(info.entities.len() as u64);
+            for template in info.entities.iter() {
+                hash_entity_info(template, hasher);
+            }
+            hasher.write_u64(info.amount as u64);
+            match info.speed {
+                DeathSpeed::Radial(speed) => {
+                    hasher.write_u64(0);
+                    hasher.write_f32(speed);
+                }
+                DeathSpeed::Directional(angle, speed) => {
+                    hasher.write_u64(1);
+                    hasher.write_f32(angle);
+                    hasher.write_f32(speed);
+                }
+                DeathSpeed::Same => hasher.write_u64(2),
+            }
+        }
+        OnDeathEffect::Explode(info) => {
+            hasher.write_u64(2);
+            hasher.write_u64(info.particles as u64);
+            hasher.write_f32(info.mass_ratio);
+            hasher.write_f32(info.speed.start);
+            hasher.write_f32(info.speed.end);
+            hasher.write_i32(info.lifetime_left);
+            hash_thrown_look(&info.color, &info.texture, hasher);
+            hash_thrown_characteristics(&info.characteristics, hasher);
+        }
+        OnDeathEffect::TransferMass(info) => {
+            hasher.write_u64(3);
+            hasher.write_f32(info.ratio);
+            match info.target {
+                MassTransferTarget::Killer => hasher.write_u64(0),
+                MassTransferTarget::Nearby(radius) => {
+                    hasher.write_u64(1);
+                    hasher.write_i32(radius);
+                }
+            }
+        }
+        OnDeathEffect::Respawn(info) => {
+            hasher.write_u64(4);
+            hasher.write_u64(info.delay);
+            match info.position {
+                Some(position) => {
+                    hasher.write_u64(1);
+                    hasher.write_i32(position.x);
+                    hasher.write_i32(position.y);
+                }
+                None => hasher.write_u64(0),
+            }
+            hasher.write_option_u64(info.mass.map(|mass| mass as u64));
+            hash_timer(&info.timer, hasher);
+            hasher.write_option_u64(info.lives.map(|lives| lives as u64));
+        }
+        OnDeathEffect::Multiple(effects) => {
+            hasher.write_u64(5);
+            hasher.write_u64(effects.len() as u64);
+            for effect in effects.iter() {
+                hash_on_death(effect, hasher);
+            }
+        }
+    }
+}
+
+fn hash_entity_info(info: &EntityInfo, hasher: &mut StateHasher) {
+    hasher.write_u64(info.player as u64);
+    hasher.write_i32(info.position.x);
+    hasher.write_i32(info.position.y);
+    hasher.write_f32(info.speed.x);
+    hasher.write_f32(info.speed.y);
+    hasher.write_u64(info.mass as u64);
+    hash_timer(&info.timer, hasher);
+    hash_characteristics(&info.characteristics, hasher);
+}
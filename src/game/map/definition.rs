@@ -0,0 +1,254 @@
+// Declarative maps (.ron), e.g. maps/premade/classic.ron
+//
+// Only the fields changed from the default are needed. Entities are templates placed by a list of positions:
+// (
+//     size: (100, 100),
+//     settings: (max_cells_spawn: 20000),
+//     entities: [
+//         (player: 1, positions: [Ratio(0.5, 0.5)]), // local player, settings.local_player_characteristics
+//         (player: 0, positions: [Random(10)], mass: 200000000, characteristics: Some((killer: true))),
+//         (player: 0, positions: [Image((path: "pixel art.png", spacing: 2000, skip: 1))]), // options in image_import.rs
+//     ],
+//     generator: [(spawn: (amount: 5000, position: Distribution(Noise((scale: 0.05)))))], // see generator.rs
+//     bots: [(amount: 5), (amount: 2, settings: (reaction: 3, split_chance: 0.5))], // see bot.rs
+//     // with settings: (teams: [(name: "blue", color: Some(...)), (name: "red")]), see TeamSettings in player.rs:
+//     // bots: [(amount: 3, team: Some(0)), (amount: 4, team: Some(1))],
+//     walls: [Segment((0, 300000), (500000, 300000), 10000), Polygon([(600000, 600000), (700000, 600000), (650000, 700000)])],
+//     topology: Torus, // or Rectangle (default) or Circle, see MapTopology in map/mod.rs
+// )
+
+use crate::game::Game;
+use crate::game::bot::{self, BotSettings};
+use crate::game::entity::{EntityCharacteristics, EntityColor, EntityInfo, EntityTimer, RATIO_MASS};
+use crate::game::map::{Map, MapInfo, MapTopology};
+use crate::game::map::generator::{self, GeneratorLayer};
+use crate::game::map::image_import::ImageImport;
+use crate::game::map::walls::Wall;
+use crate::game::player::{PlayerInfo, PlayerKind};
+use crate::game::rng::RngStream;
+use crate::game::settings::Settings;
+use crate::game::texture::{TextureRegistry, TextureRemap};
+
+use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+pub const DEFINITION_EXTENSION: &str = ".ron";
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct MapDefinition {
+    pub size: Size2D<i32>,
+    pub textures: Vec<String>, // Names of the texture indices, empty for the default textures
+    pub players: Vec<PlayerInfo>,
+    pub settings: Settings,
+    pub entities: Vec<EntityDefinition>,
+    pub generator: Vec<GeneratorLayer>, // Placed after the entities
+    pub bots: Vec<BotsDefinition>, // Added after the generator, as new players
+    pub walls: Vec<Wall>, // Positions in the world, see walls.rs
+    pub topology: MapTopology,
+}
+
+impl Default for MapDefinition {
+    fn default() -> MapDefinition {
+        MapDefinition {
+            size: Size2D::new(100, 100),
+            textures: Vec::new(),
+            players: vec![
+                PlayerInfo {
+                    kind: PlayerKind::Neutral,
+                    ..Default::default()
+                },
+                PlayerInfo {
+                    kind: PlayerKind::Player,
+                    cell_default_color: crate::game::settings::DEFAULT_COLOR[14],
+                    cell_default_texture: 14,
+                    ..Default::default()
+                },
+            ],
+            settings: Settings::default(),
+            entities: Vec::new(),
+            generator: Vec::new(),
+            bots: Vec::new(),
+            walls: Vec::new(),
+            topology: Default::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct EntityDefinition {
+    pub player: usize,
+    pub positions: Vec<Placement>, // One entity for each position
+    pub speed: Vector2D<f32>,
+    pub mass: i64,
+    pub color: Option<EntityColor>, // None: cell_default_color of the player
+    pub texture: Option<usize>, // None: cell_default_texture of the player
+    pub timer: EntityTimer,
+    pub characteristics: Option<EntityCharacteristics>, // None: settings.local_player_characteristics
+}
+
+impl Default for EntityDefinition {
+    fn default() -> EntityDefinition {
+        EntityDefinition {
+            player: 0,
+            positions: Vec::new(),
+            speed: Vector2D::zero(),
+            mass: RATIO_MASS * 10,
+            color: None,
+            texture: None,
+            timer: EntityTimer::default(),
+            characteristics: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct BotsDefinition {
+    pub amount: usize, // One player with one cell for each bot
+    pub mass: i64,
+    pub settings: BotSettings,
+    pub team: Option<usize>, // Index in settings.teams
+}
+
+impl Default for BotsDefinition {
+    fn default() -> BotsDefinition {
+        BotsDefinition {
+            amount: 0,
+            mass: RATIO_MASS * 10,
+            settings: BotSettings::default(),
+            team: None,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub enum Placement {
+    At(i32, i32), // Position in the world
+    Ratio(f32, f32), // Ratio of the size of the map, (0.5, 0.5) is the center
+    Random(usize), // Amount of entities anywhere on the map, the ones in a wall or out of a circular map are skipped
+    Line { from: (f32, f32), to: (f32, f32), amount: usize }, // Ratios, `to` excluded
+    Image(ImageImport), // One entity per pixel, with the color of the pixel
+}
+
+impl MapDefinition {
+    pub fn from_file(path: &str) -> Result<MapDefinition, String> {
+        let data = std::fs::read(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+        ron::de::from_bytes(&data).map_err(|err| format!("can't load {}: {}", path, err))
+    }
+
+    pub fn resolve_textures(&mut self, registry: &TextureRegistry) -> Result<(), String> {
+        let names = std::mem::take(&mut self.textures);
+        let mut remap = TextureRemap::new(&names, registry);
+        let mut f = |index: usize| remap.get(index);
+        self.settings.map_textures(&mut f);
+        for player in self.players.iter_mut() {
+            player.map_textures(&mut f);
+        }
+        for entity in self.entities.iter_mut() {
+            if let Some(texture) = entity.texture.as_mut() { *texture = f(*texture); }
+            if let Some(characteristics) = entity.characteristics.as_mut() { characteristics.map_textures(&mut f); }
+            for placement in entity.positions.iter_mut() {
+                if let Placement::Image(import) = placement { import.map_textures(&mut f); }
+            }
+        }
+        for layer in self.generator.iter_mut() {
+            layer.spawn.map_textures(&mut f);
+        }
+        remap.finish()?;
+        self.textures = registry.names().to_vec();
+        Ok(())
+    }
+
+    // Replaces the map of the game, which is only cleared once everything that can fail was checked
+    pub fn create(mut self, game: &mut Game) -> Result<(), String> {
+        self.resolve_textures(&game.textures)?;
+        if self.players.is_empty() { return Err("no player".to_owned()) }
+        for entity in self.entities.iter() {
+            if entity.player >= self.players.len() {
+                return Err(format!("entity of player {} but only {} players", entity.player, self.players.len()))
+            }
+        }
+        let teams = self.settings.teams.len();
+        let player_teams = self.players.iter().map(|player| player.team);
+        if let Some(team) = player_teams.chain(self.bots.iter().map(|bots| bots.team)).flatten().find(|team| *team >= teams) {
+            return Err(format!("team {} but only {} teams", team, teams))
+        }
+        let images = self.entities.iter().flat_map(|entity| entity.positions.iter())
+            .filter_map(|placement| match placement { Placement::Image(import) => Some(import.open()), _ => None })
+            .collect::<Result<Vec<DynamicImage>, String>>()?;
+        let mut images = images.iter();
+
+        game.clear();
+
+        game.map = Map::new(MapInfo {
+            size: self.size,
+            walls: std::mem::take(&mut self.walls),
+            topology: self.topology,
+        });
+        game.settings = self.settings;
+        for player in self.players.into_iter() {
+            game.new_player(player);
+        }
+
+        let mut rng = game.rng.stream(RngStream::Map, 0);
+        for entity in self.entities.iter() {
+            let info = EntityInfo {
+                player: entity.player,
+                position: Point2D::zero(),
+                speed: entity.speed,
+                mass: entity.mass,
+                color: entity.color.unwrap_or(game.players[entity.player].cell_default_color),
+                texture: entity.texture.unwrap_or(game.players[entity.player].cell_default_texture),
+                timer: entity.timer.clone(),
+                characteristics: entity.characteristics.clone().unwrap_or_else(|| game.settings.local_player_characteristics.clone()),
+            };
+            for placement in entity.positions.iter() {
+                for placed in placement.entities(game, &mut rng, &info, &mut images) {
+                    game.new_entity(placed);
+                }
+            }
+        }
+        generator::generate(game, &self.generator);
+        for bots in self.bots.iter_mut() {
+            bots.settings.validate();
+            bot::add_bots(game, bots.amount, &bots.settings, bots.mass, bots.team);
+        }
+        Ok(())
+    }
+}
+
+impl Placement {
+    // The entities placed from `info`, images also change the color (and more with their options).
+    // `images`: the images of the Image placements opened beforehand, in order
+    fn entities<'a>(&self, game: &Game, rng: &mut impl rand::Rng, info: &EntityInfo, images: &mut impl Iterator<Item = &'a DynamicImage>) -> Vec<EntityInfo> {
+        let max = game.map.max();
+        let ratio = |x: f32, y: f32| Point2D::new((max.width as f32 * x) as i32, (max.height as f32 * y) as i32);
+        let positions = match self {
+            Placement::At(x, y) => vec![Point2D::new(*x, *y)],
+            Placement::Ratio(x, y) => vec![ratio(*x, *y)],
+            Placement::Random(amount) => {
+                let size = game.map.size * game.map.size_field;
+                (0..*amount).map(|_| Point2D::new(rng.gen_range(0..size.width), rng.gen_range(0..size.height)))
+                    .filter(|position| game.map.contains(*position) && !game.map.walls.contains(*position))
+                    .collect()
+            }
+            Placement::Line { from, to, amount } => {
+                (0..*amount).map(|i| {
+                    let t = i as f32 / *amount as f32;
+                    ratio(from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t)
+                }).collect()
+            }
+            Placement::Image(import) => {
+                let image = images.next().expect("image opened by create");
+                let mut entities = import.entities_from_image(image, info);
+                let bounds = Rect::new(Point2D::zero(), game.map.size * game.map.size_field);
+                entities.retain(|entity| bounds.contains(entity.position));
+                return entities
+            }
+        };
+        positions.into_iter().map(|position| EntityInfo { position, ..info.clone() }).collect()
+    }
+}
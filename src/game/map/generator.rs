@@ -0,0 +1,302 @@
+// Procedural placement: densities over the map, sampled with the rng of the game (same seed, same map).
+// Distributions are used while playing by SpawnPosition::Distribution, and at the creation of a map by `generate`
+// (MapDefinition::generator) which places layers of entities with spacing rules, e.g. food clusters then obstacles.
+// Positions are ratios of the map, lengths are ratios of the smaller side of the map.
+
+use crate::game::Game;
+use crate::game::rng::{mix, RngStream};
+use crate::game::settings::AutoSpawnSettings;
+
+use euclid::default::Point2D;
+use rand::Rng;
+use rustc_hash::FxHashMap;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+const MAX_TRIES: usize = 32; // Tries of the rejection sampling before giving up a position
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone)]
+pub enum Distribution {
+    Uniform,
+    Noise(NoiseField),
+    Rings(RingsField),
+    Spiral(SpiralField),
+    Gradient(GradientField),
+}
+
+// Clusters: value noise, nothing spawns where it is under the threshold
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
+pub struct NoiseField {
+    pub seed: u64, // Mixed with the seed of the game, different seeds for different fields of the same map
+    pub scale: f32, // Size of a cluster
+    pub octaves: u32,
+    pub threshold: f32, // 0.0: everywhere, 1.0: nowhere
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
+pub struct RingsField {
+    pub center: (f32, f32),
+    pub radius: f32, // Radius of the first ring
+    pub gap: f32, // Between two rings
+    pub count: usize,
+    pub width: f32,
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
+pub struct SpiralField {
+    pub center: (f32, f32),
+    pub radius: f32, // End of the arms
+    pub arms: usize,
+    pub turns: f32,
+    pub width: f32,
+}
+
+// Density going linearly from `density_from` to `density_to` along the segment, constant after its ends
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
+pub struct GradientField {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub density_from: f32,
+    pub density_to: f32,
+}
+
+impl Default for NoiseField {
+    fn default() -> NoiseField {
+        NoiseField {
+            seed: 0,
+            scale: 0.1,
+            octaves: 3,
+            threshold: 0.5,
+        }
+    }
+}
+
+impl Default for RingsField {
+    fn default() -> RingsField {
+        RingsField {
+            center: (0.5, 0.5),
+            radius: 0.1,
+            gap: 0.1,
+            count: 3,
+            width: 0.02,
+        }
+    }
+}
+
+impl Default for SpiralField {
+    fn default() -> SpiralField {
+        SpiralField {
+            center: (0.5, 0.5),
+            radius: 0.45,
+            arms: 2,
+            turns: 1.5,
+            width: 0.03,
+        }
+    }
+}
+
+impl Default for GradientField {
+    fn default() -> GradientField {
+        GradientField {
+            from: (0.0, 0.5),
+            to: (1.0, 0.5),
+            density_from: 1.0,
+            density_to: 0.0,
+        }
+    }
+}
+
+impl Distribution {
+    pub fn validate(&mut self) {
+        match self {
+            Distribution::Uniform => {}
+            Distribution::Noise(field) => {
+                field.scale = field.scale.max(0.001);
+                field.octaves = field.octaves.max(1).min(8);
+                field.threshold = field.threshold.max(0.0).min(0.99);
+            }
+            Distribution::Rings(field) => {
+                field.radius = field.radius.max(0.0);
+                field.gap = field.gap.max(0.0);
+                field.count = field.count.max(1);
+                field.width = field.width.max(0.0);
+            }
+            Distribution::Spiral(field) => {
+                field.radius = field.radius.max(0.0);
+                field.arms = field.arms.max(1);
+                field.width = field.width.max(0.0);
+            }
+            Distribution::Gradient(field) => {
+                field.density_from = field.density_from.max(0.0).min(1.0);
+                field.density_to = field.density_to.max(0.0).min(1.0);
+            }
+        }
+    }
+
+    // None when no position was found inside the map (sparse density, or outside of the map)
+    pub fn sample(&self, game: &Game, rng: &mut impl Rng) -> Option<Point2D<i32>> {
+        let max = game.map.max();
+        let size = (max.width as f32, max.height as f32);
+        let side = size.0.min(size.1);
+        let world = |x: f32, y: f32| {
+            if x < 0.0 || y < 0.0 || x > size.0 || y > size.1 { return None }
+            Some(Point2D::new(x as i32, y as i32))
+        };
+        let polar = |center: (f32, f32), radius: f32, angle: f32| {
+            world(center.0 * size.0 + angle.cos() * radius * side, center.1 * size.1 + angle.sin() * radius * side)
+        };
+
+        match self {
+            Distribution::Uniform => world(rng.gen_range(0.0..=size.0), rng.gen_range(0.0..=size.1)),
+            Distribution::Noise(field) => {
+                let seed = mix(game.rng.seed ^ field.seed);
+                for _ in 0..MAX_TRIES {
+                    let (x, y) = (rng.gen_range(0.0..=size.0), rng.gen_range(0.0..=size.1));
+                    if rng.gen::<f32>() < field.density(seed, x / side, y / side) { return world(x, y) }
+                }
+                None
+            }
+            Distribution::Rings(field) => {
+                // Rings weighted by their length, for the same density on each ring
+                let radius = |ring: usize| field.radius + field.gap * ring as f32;
+                let total: f32 = (0..field.count).map(radius).sum();
+                // Rings of no length (or not validated fields): the first one
+                let ring = if total > 0.0 && total.is_finite() {
+                    let mut choice = rng.gen_range(0.0..=total);
+                    (0..field.count).find(|ring| { choice -= radius(*ring); choice <= 0.0 }).unwrap_or(0)
+                } else {
+                    0
+                };
+                let radius = radius(ring) + rng.gen_range(-0.5f32..=0.5) * field.width;
+                polar(field.center, radius, rng.gen_range(0.0..std::f32::consts::PI * 2.0))
+            }
+            Distribution::Spiral(field) => {
+                let arms = field.arms.max(1);
+                let arm = rng.gen_range(0..arms);
+                let t: f32 = rng.gen();
+                let angle = std::f32::consts::PI * 2.0 * (arm as f32 / arms as f32 + t * field.turns);
+                let radius = field.radius * t + rng.gen_range(-0.5f32..=0.5) * field.width;
+                polar(field.center, radius, angle)
+            }
+            Distribution::Gradient(field) => {
+                let direction = (field.to.0 - field.from.0, field.to.1 - field.from.1);
+                let length = (direction.0 * direction.0 + direction.1 * direction.1).max(f32::EPSILON);
+                for _ in 0..MAX_TRIES {
+                    let (x, y) = (rng.gen::<f32>(), rng.gen::<f32>());
+                    let t = (((x - field.from.0) * direction.0 + (y - field.from.1) * direction.1) / length).max(0.0).min(1.0);
+                    let density = field.density_from + (field.density_to - field.density_from) * t;
+                    if rng.gen::<f32>() < density { return world(x * size.0, y * size.1) }
+                }
+                None
+            }
+        }
+    }
+}
+
+impl NoiseField {
+    fn density(&self, seed: u64, x: f32, y: f32) -> f32 {
+        let mut value = 0.0;
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0 / self.scale;
+        for octave in 0..self.octaves {
+            value += amplitude * value_noise(seed.wrapping_add(octave as u64), x * frequency, y * frequency);
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        ((value / total - self.threshold) / (1.0 - self.threshold)).max(0.0)
+    }
+}
+
+// Random values in 0..1 on the corners of a grid, smoothly interpolated between them
+fn value_noise(seed: u64, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (i, j) = (x0 as i64, y0 as i64);
+    let corner = |i: i64, j: i64| (mix(seed ^ mix(i as u64 ^ mix(j as u64))) >> 40) as f32 / (1u64 << 24) as f32;
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let (tx, ty) = (smooth(x - x0), smooth(y - y0));
+    lerp(lerp(corner(i, j), corner(i + 1, j), tx), lerp(corner(i, j + 1), corner(i + 1, j + 1), tx), ty)
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, Default)]
+pub struct GeneratorLayer {
+    pub spawn: AutoSpawnSettings, // `amount` entities, placed at `position`. The fields for spawning while playing (every, max...) are ignored
+    pub spacing: i32, // Minimal distance to the entities of every layer with a spacing, 0: no rule
+}
+
+// Places the layers in order. A layer stops early when its positions are too often rejected by the spacing
+pub fn generate(game: &mut Game, layers: &[GeneratorLayer]) {
+    let mut rng = game.rng.stream(RngStream::Map, 1);
+    let mut spaced = SpacingGrid::new(layers.iter().map(|layer| layer.spacing).max().unwrap_or(0));
+    for layer in layers.iter() {
+        let mut placed = 0;
+        let mut tries = 0;
+        while placed < layer.spawn.amount && tries < layer.spawn.amount * MAX_TRIES {
+            tries += 1;
+            let info = match layer.spawn.entity(game, &mut rng) {
+                Some(info) => info,
+                None => continue,
+            };
+            if layer.spacing > 0 {
+                if !spaced.is_free(info.position, layer.spacing) { continue }
+                spaced.insert(info.position);
+            }
+            game.new_entity(info);
+            placed += 1;
+        }
+    }
+}
+
+// Positions of the spaced entities, by cells of the largest spacing
+struct SpacingGrid {
+    cell: i32,
+    cells: FxHashMap<(i32, i32), Vec<Point2D<i32>>>,
+}
+
+impl SpacingGrid {
+    fn new(cell: i32) -> SpacingGrid {
+        SpacingGrid {
+            cell: cell.max(1),
+            cells: FxHashMap::default(),
+        }
+    }
+
+    fn key(&self, position: Point2D<i32>) -> (i32, i32) {
+        (position.x / self.cell, position.y / self.cell)
+    }
+
+    fn is_free(&self, position: Point2D<i32>, spacing: i32) -> bool {
+        let (x, y) = self.key(position);
+        let spacing = spacing as i64;
+        for i in x - 1..=x + 1 {
+            for j in y - 1..=y + 1 {
+                if let Some(positions) = self.cells.get(&(i, j)) {
+                    let too_close = positions.iter().any(|other| {
+                        let (dx, dy) = ((other.x - position.x) as i64, (other.y - position.y) as i64);
+                        dx * dx + dy * dy < spacing * spacing
+                    });
+                    if too_close { return false }
+                }
+            }
+        }
+        true
+    }
+
+    fn insert(&mut self, position: Point2D<i32>) {
+        let key = self.key(position);
+        self.cells.entry(key).or_insert_with(Vec::new).push(position);
+    }
+}
@@ -0,0 +1,222 @@
+// Entities from an image: one entity per kept pixel, with the color of the pixel.
+// Used by Placement::Image of map definitions, the "Import" tab of the editor and `cells-sim --import-image`
+
+use crate::game::Game;
+use crate::game::entity::{EntityCharacteristics, EntityColor, EntityInfo, EntityTimer, RATIO_MASS};
+use crate::game::map::{Map, MapInfo, RATIO_POSITION};
+
+use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use image::{DynamicImage, RgbaImage};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
+pub struct ImageImport {
+    pub path: String,
+    pub origin: Point2D<i32>, // Position of the top left pixel
+    pub scale: f32, // The image is resized by this factor before the import
+    pub spacing: i32, // Distance between two pixels in the world
+    pub skip: u32, // Pixels skipped after each kept pixel, on both axes. The kept pixels stay at their place
+    pub alpha_threshold: u8, // Pixels with a lower alpha are ignored
+    pub mass: ImageMass,
+    pub templates: Vec<PixelTemplate>, // The first template matching a pixel is used
+    pub only_templates: bool, // Ignore the pixels matching no template
+}
+
+impl Default for ImageImport {
+    fn default() -> ImageImport {
+        ImageImport {
+            path: String::new(),
+            origin: Point2D::zero(),
+            scale: 1.0,
+            spacing: 1_000,
+            skip: 0,
+            alpha_threshold: 1,
+            mass: ImageMass::Same,
+            templates: Vec::new(),
+            only_templates: false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, PartialEq)]
+pub enum ImageMass {
+    Same, // Mass of the base entity
+    Brightness(i64, i64), // Mass of a black pixel, mass of a white pixel
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
+pub struct PixelTemplate {
+    pub pixels: PixelMatch,
+    pub mass: Option<i64>, // None: ImageImport::mass
+    pub color: Option<EntityColor>, // None: color of the pixel
+    pub texture: Option<usize>, // None: texture of the base entity
+    pub characteristics: Option<EntityCharacteristics>, // None: characteristics of the base entity
+}
+
+impl Default for PixelTemplate {
+    fn default() -> PixelTemplate {
+        PixelTemplate {
+            pixels: PixelMatch::Color([0, 0, 0], 0),
+            mass: None,
+            color: None,
+            texture: None,
+            characteristics: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, PartialEq)]
+pub enum PixelMatch {
+    Color([u8; 3], u8), // Color, and tolerance on each channel
+    Brightness(u8, u8), // Range of brightness, both included
+}
+
+impl PixelMatch {
+    fn matches(&self, pixel: [u8; 4]) -> bool {
+        match self {
+            PixelMatch::Color(color, tolerance) => {
+                (0..3).all(|i| (pixel[i] as i32 - color[i] as i32).abs() <= *tolerance as i32)
+            }
+            PixelMatch::Brightness(min, max) => {
+                let brightness = brightness(pixel);
+                brightness >= *min && brightness <= *max
+            }
+        }
+    }
+}
+
+fn brightness(pixel: [u8; 4]) -> u8 {
+    ((pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1_000) as u8
+}
+
+impl ImageImport {
+    pub fn new(path: &str) -> ImageImport {
+        ImageImport {
+            path: path.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn validate(&mut self) {
+        self.scale = self.scale.max(0.01);
+        self.spacing = self.spacing.max(1);
+    }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        for template in self.templates.iter_mut() {
+            if let Some(texture) = template.texture.as_mut() { *texture = f(*texture); }
+            if let Some(characteristics) = template.characteristics.as_mut() { characteristics.map_textures(f); }
+        }
+    }
+
+    pub fn open(&self) -> Result<DynamicImage, String> {
+        image::open(&self.path).map_err(|err| format!("can't load image {}: {}", self.path, err))
+    }
+
+    // The entities of the pixels, built from `base` (player, speed, mass, texture, timer and characteristics)
+    pub fn entities(&self, base: &EntityInfo) -> Result<Vec<EntityInfo>, String> {
+        Ok(self.entities_from_image(&self.open()?, base))
+    }
+
+    pub fn entities_from_image(&self, image: &DynamicImage, base: &EntityInfo) -> Vec<EntityInfo> {
+        let image = self.scaled(image);
+        let step = self.skip + 1;
+        image.enumerate_pixels().filter(|(x, y, _)| x % step == 0 && y % step == 0).filter_map(|(x, y, pixel)| {
+            let pixel = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            if pixel[3] < self.alpha_threshold { return None }
+
+            let template = self.templates.iter().find(|template| template.pixels.matches(pixel));
+            if template.is_none() && self.only_templates { return None }
+
+            let mass = match self.mass {
+                ImageMass::Same => base.mass,
+                ImageMass::Brightness(dark, bright) => dark + (bright - dark) * brightness(pixel) as i64 / 255,
+            };
+            let mut info = EntityInfo {
+                position: self.origin + Vector2D::new(x as i32, y as i32) * self.spacing,
+                mass,
+                color: EntityColor { center: pixel, edge: pixel },
+                ..base.clone()
+            };
+            if let Some(template) = template {
+                if let Some(mass) = template.mass { info.mass = mass; }
+                if let Some(color) = template.color { info.color = color; }
+                if let Some(texture) = template.texture { info.texture = texture; }
+                if let Some(characteristics) = &template.characteristics { info.characteristics = characteristics.clone(); }
+            }
+            Some(info)
+        }).collect()
+    }
+
+    // Adds the entities of the pixels inside the map, returns the amount added
+    pub fn add_entities(&self, game: &mut Game, image: &DynamicImage, base: &EntityInfo) -> usize {
+        let bounds = Rect::new(Point2D::zero(), game.map.size * game.map.size_field);
+        let mut added = 0;
+        for info in self.entities_from_image(image, base) {
+            if !bounds.contains(info.position) { continue }
+            game.new_entity(info);
+            added += 1;
+        }
+        added
+    }
+
+    // Size of the image in the world, from the origin
+    pub fn world_size(&self, image: &DynamicImage) -> Size2D<i32> {
+        let image = self.scaled(image);
+        Size2D::new(image.width() as i32, image.height() as i32) * self.spacing
+    }
+
+    fn scaled(&self, image: &DynamicImage) -> RgbaImage {
+        let image = image.to_rgba8();
+        if self.scale == 1.0 { return image }
+        let width = ((image.width() as f32 * self.scale).round() as u32).max(1);
+        let height = ((image.height() as f32 * self.scale).round() as u32).max(1);
+        image::imageops::resize(&image, width, height, image::imageops::FilterType::Nearest)
+    }
+
+    // New map fitting the image, with the pixels as neutral entities and the local player in the center
+    pub fn create_map(&self, game: &mut Game) -> Result<(), String> {
+        let image = self.open()?;
+        let size = (self.world_size(&image) + Size2D::new(self.origin.x, self.origin.y)) / RATIO_POSITION + Size2D::new(1, 1);
+
+        game.clear();
+        game.map = Map::new(MapInfo {
+            size,
+            walls: Vec::new(),
+            topology: Default::default(),
+        });
+        super::premade::helper_base(game);
+        game.settings.max_cells_spawn = 0;
+
+        let base = EntityInfo {
+            player: 0,
+            position: Point2D::zero(),
+            speed: Vector2D::zero(),
+            mass: RATIO_MASS,
+            color: EntityColor::default(),
+            texture: 0,
+            timer: EntityTimer::default(),
+            characteristics: EntityCharacteristics::default(),
+        };
+        self.add_entities(game, &image, &base);
+        let max = game.map.max();
+        game.new_entity(EntityInfo {
+            player: 1,
+            position: Point2D::new(max.width / 2, max.height / 2),
+            mass: RATIO_MASS * 10,
+            color: game.players[1].cell_default_color,
+            texture: game.players[1].cell_default_texture,
+            characteristics: game.settings.local_player_characteristics.clone(),
+            ..base
+        });
+        game.step.changed_map = true;
+        Ok(())
+    }
+}
@@ -39,6 +39,8 @@ macro_trait_impl::index!(MatrixPhysicsCell, inner, MatrixPhysicsCellContent);
 pub struct MatrixPhysics {
     index: i32,
     size_field: i32,
+    size_total: Size2D<i32>,
+    wrap: bool, // Neighbour queries continue on the other side of the map
     inner: Vec<Vec<MatrixPhysicsCell>>,
 }
 
@@ -61,6 +63,8 @@ impl MatrixPhysics {
         MatrixPhysics {
             index: index,
             size_field: size_field,
+            size_total,
+            wrap: false,
             inner: matrix,
         }
     }
@@ -72,6 +76,18 @@ impl MatrixPhysics {
         let mut list_index = Vec::new();
         let size_field = self.size_field;
 
+        if self.wrap {
+            let rect = entities.get_rect(entity);
+            let xs = super::fields_between(rect.min_x(), rect.max_x(), self.size_total.width, size_field, true);
+            let ys = super::fields_between(rect.min_y(), rect.max_y(), self.size_total.height, size_field, true);
+            for x in xs.iter() {
+                for y in ys.iter() {
+                    list_index.push((*x, *y));
+                }
+            }
+            return list_index
+        }
+
         let x_field = (entity_position.x / size_field) as usize;
         let y_field = (entity_position.y / size_field) as usize;
 
@@ -161,9 +177,14 @@ impl MapPhysics {
         self.count
     }
 
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.matrix_list.iter_mut().for_each(|matrix| matrix.wrap = wrap);
+    }
+
     pub fn rebuild(&mut self, game: &mut Game, size_total: Size2D<i32>, size_field_initial: i32, size_field_ratio: i32, count: i32) {
         if self.size_field_initial == size_field_initial && self.size_field_ratio == size_field_ratio && self.count == count { return }
         let mut new = Self::new(size_total, size_field_initial, size_field_ratio, count);
+        new.set_wrap(self.matrix_list.first().map_or(false, |matrix| matrix.wrap));
         let entities = unsafe { &*(&game.entities as *const Entities) };
         for entity in game.entities.core.iter_mut() {
             if entity.characteristics.collide {
@@ -32,6 +32,8 @@ pub struct MatrixSimple {
     inner: Vec<Vec<MatrixSimpleCell>>,
     pub size: Size2D<i32>,
     pub size_field: i32,
+    size_total: Size2D<i32>,
+    wrap: bool, // Neighbour queries continue on the other side of the map
 }
 
 macro_trait_impl::index!(MatrixSimple, inner, Vec<MatrixSimpleCell>);
@@ -57,12 +59,19 @@ impl MatrixSimple {
             inner: matrix,
             size,
             size_field,
+            size_total,
+            wrap: false,
         }
     }
 
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
     pub fn rebuild(&mut self, game: &mut Game, size_total: Size2D<i32>, size_field: i32) {
         if self.size_field == size_field { return }
         let mut new = Self::new(size_total, size_field);
+        new.set_wrap(self.wrap);
         let entities = unsafe { &mut *(&mut game.entities as *mut Entities) };
         for entity in 0..game.entities.len() {
             self.delete_entity(entities, entity);
@@ -92,6 +101,18 @@ impl MatrixSimple {
         let radius = entities.get_radius(entity);
         let scope_field = (radius / self.size_field as f32) as i32 + 1;
         let mut cells = Vec::new();
+
+        if self.wrap {
+            let rect = entities.get_rect(entity);
+            let xs = super::fields_between(rect.min_x(), rect.max_x(), self.size_total.width, self.size_field, true);
+            let ys = super::fields_between(rect.min_y(), rect.max_y(), self.size_total.height, self.size_field, true);
+            for x in xs.iter() {
+                for y in ys.iter() {
+                    cells.push((*x, *y));
+                }
+            }
+            return cells
+        }
         
         let x_min = (x_field as i32 - scope_field).max(0);
         let x_max = (x_field as i32 + scope_field).min(self.size.width - 1);
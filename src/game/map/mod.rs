@@ -1,9 +1,15 @@
 pub mod premade;
+#[cfg(feature = "serialize")]
+pub mod definition;
+pub mod generator;
+pub mod image_import;
 pub mod matrix_physics;
 pub mod matrix_simple;
+pub mod walls;
 
 use matrix_physics::MapPhysics;
 use matrix_simple::MatrixSimple;
+use walls::{Wall, Walls};
 
 use crate::prelude::*;
 use crate::game::entity::index::MatrixIndex;
@@ -11,7 +17,7 @@ use crate::game::entity::index::IndexMatrixPhysicsInside;
 use crate::game::entity::entities::Entities;
 
 use buffer::BufferMulti;
-use euclid::default::Size2D;
+use euclid::default::{Point2D, Size2D, Vector2D};
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 use std::sync::Weak;
@@ -25,10 +31,28 @@ pub enum MapAction {
     Move(Weak<AtomicUsize>),
 }
 
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum MapTopology {
+    Rectangle, // Borders of the map stop or bounce the entities
+    Torus, // Entities going out on a side come back on the other one, distances wrap
+    Circle, // Round arena inscribed in the map
+}
+
+impl Default for MapTopology {
+    fn default() -> Self {
+        MapTopology::Rectangle
+    }
+}
+
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
 #[derive(Clone)]
 pub struct MapInfo {
     pub size: Size2D<i32>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub walls: Vec<Wall>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub topology: MapTopology,
 }
 
 pub struct Map {
@@ -36,15 +60,20 @@ pub struct Map {
     pub matrix_physics: MapPhysics,
     pub size: Size2D<i32>,
     pub size_field: i32,
+    pub walls: Walls,
+    pub topology: MapTopology,
     pub buffer: BufferMulti<MapAction>,
 }
 
 impl Map {
     pub fn new(info: MapInfo) -> Map {
-        let matrix_simple = MatrixSimple::new(info.size * RATIO_POSITION, RATIO_POSITION);
-        let matrix_physics = MapPhysics::new(info.size * RATIO_POSITION, RATIO_POSITION, 10, 3);
+        let mut matrix_simple = MatrixSimple::new(info.size * RATIO_POSITION, RATIO_POSITION);
+        let mut matrix_physics = MapPhysics::new(info.size * RATIO_POSITION, RATIO_POSITION, 10, 3);
+        matrix_simple.set_wrap(info.topology == MapTopology::Torus);
+        matrix_physics.set_wrap(info.topology == MapTopology::Torus);
         
         let size = info.size;
+        let walls = Walls::new(info.walls, size);
         let buffer = BufferMulti::with_capacity(1, 8);
         
         Map {
@@ -52,10 +81,16 @@ impl Map {
             matrix_physics,
             size,
             size_field: RATIO_POSITION,
+            walls,
+            topology: info.topology,
             buffer,
         }
     }
 
+    pub fn size_total(&self) -> Size2D<i32> {
+        self.size * self.size_field
+    }
+
     pub fn max(&self) -> Size2D<i32> {
         Size2D::new(
             self.size.width * self.size_field - 1,
@@ -63,6 +98,58 @@ impl Map {
         )
     }
     
+    pub fn wrap(&self) -> bool {
+        self.topology == MapTopology::Torus
+    }
+
+    // Shortest vector from `from` to `to`, through the borders on a torus
+    pub fn delta(&self, from: Point2D<i32>, to: Point2D<i32>) -> Vector2D<i32> {
+        let delta = to - from;
+        if !self.wrap() { return delta }
+        let total = self.size_total();
+        let wrap = |delta: i32, total: i32| (delta + total / 2).rem_euclid(total) - total / 2;
+        Vector2D::new(wrap(delta.x, total.width), wrap(delta.y, total.height))
+    }
+
+    pub fn distance(&self, from: Point2D<i32>, to: Point2D<i32>) -> f32 {
+        self.delta(from, to).to_f32().length()
+    }
+
+    // Center and radius of the circular arena
+    pub fn circle(&self) -> (Point2D<i32>, f32) {
+        let max = self.max();
+        (Point2D::new(max.width / 2, max.height / 2), max.width.min(max.height) as f32 / 2.0)
+    }
+
+    // Whether the position is inside the playable area, before being wrapped on a torus
+    pub fn contains(&self, position: Point2D<i32>) -> bool {
+        match self.topology {
+            MapTopology::Rectangle | MapTopology::Torus => {
+                let max = self.max();
+                position.x >= 0 && position.y >= 0 && position.x <= max.width && position.y <= max.height
+            }
+            MapTopology::Circle => {
+                let (center, radius) = self.circle();
+                (position - center).to_f32().length() <= radius
+            }
+        }
+    }
+
+    // Nearest position inside the playable area, wrapped on a torus
+    pub fn confine(&self, position: Point2D<i32>) -> Point2D<i32> {
+        let max = self.max();
+        match self.topology {
+            MapTopology::Rectangle => Point2D::new(position.x.max(0).min(max.width), position.y.max(0).min(max.height)),
+            MapTopology::Torus => Point2D::new(position.x.rem_euclid(max.width + 1), position.y.rem_euclid(max.height + 1)),
+            MapTopology::Circle => {
+                let (center, radius) = self.circle();
+                let offset = (position - center).to_f32();
+                if offset.length() <= radius { return position }
+                center + (offset * (radius / offset.length())).to_i32()
+            }
+        }
+    }
+
     pub fn update_entity_index(&mut self, entities: &Entities, entity: &mut EntityCore) {
         self.matrix_simple.update_entity_index(entities, entity.index.main);
         if entity.characteristics.collide {
@@ -96,4 +183,26 @@ impl Map {
         self.matrix_simple.add_entity_multithread(unsafe { &mut *(entities as *const Entities as *mut Entities) }, entity.index.main);
         if entity.characteristics.collide { self.matrix_physics.add_entity_multithread(entities, entity) };
     }
-}
\ No newline at end of file
+}
+
+// Fields of size `size_field` covering the positions from `min` to `max` on an axis of length `total`.
+// Without wrap, the positions out of the map are ignored, with wrap they continue on the other side
+pub fn fields_between(min: i32, max: i32, total: i32, size_field: i32, wrap: bool) -> Vec<usize> {
+    let last = (total - 1) / size_field;
+    let fields = |min: i32, max: i32| (min.div_euclid(size_field).max(0)..=max.div_euclid(size_field).min(last)).map(|field| field as usize);
+    if !wrap {
+        return fields(min, max).collect()
+    }
+    if max - min + 1 >= total {
+        return fields(0, total - 1).collect()
+    }
+    let (min, max) = (min.rem_euclid(total), max.rem_euclid(total));
+    if min <= max {
+        fields(min, max).collect()
+    } else {
+        let mut list: Vec<usize> = fields(min, total - 1).chain(fields(0, max)).collect();
+        list.sort_unstable();
+        list.dedup();
+        list
+    }
+}
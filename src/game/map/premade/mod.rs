@@ -1,42 +1,13 @@
-mod map_example;
-mod map_classic;
-mod map_1_000_000_cells;
-mod map_exponential;
-mod map_exponential_extreme;
-mod map_trail;
-mod map_gravity;
-mod map_negative_gravity;
-mod map_black_hole;
-mod map_collisions;
-mod map_autonomous;
-mod map_particles;
-mod map_paint;
-mod map_mona_lisa;
-mod map_definitive;
-
-
 use crate::game::Game;
-use crate::game::entity::EntityColor;
 use crate::game::entity::EntityInfo;
 use crate::game::entity::EntityTimer;
-use crate::game::entity::EntityGravityInfo;
 use crate::game::entity::EntityCharacteristics;
-use crate::game::entity::OnDeathEffect;
 use crate::game::entity::RATIO_MASS;
-use crate::game::entity::ThrownEntityCharacteristics;
-use crate::game::entity::ThrowEntityInfo;
-use crate::game::entity::ThrownEntityColor;
-use crate::game::entity::ThrownEntityTexture;
-use crate::game::Map;
-use crate::game::map::RATIO_POSITION;
-use crate::game::MapInfo;
 use crate::game::player::PlayerInfo;
 use crate::game::player::PlayerKind;
-use crate::game::settings::AutoSpawnEntityColor;
-use euclid::default::{Point2D, Size2D, Vector2D};
-
-mod debug_map_collisions;
+use euclid::default::{Point2D, Vector2D};
 
+// Order of the shipped maps of PREMADE_MAPS_FOLDER in the menus, the other maps of the folder come after them
 pub const PREMADE_MAPS: [&str; 15] = [
     "definitive",
     "example",
@@ -55,65 +26,59 @@ pub const PREMADE_MAPS: [&str; 15] = [
     "mona lisa"
 ];
 
-pub fn create_premade_map(game: &mut Game, map_choosen: &str) {
-    game.clear();
-    match map_choosen {
-        /*
-        "example" => {
-            debug_map_collisions::create(game);
-        }
-        */
-        "definitive" => {
-            map_definitive::create(game);
-        }
-        "example" => {
-            map_example::create(game);
-        }
-        "classic" => {
-            map_classic::create(game);
-        }
-        "1_000_000 cells" => {
-            map_1_000_000_cells::create(game);
-        }
-        "exponential" => {
-            map_exponential::create(game);
-        }
-        "exponential extreme" => {
-            map_exponential_extreme::create(game);
-        }
-        "trail" => {
-            map_trail::create(game);
-        }
-        "negative gravity" => {
-            map_negative_gravity::create(game);
-        }
-        "gravity" => {
-            map_gravity::create(game);
-        }
-        "black hole" => {
-            map_black_hole::create(game);
-        }
-        "collisions" => {
-            map_collisions::create(game);
-        }
-        "autonomous" => {
-            map_autonomous::create(game);
-        }
-        "particles" => {
-            map_particles::create(game);
-        }
-        "paint" => {
-            map_paint::create(game);
-        }
-        "mona lisa" => {
-            map_mona_lisa::create(game);
-        }
-        _ => { panic!(); }
+// Maps of this folder (map definitions, .binmap and .ronmap) are the premade maps
+#[cfg(feature = "serialize")]
+pub const PREMADE_MAPS_FOLDER: &str = "maps/premade";
+
+// Built-in map: the local player and the default food, when PREMADE_MAPS_FOLDER is missing (or without the serialize feature)
+pub const FALLBACK_MAP: &str = "sandbox";
+
+// Maps of PREMADE_MAPS_FOLDER, or the fallback map
+pub fn premade_maps() -> Vec<String> {
+    #[cfg(feature = "serialize")]
+    {
+        let mut maps: Vec<String> = premade_map_files().into_iter().map(|(name, _)| name).collect();
+        maps.sort_by_key(|name| PREMADE_MAPS.iter().position(|premade| premade == name).unwrap_or(PREMADE_MAPS.len()));
+        if !maps.is_empty() { return maps }
+    }
+    vec![FALLBACK_MAP.to_owned()]
+}
+
+// Name and path of the maps of PREMADE_MAPS_FOLDER, sorted by name
+#[cfg(feature = "serialize")]
+fn premade_map_files() -> Vec<(String, String)> {
+    let entries = match std::fs::read_dir(PREMADE_MAPS_FOLDER) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut files: Vec<(String, String)> = entries.filter_map(|entry| entry.ok()).filter_map(|entry| {
+        let path = entry.path().to_string_lossy().to_string();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        [crate::game::map::definition::DEFINITION_EXTENSION, ".binmap", ".ronmap"].iter()
+            .find_map(|extension| file_name.strip_suffix(extension))
+            .map(|name| (name.to_owned(), path.clone()))
+    }).collect();
+    files.sort();
+    files
+}
+
+pub fn create_premade_map(game: &mut Game, map_choosen: &str) -> Result<(), String> {
+    #[cfg(feature = "serialize")]
+    if let Some((_, path)) = premade_map_files().into_iter().find(|(name, _)| name == map_choosen) {
+        return game.load_map(&path)
     }
-    crate::APP.get_mut().renderer.camera_future.size = game.settings.camera_initial;
+    if map_choosen != FALLBACK_MAP {
+        return Err(format!("unknown map: {} (premade maps are in maps/premade)", map_choosen))
+    }
+
+    game.clear();
+    helper_base(game);
+    helper_new_entity_center(game);
+    game.step.changed_map = true;
+    Ok(())
 }
 
-fn helper_base(game: &mut Game) {
+pub(crate) fn helper_base(game: &mut Game) {
     game.new_player(PlayerInfo {
         kind: PlayerKind::Neutral,
         entities: Vec::new(),
@@ -124,6 +89,7 @@ fn helper_base(game: &mut Game) {
         entities: Vec::new(),
         cell_default_color: crate::game::settings::DEFAULT_COLOR[14],
         cell_default_texture: 14,
+        ..Default::default()
     });
     
     game.settings.local_player_characteristics = EntityCharacteristics {
@@ -145,10 +111,6 @@ fn helper_new_entity_center(game: &mut Game) {
     helper_new_entity_location(game, Point2D::new(game.map.max().width / 2, game.map.max().height / 2));
 }
 
-fn helper_new_entity_up_left(game: &mut Game) {
-    helper_new_entity_location(game, Point2D::new(0, 0));
-}
-
 fn helper_new_entity_location(game: &mut Game, position: Point2D<i32>) {
     game.new_entity(EntityInfo {
         player: 1,
@@ -0,0 +1,293 @@
+// Static walls of the map: segments with a thickness and convex polygons. Moving entities bounce off or slide along them and can't eat through them
+
+use super::RATIO_POSITION;
+
+use euclid::default::{Point2D, Size2D, Vector2D};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+pub const WALLS_FIELD: i32 = RATIO_POSITION * 10; // Size of a field of the grid of the walls
+const MAX_STEP: f32 = RATIO_POSITION as f32 / 4.0; // Longest move between two checks, below the half of the thinnest wall
+const MAX_STEPS: usize = 64; // Faster entities can go through thin walls
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, PartialEq)]
+pub enum Wall {
+    Segment(Point2D<i32>, Point2D<i32>, i32), // Ends, thickness
+    Polygon(Vec<Point2D<i32>>), // Points of a convex polygon, replaced by their convex hull
+}
+
+impl Wall {
+    pub fn validate(&mut self) {
+        match self {
+            Wall::Segment(_, _, thickness) => {
+                *thickness = (*thickness).max(RATIO_POSITION / 2);
+            }
+            Wall::Polygon(points) => {
+                *points = convex_hull(points);
+            }
+        }
+    }
+
+    // Bounding box (min, max) of the wall, thickness included
+    fn bounds(&self) -> (Point2D<i32>, Point2D<i32>) {
+        match self {
+            Wall::Segment(a, b, thickness) => {
+                let half = thickness / 2 + 1;
+                (a.min(*b) - Vector2D::new(half, half), a.max(*b) + Vector2D::new(half, half))
+            }
+            Wall::Polygon(points) => {
+                let first = points.first().copied().unwrap_or_else(Point2D::zero);
+                points.iter().fold((first, first), |(min, max), point| (min.min(*point), max.max(*point)))
+            }
+        }
+    }
+
+    // Direction to push a circle out of the wall and how far, None if they don't overlap
+    pub fn penetration(&self, center: Point2D<i32>, radius: f32) -> Option<(Vector2D<f32>, f32)> {
+        let center = center.to_f64();
+        match self {
+            Wall::Segment(a, b, thickness) => {
+                let closest = closest_point(center, a.to_f64(), b.to_f64());
+                let reach = radius as f64 + *thickness as f64 / 2.0;
+                let distance = (center - closest).length();
+                if distance >= reach { return None }
+                let normal = if distance > 0.0 {
+                    (center - closest) / distance
+                } else if a != b {
+                    let direction = (b.to_f64() - a.to_f64()).normalize();
+                    Vector2D::new(-direction.y, direction.x)
+                } else {
+                    Vector2D::new(1.0, 0.0)
+                };
+                Some((normal.to_f32(), (reach - distance) as f32))
+            }
+            Wall::Polygon(points) => {
+                if points.len() < 3 { return None }
+                // Counterclockwise after validate: the outward normal of an edge is on its right
+                let mut inside = true;
+                let mut nearest_edge = (Vector2D::zero(), f64::MIN);
+                let mut nearest_point = (Point2D::zero(), f64::MAX);
+                for (a, b) in edges(points) {
+                    let (a, b) = (a.to_f64(), b.to_f64());
+                    let edge = b - a;
+                    let length = edge.length();
+                    if length == 0.0 { continue }
+                    let normal = Vector2D::new(edge.y, -edge.x) / length;
+                    let side = (center - a).dot(normal);
+                    if side > 0.0 { inside = false }
+                    if side > nearest_edge.1 { nearest_edge = (normal, side) }
+                    let closest = closest_point(center, a, b);
+                    let distance = (center - closest).length();
+                    if distance < nearest_point.1 { nearest_point = (closest, distance) }
+                }
+                if inside {
+                    return Some((nearest_edge.0.to_f32(), (radius as f64 - nearest_edge.1) as f32))
+                }
+                let (closest, distance) = nearest_point;
+                if distance >= radius as f64 || distance == 0.0 { return None }
+                Some((((center - closest) / distance).to_f32(), (radius as f64 - distance) as f32))
+            }
+        }
+    }
+
+    // Whether the segment from `a` to `b` goes through the wall
+    pub fn blocks(&self, a: Point2D<i32>, b: Point2D<i32>) -> bool {
+        let (a, b) = (a.to_f64(), b.to_f64());
+        match self {
+            Wall::Segment(start, end, thickness) => {
+                segments_distance(a, b, start.to_f64(), end.to_f64()) < *thickness as f64 / 2.0
+            }
+            Wall::Polygon(points) => {
+                if points.len() < 3 { return false }
+                if self.contains(a.to_i32()) || self.contains(b.to_i32()) { return true }
+                edges(points).any(|(start, end)| segments_distance(a, b, start.to_f64(), end.to_f64()) == 0.0)
+            }
+        }
+    }
+
+    pub fn contains(&self, point: Point2D<i32>) -> bool {
+        self.penetration(point, 0.0).is_some()
+    }
+}
+
+pub struct Walls {
+    pub list: Vec<Wall>,
+    grid: Vec<Vec<usize>>, // Indices in `list` of the walls touching each field
+    size: Size2D<i32>, // Fields of the grid
+}
+
+impl Walls {
+    pub fn new(mut list: Vec<Wall>, map_size: Size2D<i32>) -> Walls {
+        list.iter_mut().for_each(Wall::validate);
+        list.retain(|wall| !matches!(wall, Wall::Polygon(points) if points.len() < 3));
+
+        if list.is_empty() {
+            return Walls { list, grid: Vec::new(), size: Size2D::zero() }
+        }
+        let size = Size2D::new(
+            (map_size.width * RATIO_POSITION) / WALLS_FIELD + 1,
+            (map_size.height * RATIO_POSITION) / WALLS_FIELD + 1,
+        );
+        let mut walls = Walls {
+            list,
+            grid: vec![Vec::new(); (size.width * size.height) as usize],
+            size,
+        };
+        for (index, wall) in walls.list.iter().enumerate() {
+            let (min, max) = wall.bounds();
+            let (x_min, y_min, x_max, y_max) = walls.fields(min, max);
+            for x in x_min..=x_max {
+                for y in y_min..=y_max {
+                    walls.grid[(x * size.height + y) as usize].push(index);
+                }
+            }
+        }
+        walls
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    // Range of fields of the grid covering the box (min, max)
+    fn fields(&self, min: Point2D<i32>, max: Point2D<i32>) -> (i32, i32, i32, i32) {
+        let field = |value: i32, size: i32| value.div_euclid(WALLS_FIELD).max(0).min(size - 1);
+        (
+            field(min.x, self.size.width),
+            field(min.y, self.size.height),
+            field(max.x, self.size.width),
+            field(max.y, self.size.height),
+        )
+    }
+
+    // Calls `f` with the walls near the box (min, max), a wall can be given more than once
+    fn near(&self, min: Point2D<i32>, max: Point2D<i32>, mut f: impl FnMut(&Wall) -> bool) -> bool {
+        if self.list.is_empty() { return false }
+        let (x_min, y_min, x_max, y_max) = self.fields(min, max);
+        for x in x_min..=x_max {
+            for y in y_min..=y_max {
+                for index in self.grid[(x * self.size.height + y) as usize].iter() {
+                    if f(&self.list[*index]) { return true }
+                }
+            }
+        }
+        false
+    }
+
+    // The deepest overlap of a circle with the walls
+    pub fn penetration(&self, center: Point2D<i32>, radius: f32) -> Option<(Vector2D<f32>, f32)> {
+        let reach = Vector2D::new(radius as i32 + 1, radius as i32 + 1);
+        let mut deepest: Option<(Vector2D<f32>, f32)> = None;
+        self.near(center - reach, center + reach, |wall| {
+            if let Some((normal, depth)) = wall.penetration(center, radius) {
+                if deepest.map_or(true, |(_, deepest)| depth > deepest) { deepest = Some((normal, depth)) }
+            }
+            false
+        });
+        deepest
+    }
+
+    pub fn blocks(&self, a: Point2D<i32>, b: Point2D<i32>) -> bool {
+        self.near(a.min(b), a.max(b), |wall| wall.blocks(a, b))
+    }
+
+    pub fn contains(&self, point: Point2D<i32>) -> bool {
+        self.near(point, point, |wall| wall.contains(point))
+    }
+
+    // Moves the circle by `speed` in small steps, pushing it out of the walls. Bouncing reflects the speed, otherwise it slides along the wall
+    pub fn solve(&self, position: &mut Point2D<i32>, speed: &mut Vector2D<f32>, radius: f32, bounce: bool) {
+        if self.list.is_empty() {
+            *position += speed.to_i32();
+            return
+        }
+        let steps = ((speed.length() / MAX_STEP).ceil() as usize).max(1).min(MAX_STEPS);
+        let mut moved = Vector2D::zero();
+        for step in 1..=steps {
+            // Each step goes to a fraction of the whole move, the speed can change on the way
+            let target = (*speed * step as f32 / steps as f32).to_i32();
+            *position += target - moved;
+            moved = target;
+            if self.push_out(position, speed, radius, bounce) {
+                moved = (*speed * step as f32 / steps as f32).to_i32();
+            }
+        }
+    }
+
+    // Pushes the circle out of the walls, true if the speed changed
+    pub fn push_out(&self, position: &mut Point2D<i32>, speed: &mut Vector2D<f32>, radius: f32, bounce: bool) -> bool {
+        let mut changed = false;
+        for _ in 0..2 {
+            let (normal, depth) = match self.penetration(*position, radius) {
+                Some(penetration) => penetration,
+                None => break,
+            };
+            *position += (normal * depth.ceil()).to_i32();
+            let normal_speed = speed.dot(normal);
+            if normal_speed < 0.0 {
+                let factor = if bounce { 2.0 } else { 1.0 };
+                *speed -= normal * normal_speed * factor;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn edges(points: &[Point2D<i32>]) -> impl Iterator<Item = (Point2D<i32>, Point2D<i32>)> + '_ {
+    points.iter().zip(points.iter().cycle().skip(1)).map(|(a, b)| (*a, *b))
+}
+
+fn closest_point(point: Point2D<f64>, a: Point2D<f64>, b: Point2D<f64>) -> Point2D<f64> {
+    let segment = b - a;
+    let length = segment.square_length();
+    if length == 0.0 { return a }
+    let t = ((point - a).dot(segment) / length).max(0.0).min(1.0);
+    a + segment * t
+}
+
+fn cross(a: Vector2D<f64>, b: Vector2D<f64>) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn segments_distance(a: Point2D<f64>, b: Point2D<f64>, c: Point2D<f64>, d: Point2D<f64>) -> f64 {
+    let (d1, d2) = (cross(b - a, c - a), cross(b - a, d - a));
+    let (d3, d4) = (cross(d - c, a - c), cross(d - c, b - c));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 { return 0.0 }
+    [
+        (a - closest_point(a, c, d)).length(),
+        (b - closest_point(b, c, d)).length(),
+        (c - closest_point(c, a, b)).length(),
+        (d - closest_point(d, a, b)).length(),
+    ].iter().fold(f64::MAX, |min, distance| min.min(*distance))
+}
+
+// Counterclockwise convex hull (monotone chain), empty if the points are aligned
+pub fn convex_hull(points: &[Point2D<i32>]) -> Vec<Point2D<i32>> {
+    let mut points = points.to_vec();
+    points.sort_by_key(|point| (point.x, point.y));
+    points.dedup();
+    if points.len() < 3 { return Vec::new() }
+
+    // Lower then upper half, each one ends with the first point of the other
+    let mut hull = half_hull(points.iter());
+    hull.extend(half_hull(points.iter().rev()));
+    if hull.len() < 3 { return Vec::new() }
+    hull
+}
+
+fn half_hull<'a>(points: impl Iterator<Item = &'a Point2D<i32>>) -> Vec<Point2D<i32>> {
+    let turn = |o: Point2D<i32>, a: Point2D<i32>, b: Point2D<i32>| {
+        (a.x as i64 - o.x as i64) * (b.y as i64 - o.y as i64) - (a.y as i64 - o.y as i64) * (b.x as i64 - o.x as i64)
+    };
+    let mut hull: Vec<Point2D<i32>> = Vec::new();
+    for point in points {
+        while hull.len() >= 2 && turn(hull[hull.len() - 2], hull[hull.len() - 1], *point) <= 0 {
+            hull.pop();
+        }
+        hull.push(*point);
+    }
+    hull.pop();
+    hull
+}
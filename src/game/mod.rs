@@ -1,16 +1,22 @@
+pub mod bot;
 pub mod map;
 pub mod player;
 pub mod entity;
-pub mod gui;
+#[cfg(feature = "serialize")]
+pub mod format;
+pub mod hash;
+pub mod mode;
+pub mod replay;
+pub mod rewind;
+pub mod rng;
 pub mod settings;
+pub mod texture;
 
 pub use settings::Settings;
 use map::{Map, MapInfo};
 use player::{Player, PlayerInfo};
-use entity::{EntityCore, Entities, EntityCharacteristics, EntityInfo};
-use gui::Gui;
-
-use crate::window::Window;
+use entity::{EntityCore, EntityFlags, Entities, EntityCharacteristics, EntityInfo, PendingRespawn};
+use rng::GameRng;
 
 use benchmark::Benchmark;
 //use benchmark::timer_node::Benchmark;
@@ -22,19 +28,34 @@ use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use quintuple_buffer::QuintupleBuffer;
 
-use std::io::{Read, Write};
+#[cfg(feature = "serialize")]
+use std::io::Write;
 use std::sync::{RwLock, Weak};
 use std::sync::mpsc::Sender;
 use std::sync::atomic::AtomicUsize;
 use std::time::Duration;
 use std::time::Instant;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepMode {
+    Adaptive, // Tick duration follows duration_vec depending on load
+    Fixed(Duration), // Constant tick duration, slow ticks are counted as overruns
+}
+
 pub struct GameStep {
     pub actual_count: i32,
     pub duration_vec: Vec<Duration>,
     pub last_duration: Duration,
     pub changed_map: bool,
     pub full_speed: bool,
+    pub paused: bool, // World not updated, used when rewinding
+    pub mode: StepMode,
+    pub overruns: u64,
+    pub last_overrun: Duration,
+    pub world_hash: u64, // Computed after each update of the world
+    pub thread_independent: bool, // Same result at any thread count, a bit slower
+    pub invariant_error: Option<String>, // First invariant broken (DebugSettings::check_invariants), the game is paused
+    pub session_error: Option<String>, // The next round of the session couldn't start, the session was stopped. Until the next session
     pub waiting: Mutex<Option<(Instant, Duration, Duration, Sender<()>)>>,
 }
 
@@ -47,6 +68,14 @@ impl Default for GameStep {
             last_duration: Duration::from_millis(20),
             changed_map: false,
             full_speed: false,
+            paused: false,
+            mode: StepMode::Adaptive,
+            overruns: 0,
+            last_overrun: Duration::from_millis(0),
+            world_hash: 0,
+            thread_independent: false,
+            invariant_error: None,
+            session_error: None,
             waiting: Mutex::new(None),
         }
     }
@@ -57,6 +86,7 @@ pub enum GameState {
     MainMenu,
     Editor,
     Playing,
+    Results, // End of a round of the session, the world is stopped
 }
 #[derive(Clone, Default)]
 pub struct EditorState {
@@ -71,6 +101,9 @@ pub struct EditorState {
     pub entity_hovered: Option<usize>,
     pub entity_hovered_atomic: Option<std::sync::Weak<AtomicUsize>>,
     pub hovered: bool,
+    pub image_import: map::image_import::ImageImport,
+    pub wall_points: Vec<Point2D<i32>>, // Clicked in the Walls tab, not yet added as a wall
+    pub wall_thickness: i32,
 }
 
 impl EditorState {
@@ -84,6 +117,22 @@ impl EditorState {
     }
 }
 
+// Entities added during a tick by other entities are applied in the order of this key in thread independent mode (see CacheGameSolver)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AddEntityKey {
+    pub source: usize, // Unique id of the entity adding it
+    pub origin: AddEntityOrigin,
+    pub sequence: usize, // Rank among the entities added by the source with this origin during the tick
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AddEntityOrigin {
+    Throw,
+    Virus,
+    Split,
+    OnDeath,
+}
+
 pub enum GameAction { //TODO: change all BufferThing to ActionThing
     AddEntity(Box<EntityInfo>),
     KillEntity(Weak<AtomicUsize>), //TODO: just Arc<AtomicUsize> should be enough and better
@@ -94,8 +143,7 @@ pub struct SolverCache {
     pub collision: RwLock<Vec<&'static EntityCore>>,
 }
 
-pub struct GameInfo<'a> {
-    pub window: &'a Window,
+pub struct GameInfo {
     pub threads: usize,
 }
 
@@ -108,16 +156,24 @@ pub struct Game {
     pub entities: Entities, //TODO: Double Vec ? -> Avoid big allocation
     //pub entities: crate::utils::vec_chunk::VecChunk<Entity>,
     pub entities_characteristics: Vec<EntityCharacteristics>,
-    pub gui: Gui,
     pub benchmark: Benchmark,
     pub threadpool: threadpool::ThreadPool, //TODO: not in Game
     //pub buffer: BufferMulti<GameAction>,
-    pub buffer_add_entity: BufferMulti<Box<EntityInfo>>, //TODO: Refactor
+    pub buffer_add_entity: BufferMulti<(AddEntityKey, Box<EntityInfo>)>, //TODO: Refactor
     pub buffer_kill_entity: BufferMulti<Weak<AtomicUsize>>, //TODO: Refactor
+    pub buffer_respawn: BufferMulti<(AddEntityKey, PendingRespawn)>,
+    pub respawns: Vec<PendingRespawn>, // Entities waiting to come back, see OnDeathEffect::Respawn
     pub solver_cache: SolverCache,
     pub drawable: QuintupleBuffer<DrawableGame>,
     pub step: GameStep,
+    pub rng: GameRng,
+    pub rewind: rewind::Rewind,
+    #[cfg(feature = "serialize")]
+    pub replay: replay::ReplayState,
     pub id_generator: crate::utils::VecUniqueIndex, //TODO: Improve
+    pub textures: texture::TextureRegistry,
+    pub session: Option<mode::Session>, // Rounds of a game mode, None: sandbox
+    pub spawned: Vec<usize>, // Entities alive added by each spawn rule (EntityCore::spawn_rule), not the entities of the map file
 }
 
 unsafe impl Send for Game {} //TODO: bad
@@ -132,20 +188,23 @@ impl Game {
                 100,
                 100,
             ),
+            walls: Vec::new(),
+            topology: Default::default(),
         });
         let players = Vec::new();
         let entities = Entities::new();
         //let entities = crate::utils::vec_chunk::VecChunk::new();
         let entities_characteristics = Vec::new();
-        let gui = Gui::new(info.window);
         let benchmark = Benchmark::new(2);
         let threadpool = threadpool::ThreadPool::new(info.threads);
         //let buffer = BufferMulti::with_capacity(10, 8);
         let buffer_add_entity = BufferMulti::with_capacity(10, 8);
         let buffer_kill_entity = BufferMulti::with_capacity(10, 8);
+        let buffer_respawn = BufferMulti::with_capacity(10, 8);
         let solver_cache = SolverCache::default();
         let drawable = QuintupleBuffer::new(DrawableGame::default());
         let step = GameStep::default();
+        let rng = GameRng::default();
 
         Game {
             settings,
@@ -155,29 +214,82 @@ impl Game {
             players,
             entities,
             entities_characteristics,
-            gui,
             benchmark,
             threadpool,
             //buffer,
             buffer_add_entity,
             buffer_kill_entity,
+            buffer_respawn,
+            respawns: Vec::new(),
             solver_cache,
             drawable,
             step,
+            rng,
+            rewind: rewind::Rewind::default(),
+            #[cfg(feature = "serialize")]
+            replay: replay::ReplayState::default(),
             id_generator: crate::utils::VecUniqueIndex::new(),
+            textures: texture::TextureRegistry::default(),
+            session: None,
+            spawned: Vec::new(),
         }
     }
 
-    pub fn init(&mut self) {
+    pub fn from_map_info(info: GameInfo, map_info: MapInfo) -> Game {
+        let mut game = Game::new(info);
+        game.map = Map::new(map_info);
+        game
+    }
+
+    #[cfg(feature = "serialize")]
+    pub fn from_serialized(info: GameInfo, game_serialize: &GameSerialize) -> Game {
+        let mut game = Game::new(info);
+        game_serialize.to_game(&mut game);
+        game
+    }
+
+    // On error the built-in map is loaded instead, the error says so
+    pub fn init(&mut self) -> Result<(), String> {
         //map::premade::create_premade_map(self, "example");
-        map::premade::create_premade_map(self, "definitive");
+        map::premade::create_premade_map(self, "definitive").map_err(|err| {
+            map::premade::create_premade_map(self, map::premade::FALLBACK_MAP).expect("built-in map");
+            format!("{}, map {} loaded instead", err, map::premade::FALLBACK_MAP)
+        })
     }
 
-    pub fn new_player(&mut self, info: PlayerInfo) {
+    // Settings must be set before, for the color of the team
+    pub fn new_player(&mut self, mut info: PlayerInfo) {
+        if let Some(color) = info.team.and_then(|team| self.settings.teams.get(team)).and_then(|team| team.color) {
+            info.cell_default_color = color;
+        }
         let player = Player::new(info);
         self.players.push(player);
     }
 
+    // Rules of the team of both players, None when they are not teammates (or are the same player)
+    pub fn teammates(&self, player: usize, other: usize) -> Option<&player::TeamSettings> {
+        if player == other { return None }
+        let team = self.players[player].team?;
+        if self.players[other].team != Some(team) { return None }
+        self.settings.teams.get(team)
+    }
+
+    // The players that aren't neutral, the biggest first
+    pub fn leaderboard(&self, size: usize) -> Vec<player::LeaderboardEntry> {
+        let mut leaderboard: Vec<player::LeaderboardEntry> = self.players.iter().enumerate()
+            .filter(|(_, player)| player.kind != player::PlayerKind::Neutral)
+            .map(|(index, player)| player::LeaderboardEntry {
+                player: index,
+                name: mode::Side::Player(index).name(self),
+                team: player.team,
+                stats: player.stats.clone(),
+            })
+            .collect();
+        leaderboard.sort_by(|a, b| b.stats.mass.cmp(&a.stats.mass).then(b.stats.peak_mass.cmp(&a.stats.peak_mass)).then(a.player.cmp(&b.player)));
+        leaderboard.truncate(size);
+        leaderboard
+    }
+
     pub fn new_entity(&mut self, info: EntityInfo) { // TODO: Manage for multithreading
         let entities = unsafe { &* (&self.entities as *const Entities) };
 
@@ -187,6 +299,7 @@ impl Game {
         entity_core.index.unique_id = self.id_generator.gen_id();
         self.map.add_entity(entities, entity_core);
         self.players[info.player].entities.push(index);
+        self.players[info.player].stats.alive = true; // Its death is counted even in the tick it was added
     }
 
     pub fn delete_entity(&mut self, index: usize) { // TODO: Manage for multithreading
@@ -195,6 +308,7 @@ impl Game {
         let entity = unsafe { &mut *(&mut self.entities.core[index] as *mut EntityCore) };
         let player = unsafe { &mut *(&mut self.players[entity.player] as *mut Player) };
         self.id_generator.remove(entity.index.unique_id);
+        self.spawned_removed(entity.spawn_rule);
         self.map.delete_entity(&self.entities, entity);
         player.entities.swap_remove(entity.index.player);
         if player.entities.len() != entity.index.player {
@@ -210,6 +324,50 @@ impl Game {
         }
     }
 
+    // An entity added by a spawn rule is deleted
+    pub(crate) fn spawned_removed(&mut self, rule: Option<usize>) {
+        if let Some(count) = rule.and_then(|rule| self.spawned.get_mut(rule)) { *count = count.saturating_sub(1); }
+    }
+
+    // The spawn rules were moved or removed (editor): moved[old] is the new index of a rule, None when removed
+    pub fn spawn_rules_moved(&mut self, moved: &[Option<usize>]) {
+        for entity in self.entities.core.iter_mut() {
+            entity.spawn_rule = entity.spawn_rule.and_then(|rule| moved.get(rule).copied().flatten());
+        }
+        self.count_spawned();
+    }
+
+    // Counts of Game::spawned rebuilt from the tags of the entities, the tags of rules that no longer exist are cleared
+    pub(crate) fn count_spawned(&mut self) {
+        let rules = self.settings.auto_spawn.len();
+        self.spawned = vec![0; rules];
+        for entity in self.entities.core.iter_mut() {
+            match entity.spawn_rule {
+                Some(rule) if rule < rules => self.spawned[rule] += 1,
+                _ => entity.spawn_rule = None,
+            }
+        }
+    }
+
+    pub fn update_entity(&mut self, index: usize, info: EntityInfo) {
+        let game_bis = unsafe { &*(self as *const Game) };
+
+        let entity = unsafe { &mut *(&mut self.entities.core[index] as *mut EntityCore) };
+        if entity.player != info.player {
+            self.players[entity.player].entities.swap_remove(entity.index.player);
+            if self.players[entity.player].entities.len() != entity.index.player {
+                self.entities.core[self.players[entity.player].entities[entity.index.player]].index.player = entity.index.player;
+            }
+            entity.player = info.player;
+            entity.index.player = self.players[entity.player].entities.len();
+            self.players[entity.player].entities.push(index);
+        }
+        if !info.characteristics.collide && self.entities.flags[index].contains(EntityFlags::COLLIDE) {
+            self.map.matrix_physics.delete_entity_multithread(&game_bis.entities, entity);
+        }
+        self.entities.update(index, info);
+    }
+
     pub fn total_mass(&self) -> i64 {
         let mut total = 0;
         for &mass in self.entities.mass.iter() {
@@ -225,44 +383,158 @@ impl Game {
                 100,
                 100
             ),
+            walls: Vec::new(),
+            topology: Default::default(),
         });
         self.players.clear();
         self.entities.clear();
-        self.step.last_duration = self.step.duration_vec[0];
+        self.respawns.clear();
+        self.spawned.clear();
+        self.id_generator = crate::utils::VecUniqueIndex::new();
+        self.rewind.clear();
+        self.step.paused = false;
+        self.step.last_duration = match self.step.mode {
+            StepMode::Adaptive => self.step.duration_vec[0],
+            StepMode::Fixed(duration) => duration,
+        };
+        self.step.overruns = 0;
+        self.step.last_overrun = Duration::from_millis(0);
+        self.rng.reset();
+    }
+
+    pub fn rewind_save(&mut self) {
+        if !self.rewind.enabled || self.rewind.selected.is_some() { return }
+        if self.rng.tick % self.rewind.interval.max(1) != 0 { return }
+        let snapshot = rewind::WorldSnapshot::new(self);
+        self.rewind.snapshots.push_back(snapshot);
+        while self.rewind.snapshots.len() > self.rewind.capacity.max(1) {
+            self.rewind.snapshots.pop_front();
+        }
+    }
+
+    // Go back to a snapshot and wait there
+    pub fn rewind_to(&mut self, index: usize) {
+        if index >= self.rewind.snapshots.len() { return }
+        let rewind = std::mem::take(&mut self.rewind);
+        rewind.snapshots[index].restore(self);
+        self.rewind = rewind;
+        self.rewind.selected = Some(index);
+        self.step.paused = true;
+    }
+
+    // Continue from the selected snapshot: the snapshots after it are another future
+    pub fn rewind_resume(&mut self) {
+        if let Some(index) = self.rewind.selected.take() {
+            self.rewind.snapshots.truncate(index + 1);
+        }
+        self.step.paused = false;
     }
 
     #[cfg(feature = "serialize")]
-    pub fn to_binary_file(&mut self) {
-        let game_serialized = GameSerialize::from_game(&self);
-        let game_string = bincode::serialize(&game_serialized).unwrap();
-        std::fs::File::create("maps/Map_test.binmap").unwrap().write(&game_string).unwrap();
+    pub fn start_recording(&mut self) {
+        self.replay = replay::ReplayState::StartRecording;
     }
 
     #[cfg(feature = "serialize")]
-    pub fn from_binary_file(&mut self) {
-        let mut game_string = Vec::new();
-        let mut game_file = std::fs::File::open("maps/Map_test.binmap").unwrap();
-        game_file.read_to_end(&mut game_string).unwrap();
-        let game_serialize: GameSerialize = bincode::deserialize(&game_string).unwrap();
-        game_serialize.to_game(self);
+    pub fn stop_recording(&mut self, path: &str) -> Result<(), String> {
+        match std::mem::take(&mut self.replay) {
+            replay::ReplayState::Recording(replay) => replay.to_file(path),
+            _ => Err("not recording".to_owned()),
+        }
+    }
+
+    #[cfg(feature = "serialize")]
+    pub fn play_replay(&mut self, replay: replay::Replay) {
+        self.replay = replay::ReplayState::StartPlaying(replay);
     }
 
+    // Map template: settings, players and entities as at the start of a game. Format from the extension
     #[cfg(feature = "serialize")]
-    pub fn to_ron_file(&mut self) {
+    pub fn save_map(&self, path: &str) -> Result<(), String> {
         let game_serialized = GameSerialize::from_game(&self);
-        let game_string = ron::ser::to_string(&game_serialized).unwrap();
-        std::fs::File::create("maps/Map_test.ronmap").unwrap().write(&game_string.as_bytes()).unwrap();
+        let data = if path.ends_with(".binmap") {
+            format::write_binary(&game_serialized)?
+        } else if path.ends_with(".ronmap") {
+            format::write_ron(&game_serialized)?
+        } else {
+            return Err(format!("unknown map extension: {} (.binmap or .ronmap)", path))
+        };
+        write_file(path, &data)
     }
 
+    // Map definition (see map::definition) or map file, old versions of the format are upgraded (see format)
     #[cfg(feature = "serialize")]
-    pub fn from_ron_file(&mut self) {
-        let mut game_string = Vec::new();
-        let mut game_file = std::fs::File::open("maps/Map_test.ronmap").unwrap();
-        game_file.read_to_end(&mut game_string).unwrap();
-        let game_serialize: GameSerialize = ron::de::from_reader(game_string.as_slice()).unwrap();
+    pub fn load_map(&mut self, path: &str) -> Result<(), String> {
+        if path.ends_with(map::definition::DEFINITION_EXTENSION) {
+            let definition = map::definition::MapDefinition::from_file(path)?;
+            definition.create(self).map_err(|err| format!("can't load {}: {}", path, err))?;
+            self.step.changed_map = true;
+            return Ok(())
+        }
+        let (mut game_serialize, _version) = read_map(path)?;
+        game_serialize.resolve_textures(&self.textures).map_err(|err| format!("can't load {}: {}", path, err))?;
+        self.clear();
         game_serialize.to_game(self);
+        self.step.changed_map = true;
+        Ok(())
+    }
+
+    // Whole game in progress with the stats of the players and the session, restored exactly as it was (see format)
+    #[cfg(feature = "serialize")]
+    pub fn save_snapshot(&self, path: &str) -> Result<(), String> {
+        let snapshot = rewind::WorldSnapshot::new(self);
+        let data = format::write_snapshot(&snapshot)?;
+        write_file(path, &data)
     }
+
+    #[cfg(feature = "serialize")]
+    pub fn load_snapshot(&mut self, path: &str) -> Result<(), String> {
+        let data = std::fs::read(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+        let mut snapshot = format::read_snapshot(&data).map_err(|err| format!("can't load {}: {}", path, err))?;
+        snapshot.resolve_textures(&self.textures).map_err(|err| format!("can't load {}: {}", path, err))?;
+        snapshot.restore(self);
+        Ok(())
+    }
+
+    // Map or snapshot depending on the extension
+    #[cfg(feature = "serialize")]
+    pub fn save_file(&self, path: &str) -> Result<(), String> {
+        if path.ends_with(SNAPSHOT_EXTENSION) { self.save_snapshot(path) } else { self.save_map(path) }
+    }
+
+    #[cfg(feature = "serialize")]
+    pub fn load_file(&mut self, path: &str) -> Result<(), String> {
+        if path.ends_with(SNAPSHOT_EXTENSION) { self.load_snapshot(path) } else { self.load_map(path) }
+    }
+}
+
+// Returns the map and the version of its file
+#[cfg(feature = "serialize")]
+pub fn read_map(path: &str) -> Result<(GameSerialize, u32), String> {
+    let data = std::fs::read(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+    let result = if path.ends_with(".binmap") {
+        format::read_binary(&data)
+    } else if path.ends_with(".ronmap") {
+        format::read_ron(&data)
+    } else {
+        return Err(format!("unknown map extension: {} (.binmap or .ronmap)", path))
+    };
+    result.map_err(|err| format!("can't load {}: {}", path, err))
 }
+
+#[cfg(feature = "serialize")]
+pub const SNAPSHOT_EXTENSION: &str = ".binsave";
+
+#[cfg(feature = "serialize")]
+pub(crate) fn write_file(path: &str, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(parent).map_err(|err| format!("can't create {}: {}", parent.display(), err))?;
+    }
+    std::fs::File::create(path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|err| format!("can't write {}: {}", path, err))
+}
+
 #[derive(Clone)]
 pub struct DrawableGame {
     pub instant: std::time::Instant,
@@ -277,6 +549,8 @@ pub struct DrawableGame {
     pub position: Vec<Point2D<i32>>,
     pub mass: Vec<f32>,
     pub background_color: [f32;4],
+    pub leaderboard: Vec<player::LeaderboardEntry>,
+    pub local_player_stats: player::PlayerStats,
 }
 
 impl Default for DrawableGame {
@@ -294,6 +568,8 @@ impl Default for DrawableGame {
             position: Vec::new(),
             mass: Vec::new(),
             background_color: [0.0, 0.0, 0.0, 1.0],
+            leaderboard: Vec::new(),
+            local_player_stats: Default::default(),
         }
     }
 }
@@ -303,13 +579,17 @@ impl Default for DrawableGame {
 pub struct GameSerialize {
     pub settings: Settings,
     pub players_info: Vec<PlayerInfo>,
-    pub entities_info: Vec<EntityInfo>, //TODO: Change "usize" texture by the "String" texture name.. Or not!!!
+    pub entities_info: Vec<EntityInfo>,
     pub entities_characteristics: Vec<EntityCharacteristics>,
     pub map_info: MapInfo,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub rng: GameRng,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub textures: Vec<String>, // Names of the texture indices used in the map, see resolve_textures
 }
 
 impl GameSerialize {
-    fn from_game(game: &Game) -> GameSerialize {
+    pub fn from_game(game: &Game) -> GameSerialize {
         let settings = game.settings.clone();
         let mut players_info = Vec::new();
         for player in game.players.iter() {
@@ -324,7 +604,11 @@ impl GameSerialize {
         let entities_characteristics = game.entities_characteristics.clone();
         let map_info = MapInfo {            
             size: game.map.size,
+            walls: game.map.walls.list.clone(),
+            topology: game.map.topology,
         };
+        let rng = game.rng.clone();
+        let textures = game.textures.names().to_vec();
 
         GameSerialize {
             settings,
@@ -332,10 +616,33 @@ impl GameSerialize {
             entities_info,
             entities_characteristics,
             map_info,
+            rng,
+            textures,
+        }
+    }
+
+    // Texture indices of the file -> indices of the registry of this game. Fails if a texture is unknown
+    pub fn resolve_textures(&mut self, registry: &texture::TextureRegistry) -> Result<(), String> {
+        let names = std::mem::take(&mut self.textures);
+        let mut remap = texture::TextureRemap::new(&names, registry);
+        let mut f = |index: usize| remap.get(index);
+        self.settings.map_textures(&mut f);
+        for player_info in self.players_info.iter_mut() {
+            player_info.map_textures(&mut f);
+        }
+        for entity_info in self.entities_info.iter_mut() {
+            entity_info.texture = f(entity_info.texture);
+            entity_info.characteristics.map_textures(&mut f);
+        }
+        for characteristics in self.entities_characteristics.iter_mut() {
+            characteristics.map_textures(&mut f);
         }
+        remap.finish()?;
+        self.textures = registry.names().to_vec();
+        Ok(())
     }
     
-    fn to_game(&self, game: &mut Game) {
+    pub fn to_game(&self, game: &mut Game) {
         game.settings = self.settings.clone();
         game.map = Map::new(self.map_info.clone());
         game.players.clear();
@@ -347,6 +654,7 @@ impl GameSerialize {
             game.new_entity(entity_info.clone());
         }
         game.entities_characteristics = self.entities_characteristics.clone();
+        game.rng = self.rng.clone();
     }
     
 }
\ No newline at end of file
@@ -0,0 +1,267 @@
+// Game modes: rounds on a premade map, each one ends on the win condition of the mode, then the results are shown
+// and the map is created again for the next round. Without session (Game::session) the game is an endless sandbox.
+// The session isn't saved with the maps but with the games in progress (see WorldSnapshot), replays only record one round
+
+use crate::game::{Game, GameState};
+use crate::game::bot::{self, BotSettings};
+use crate::game::entity::RATIO_MASS;
+use crate::game::map::premade;
+use crate::game::player::PlayerKind;
+
+use euclid::default::Point2D;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+pub const MODES: [&str; 4] = ["free-for-all", "last-man-standing", "target-mass", "king-of-the-hill"];
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModeKind {
+    FreeForAll, // The biggest when the time runs out
+    LastManStanding, // The last with cells, nobody respawns
+    TargetMass(i64), // The first to reach this mass
+    KingOfTheHill(HillSettings), // The first to hold the hill long enough
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HillSettings {
+    pub position: (f32, f32), // Ratio of the size of the map, (0.5, 0.5) is the center
+    pub radius: i32,
+    pub score: u64, // Ticks held to win, the side with the most mass in the hill holds it
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModeSettings {
+    pub kind: ModeKind,
+    pub time_limit: Option<u64>, // Ticks of a round, then the best one wins
+    pub rounds: usize, // Rounds of the session, 0: endless
+    pub results_duration: f32, // Seconds the results are shown before the next round, not waited headless
+}
+
+impl ModeSettings {
+    // Presets of MODES
+    pub fn from_name(name: &str) -> Option<ModeSettings> {
+        let kind = match name {
+            "free-for-all" => ModeKind::FreeForAll,
+            "last-man-standing" => ModeKind::LastManStanding,
+            "target-mass" => ModeKind::TargetMass(RATIO_MASS * 1_000),
+            "king-of-the-hill" => ModeKind::KingOfTheHill(HillSettings {
+                position: (0.5, 0.5),
+                radius: 50_000,
+                score: 1_500,
+            }),
+            _ => return None,
+        };
+        Some(ModeSettings {
+            time_limit: if kind == ModeKind::LastManStanding { Some(15_000) } else { Some(5_000) },
+            kind,
+            rounds: 3,
+            results_duration: 5.0,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            ModeKind::FreeForAll => MODES[0],
+            ModeKind::LastManStanding => MODES[1],
+            ModeKind::TargetMass(_) => MODES[2],
+            ModeKind::KingOfTheHill(_) => MODES[3],
+        }
+    }
+
+    pub fn validate(&mut self) {
+        if let Some(time_limit) = self.time_limit.as_mut() { *time_limit = (*time_limit).max(1); }
+        if let ModeKind::KingOfTheHill(hill) = &mut self.kind {
+            hill.radius = hill.radius.max(1);
+            hill.score = hill.score.max(1);
+        }
+        self.results_duration = self.results_duration.max(0.0);
+    }
+
+    pub fn respawn(&self) -> bool {
+        self.kind != ModeKind::LastManStanding
+    }
+}
+
+// A player, or all the players of a team
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Player(usize),
+    Team(usize),
+}
+
+impl Side {
+    pub fn of(game: &Game, player: usize) -> Side {
+        match game.players[player].team {
+            Some(team) if team < game.settings.teams.len() => Side::Team(team),
+            _ => Side::Player(player),
+        }
+    }
+
+    pub fn name(&self, game: &Game) -> String {
+        match *self {
+            Side::Team(team) => match game.settings.teams.get(team) {
+                Some(settings) if !settings.name.is_empty() => settings.name.clone(),
+                _ => format!("team {}", team),
+            },
+            Side::Player(player) if player == game.settings.local_player => "you".to_owned(),
+            Side::Player(player) => match game.players.get(player).map(|player| &player.kind) {
+                Some(PlayerKind::Bot(_)) => format!("bot {}", player),
+                _ => format!("player {}", player),
+            },
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug)]
+pub struct Standing {
+    pub side: Side,
+    pub mass: i64,
+    pub score: u64, // Ticks holding the hill
+    pub alive: bool,
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug)]
+pub struct RoundResult {
+    pub round: usize,
+    pub ticks: u64,
+    pub winner: Option<Side>, // None: draw
+    pub standings: Vec<Standing>, // Best first
+}
+
+// Bots added to the map at each round, e.g. by `cells-sim --bots`
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone)]
+pub struct SessionBots {
+    pub amount: usize,
+    pub settings: BotSettings,
+    pub team: Option<usize>,
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone)]
+pub struct Session {
+    pub mode: ModeSettings,
+    pub map: String, // Premade map created again at each round
+    pub bots: Vec<SessionBots>,
+    pub seed: u64, // Each round is seeded from it
+    pub round: usize, // Current round, from 1
+    pub round_start: u64, // Tick of the start of the round
+    pub scores: Vec<(Side, u64)>,
+    pub results: Vec<RoundResult>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub ended: Option<Instant>, // End of the last round, while the results are shown
+}
+
+impl Session {
+    // Replaces the session of the game and starts its first round
+    pub fn start(game: &mut Game, mut mode: ModeSettings, map: &str, bots: Vec<SessionBots>) -> Result<(), String> {
+        if !premade::premade_maps().iter().any(|premade| premade == map) {
+            return Err(format!("unknown map: {}", map))
+        }
+        mode.validate();
+        game.step.session_error = None;
+        game.session = Some(Session {
+            mode,
+            map: map.to_owned(),
+            bots,
+            seed: game.rng.seed,
+            round: 0,
+            round_start: 0,
+            scores: Vec::new(),
+            results: Vec::new(),
+            ended: None,
+        });
+        Session::start_round(game)
+    }
+
+    pub fn start_round(game: &mut Game) -> Result<(), String> {
+        let session = game.session.as_mut().ok_or("no session")?;
+        session.round += 1;
+        let seed = crate::game::rng::mix(session.seed ^ session.round as u64);
+        let map = session.map.clone();
+        let bots = session.bots.clone();
+        game.rng.reseed(seed);
+        premade::create_premade_map(game, &map)?;
+        for bots in bots.iter() {
+            bot::add_bots(game, bots.amount, &bots.settings, RATIO_MASS * 10, bots.team);
+        }
+        game.state = GameState::Playing;
+
+        let tick = game.rng.tick;
+        let session = game.session.as_mut().ok_or("no session")?;
+        session.round_start = tick;
+        session.scores.clear();
+        session.ended = None;
+        Ok(())
+    }
+
+    pub fn finished(&self) -> bool {
+        self.mode.rounds != 0 && self.results.len() >= self.mode.rounds
+    }
+
+    // Whether the results were shown long enough for the next round
+    pub fn results_shown(&self) -> bool {
+        self.ended.map_or(true, |ended| ended.elapsed().as_secs_f32() >= self.mode.results_duration)
+    }
+
+    pub fn wins(&self, side: Side) -> usize {
+        self.results.iter().filter(|result| result.winner == Some(side)).count()
+    }
+
+    pub fn score(&self, side: Side) -> u64 {
+        self.scores.iter().find(|(other, _)| *other == side).map_or(0, |(_, score)| *score)
+    }
+
+    pub fn add_score(&mut self, side: Side) {
+        match self.scores.iter_mut().find(|(other, _)| *other == side) {
+            Some((_, score)) => *score += 1,
+            None => self.scores.push((side, 1)),
+        }
+    }
+
+    pub fn ticks(&self, game: &Game) -> u64 {
+        game.rng.tick.saturating_sub(self.round_start)
+    }
+
+    pub fn time_left(&self, game: &Game) -> Option<u64> {
+        self.mode.time_limit.map(|limit| limit.saturating_sub(self.ticks(game)))
+    }
+
+    // Every side with a player that isn't neutral, best first
+    pub fn standings(&self, game: &Game) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = Vec::new();
+        for (index, player) in game.players.iter().enumerate() {
+            if player.kind == PlayerKind::Neutral { continue }
+            let side = Side::of(game, index);
+            let mass: i64 = player.entities.iter().map(|entity| game.entities.mass[*entity]).sum();
+            match standings.iter_mut().find(|standing| standing.side == side) {
+                Some(standing) => {
+                    standing.mass += mass;
+                    standing.alive |= !player.entities.is_empty();
+                }
+                None => standings.push(Standing {
+                    side,
+                    mass,
+                    score: self.score(side),
+                    alive: !player.entities.is_empty(),
+                }),
+            }
+        }
+        standings.sort_by(|a, b| b.score.cmp(&a.score).then(b.mass.cmp(&a.mass)));
+        standings
+    }
+}
+
+impl HillSettings {
+    pub fn center(&self, game: &Game) -> Point2D<i32> {
+        let max = game.map.max();
+        Point2D::new((max.width as f32 * self.position.0) as i32, (max.height as f32 * self.position.1) as i32)
+    }
+}
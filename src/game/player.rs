@@ -1,3 +1,5 @@
+use crate::game::settings::ThrownFoodInfo;
+
 use buffer::BufferMulti;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
@@ -5,12 +7,15 @@ use std::sync::Weak;
 use std::sync::atomic::AtomicUsize;
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
 #[derive(Clone, Default)]
 pub struct PlayerInfo {
     pub kind: PlayerKind, // TODO: change to PlayerKind?
     pub entities: Vec<usize>,
     pub cell_default_color: crate::game::entity::EntityColor,
     pub cell_default_texture: usize,
+    pub settings: PlayerSettings,
+    pub team: Option<usize>, // Index in Settings::teams
 }
 
 
@@ -21,15 +26,67 @@ impl PlayerInfo {
             entities: player.entities.clone(),
             cell_default_color: player.cell_default_color,
             cell_default_texture: player.cell_default_texture,
+            settings: player.settings.clone(),
+            team: player.team,
+        }
+    }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        self.cell_default_texture = f(self.cell_default_texture);
+        self.settings.map_textures(f);
+    }
+}
+
+// Movement, split and throw rules of a player
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
+pub struct PlayerSettings {
+    pub unit_speed: f32,
+    pub unit_speed_split: f32,
+    pub max_split: usize, // Entities of the player after which it can't split anymore
+    pub food: ThrownFoodInfo,
+}
+
+impl PlayerSettings {
+    pub fn validate(&mut self) {
+        self.unit_speed_split = self.unit_speed_split.max(0.0);
+        self.food.validate();
+    }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        self.food.map_textures(f);
+    }
+}
+
+// Rules between the players of a team, the eat ratio of Settings applies between the other players
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone, Default)]
+pub struct TeamSettings {
+    pub name: String,
+    pub color: Option<crate::game::entity::EntityColor>, // Replaces cell_default_color of the players of the team
+    pub feed: bool, // Cells can be eaten by the bigger cells of teammates, else teammates never eat each other
+    pub merge: bool, // Cells of teammates collide and merge like the cells of one player (mergeable timers)
+}
+
+impl Default for PlayerSettings {
+    fn default() -> PlayerSettings {
+        PlayerSettings {
+            unit_speed: 5_000.0,
+            unit_speed_split: 0.05,
+            max_split: 64,
+            food: ThrownFoodInfo::default(),
         }
     }
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum PlayerKind {
     Player,
     Neutral,
+    Bot(crate::game::bot::BotSettings),
 }
 
 impl Default for PlayerKind {
@@ -42,6 +99,32 @@ pub enum PlayerAction {
     AddEntity(usize),
     KillEntity(usize), //TODO: DeleteEntity instead ?
     Move(Weak<AtomicUsize>),
+    AteCell(usize), // Player of the cell eaten
+    Decayed(i64), // Mass lost by a cell of the player with its mass evolution
+}
+
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// Kept by the game while it runs, not saved with the maps but with the games in progress (see WorldSnapshot)
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default)]
+pub struct PlayerStats {
+    pub mass: i64,
+    pub peak_mass: i64,
+    pub cells_eaten: u64, // Cells of other players and neutral cells, not of teammates
+    pub players_eaten: u64, // Players losing their last cell to this player
+    pub deaths: u64,
+    pub ticks_alive: u64,
+    pub mass_decayed: i64,
+    pub alive: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct LeaderboardEntry {
+    pub player: usize,
+    pub name: String,
+    pub team: Option<usize>,
+    pub stats: PlayerStats,
 }
 
 #[repr(C)]
@@ -50,6 +133,9 @@ pub struct Player {
     pub entities: Vec<usize>,
     pub cell_default_color: crate::game::entity::EntityColor, // TODO: Change this
     pub cell_default_texture: usize, // TODO: Change this
+    pub settings: PlayerSettings,
+    pub team: Option<usize>,
+    pub stats: PlayerStats,
     pub buffer: BufferMulti<PlayerAction>,
 }
 
@@ -60,6 +146,9 @@ impl Player {
             entities: Vec::new(),
             cell_default_color: info.cell_default_color,
             cell_default_texture: info.cell_default_texture,
+            settings: info.settings,
+            team: info.team,
+            stats: PlayerStats::default(),
             buffer: BufferMulti::with_capacity(1, 8),
         }
     }
@@ -0,0 +1,168 @@
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serialize")]
+use crate::game::{Game, GameState};
+#[cfg(feature = "serialize")]
+use crate::game::rewind::WorldSnapshot;
+#[cfg(feature = "serialize")]
+use crate::game::texture::TextureRegistry;
+
+use euclid::default::Point2D;
+
+#[cfg(feature = "serialize")]
+pub const REPLAY_PATH: &str = "replays/last.binreplay";
+
+// Keys and buttons read by the game while playing (see InputSolver)
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputKey {
+    R,
+    Space,
+    E,
+    Up,
+    Down,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    MouseRight,
+}
+
+// Everything the game gets from the window during one tick
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default)]
+pub struct TickInput {
+    pub pressed: Vec<(InputKey, Point2D<i32>)>, // Key and mouse position in the world when pressed
+    pub mouse_position_world: Point2D<i32>,
+    pub mouse_on_gui: bool,
+    pub gui_open: bool,
+    pub throwing: bool, // W held
+    pub debug: bool,
+    pub debug_add_mass: bool, // Z held
+}
+
+#[cfg(feature = "serialize")]
+#[derive(Deserialize, Serialize)]
+pub struct Replay {
+    pub snapshot: WorldSnapshot, // Whole game when the recording started, with its runtime state (timers, directions, ids, stats...)
+    pub threads: usize,
+    pub thread_independent: bool,
+    pub inputs: Vec<Option<TickInput>>, // None: tick without window (headless), InputSolver didn't run
+    #[serde(default)]
+    pub hashes: Vec<u64>, // World hash after each tick
+    #[serde(skip)]
+    pub divergence: Option<usize>, // First tick with a different hash when playing
+}
+
+#[cfg(feature = "serialize")]
+impl Replay {
+    pub fn new(game: &Game) -> Replay {
+        Replay {
+            snapshot: WorldSnapshot::new(game),
+            threads: game.threadpool.num_threads(),
+            thread_independent: game.step.thread_independent,
+            inputs: Vec::new(),
+            hashes: Vec::new(),
+            divergence: None,
+        }
+    }
+
+    // Put the game back in the state of the start of the recording
+    pub fn restore(&self, game: &mut Game) {
+        game.state = GameState::Playing; // The snapshot shows the results again if the round was over
+        self.snapshot.restore(game);
+        game.threadpool.set_threads(self.threads);
+        game.step.thread_independent = self.thread_independent;
+    }
+
+    pub fn to_file(&self, path: &str) -> Result<(), String> {
+        let data = crate::game::format::write_replay(self)?;
+        crate::game::write_file(path, &data)
+    }
+
+    pub fn from_file(path: &str, textures: &TextureRegistry) -> Result<Replay, String> {
+        let data = std::fs::read(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+        let mut replay = crate::game::format::read_replay(&data).map_err(|err| format!("can't load {}: {}", path, err))?;
+        replay.snapshot.resolve_textures(textures).map_err(|err| format!("can't load {}: {}", path, err))?;
+        Ok(replay)
+    }
+}
+
+// Start of recording and playback wait for the beginning of the next tick, so both see the same state
+#[cfg(feature = "serialize")]
+pub enum ReplayState {
+    Off,
+    StartRecording,
+    Recording(Replay),
+    StartPlaying(Replay),
+    Playing(Replay, usize),
+}
+
+#[cfg(feature = "serialize")]
+impl Default for ReplayState {
+    fn default() -> ReplayState {
+        ReplayState::Off
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl ReplayState {
+    pub fn is_recording(&self) -> bool {
+        matches!(self, ReplayState::StartRecording | ReplayState::Recording(_))
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self, ReplayState::StartPlaying(_) | ReplayState::Playing(_, _))
+    }
+}
+
+// Called at the beginning of each playing tick with the input of the window (None when headless)
+#[cfg(feature = "serialize")]
+pub fn next_input(game: &mut Game, input: Option<TickInput>) -> Option<TickInput> {
+    match std::mem::take(&mut game.replay) {
+        ReplayState::StartRecording => {
+            game.replay = ReplayState::Recording(Replay::new(game));
+        }
+        ReplayState::StartPlaying(replay) => {
+            replay.restore(game);
+            game.replay = ReplayState::Playing(replay, 0);
+        }
+        other => game.replay = other,
+    }
+
+    match &mut game.replay {
+        ReplayState::Off | ReplayState::StartRecording | ReplayState::StartPlaying(_) => input,
+        ReplayState::Recording(replay) => {
+            replay.inputs.push(input.clone());
+            input
+        }
+        ReplayState::Playing(replay, index) => {
+            if *index < replay.inputs.len() {
+                *index += 1;
+                replay.inputs[*index - 1].clone()
+            } else {
+                game.replay = ReplayState::Off;
+                input
+            }
+        }
+    }
+}
+
+// Called after each update of the world
+#[cfg(feature = "serialize")]
+pub fn check_hash(game: &mut Game) {
+    let hash = game.step.world_hash;
+    match &mut game.replay {
+        ReplayState::Recording(replay) => {
+            replay.hashes.push(hash);
+        }
+        ReplayState::Playing(replay, index) => {
+            if let Some(tick) = index.checked_sub(1) {
+                if replay.divergence.is_none() && replay.hashes.get(tick).map_or(false, |&original| original != hash) {
+                    replay.divergence = Some(tick);
+                }
+            }
+        }
+        _ => {}
+    }
+}
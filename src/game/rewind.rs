@@ -0,0 +1,224 @@
+use crate::game::{Game, GameState, Settings};
+use crate::game::entity::{EntityCharacteristics, EntityColor, EntityFlags, EntityInfo, EntitySpecial, EntityTimer, PendingRespawn};
+use crate::game::map::{Map, MapInfo};
+use crate::game::mode::Session;
+use crate::game::player::{PlayerInfo, PlayerStats};
+use crate::game::rng::GameRng;
+use crate::game::texture::{TextureRegistry, TextureRemap};
+use crate::utils::VecUniqueIndex;
+
+use euclid::default::{Point2D, Vector2D};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const MAX_CHARACTERISTICS_SEARCH: usize = 256;
+
+// State of the world at the end of a tick. Characteristics are shared between entities to keep it small
+// Also used for save files of a game in progress (see Game::save_snapshot)
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub struct WorldSnapshot {
+    pub tick: u64,
+    settings: Settings,
+    rng: GameRng,
+    map_info: MapInfo,
+    players: Vec<PlayerInfo>,
+    stats: Vec<PlayerStats>,
+    session: Option<Session>,
+    entities_characteristics: Vec<EntityCharacteristics>,
+    id_generator: VecUniqueIndex,
+    characteristics: Vec<EntityCharacteristics>,
+    characteristics_index: Vec<u32>,
+    player: Vec<usize>,
+    unique_id: Vec<usize>,
+    killed: Vec<Option<usize>>,
+    spawn_rule: Vec<Option<usize>>,
+    fed: Vec<Option<Vector2D<f32>>>,
+    color: Vec<EntityColor>,
+    texture: Vec<usize>,
+    position: Vec<Point2D<i32>>,
+    speed: Vec<Vector2D<f32>>,
+    mass: Vec<i64>,
+    direction: Vec<Option<Point2D<i32>>>,
+    timer: Vec<EntityTimer>,
+    flags: Vec<u32>, // EntityFlags bits
+    mass_evolution: Vec<Option<f32>>,
+    lifetime: Vec<i32>,
+    special: Vec<Vec<EntitySpecial>>,
+    respawns: Vec<PendingRespawn>,
+    textures: Vec<String>, // Names of the texture indices, for save files
+}
+
+impl WorldSnapshot {
+    pub fn new(game: &Game) -> WorldSnapshot {
+        let entities = &game.entities;
+        let mut characteristics: Vec<EntityCharacteristics> = Vec::new();
+        let mut characteristics_index = Vec::with_capacity(entities.len());
+        let mut last = 0;
+        for entity in entities.core.iter() {
+            let index = if characteristics.get(last) == Some(&entity.characteristics) {
+                last
+            } else if let Some(index) = characteristics.iter().take(MAX_CHARACTERISTICS_SEARCH).position(|c| *c == entity.characteristics) {
+                index
+            } else {
+                characteristics.push(entity.characteristics.clone());
+                characteristics.len() - 1
+            };
+            characteristics_index.push(index as u32);
+            last = index;
+        }
+
+        WorldSnapshot {
+            tick: game.rng.tick,
+            settings: game.settings.clone(),
+            rng: game.rng.clone(),
+            map_info: MapInfo {
+                size: game.map.size,
+                walls: game.map.walls.list.clone(),
+                topology: game.map.topology,
+            },
+            players: game.players.iter().map(|player| PlayerInfo::from_player(player)).collect(),
+            stats: game.players.iter().map(|player| player.stats.clone()).collect(),
+            session: game.session.clone(),
+            entities_characteristics: game.entities_characteristics.clone(),
+            id_generator: game.id_generator.clone(),
+            characteristics,
+            characteristics_index,
+            player: entities.core.iter().map(|entity| entity.player).collect(),
+            unique_id: entities.core.iter().map(|entity| entity.index.unique_id).collect(),
+            killed: entities.core.iter().map(|entity| entity.killed).collect(),
+            spawn_rule: entities.core.iter().map(|entity| entity.spawn_rule).collect(),
+            fed: entities.core.iter().map(|entity| entity.fed).collect(),
+            color: entities.core.iter().map(|entity| entity.color).collect(),
+            texture: entities.core.iter().map(|entity| entity.index.texture).collect(),
+            position: entities.position.clone(),
+            speed: entities.speed.clone(),
+            mass: entities.mass.clone(),
+            direction: entities.direction.clone(),
+            timer: entities.timer.clone(),
+            flags: entities.flags.iter().map(|flags| flags.bits()).collect(),
+            mass_evolution: entities.mass_evolution.clone(),
+            lifetime: entities.lifetime.clone(),
+            special: entities.special.clone(),
+            respawns: game.respawns.clone(),
+            textures: game.textures.names().to_vec(),
+        }
+    }
+
+    // Same as GameSerialize::resolve_textures, for save files
+    pub fn resolve_textures(&mut self, registry: &TextureRegistry) -> Result<(), String> {
+        let names = std::mem::take(&mut self.textures);
+        let mut remap = TextureRemap::new(&names, registry);
+        let mut f = |index: usize| remap.get(index);
+        self.settings.map_textures(&mut f);
+        for player in self.players.iter_mut() {
+            player.map_textures(&mut f);
+        }
+        for texture in self.texture.iter_mut() {
+            *texture = f(*texture);
+        }
+        for characteristics in self.characteristics.iter_mut().chain(self.entities_characteristics.iter_mut()) {
+            characteristics.map_textures(&mut f);
+        }
+        for respawn in self.respawns.iter_mut() {
+            respawn.info.texture = f(respawn.info.texture);
+            respawn.info.characteristics.map_textures(&mut f);
+        }
+        remap.finish()?;
+        self.textures = registry.names().to_vec();
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.position.len()
+    }
+
+    pub fn restore(&self, game: &mut Game) {
+        game.clear();
+        game.settings = self.settings.clone();
+        game.map = Map::new(self.map_info.clone());
+        for player in self.players.iter() {
+            game.new_player(player.clone());
+        }
+
+        for index in 0..self.len() {
+            game.new_entity(EntityInfo {
+                player: self.player[index],
+                position: self.position[index],
+                speed: self.speed[index],
+                mass: self.mass[index],
+                characteristics: self.characteristics[self.characteristics_index[index] as usize].clone(),
+                timer: self.timer[index].clone(),
+                color: self.color[index],
+                texture: self.texture[index],
+            });
+        }
+        game.entities.direction = self.direction.clone();
+        game.entities.flags = self.flags.iter().map(|&bits| EntityFlags::from_bits_truncate(bits)).collect();
+        game.entities.mass_evolution = self.mass_evolution.clone();
+        game.entities.lifetime = self.lifetime.clone();
+        game.entities.special = self.special.clone();
+        for (index, &lifetime) in self.lifetime.iter().enumerate() {
+            game.entities.drawable_entities[index].lifetime = lifetime;
+        }
+        for (index, entity) in game.entities.core.iter_mut().enumerate() {
+            entity.index.unique_id = self.unique_id[index];
+            entity.killed = self.killed[index]; // origin is only a runtime link, never set for now
+            entity.spawn_rule = self.spawn_rule[index];
+            entity.fed = self.fed[index];
+        }
+        game.count_spawned();
+        game.entities_characteristics = self.entities_characteristics.clone();
+        game.respawns = self.respawns.clone();
+        game.id_generator = self.id_generator.clone();
+
+        // Same order of entities inside players as when saved
+        for (player_index, player) in self.players.iter().enumerate() {
+            game.players[player_index].entities = player.entities.clone();
+            game.players[player_index].stats = self.stats[player_index].clone();
+            for (index_player, &entity_index) in player.entities.iter().enumerate() {
+                game.entities.core[entity_index].index.player = index_player;
+            }
+        }
+
+        game.session = self.session.clone();
+        // Back in the round, or at its end: the results are shown again from now
+        if game.state == GameState::Playing || game.state == GameState::Results {
+            let over = game.session.as_ref().map_or(false, |session| session.results.last().map(|result| result.round) == Some(session.round));
+            if let Some(session) = game.session.as_mut() {
+                session.ended = if over { Some(std::time::Instant::now()) } else { None };
+            }
+            game.state = if over { GameState::Results } else { GameState::Playing };
+        }
+        game.rng = self.rng.clone();
+        game.step.changed_map = true;
+    }
+}
+
+// Ring buffer of the last snapshots, one every `interval` ticks
+pub struct Rewind {
+    pub enabled: bool,
+    pub interval: u64,
+    pub capacity: usize,
+    pub snapshots: VecDeque<WorldSnapshot>,
+    pub selected: Option<usize>, // Snapshot shown while scrubbing, the game is paused
+}
+
+impl Default for Rewind {
+    fn default() -> Rewind {
+        Rewind {
+            enabled: false,
+            interval: 50,
+            capacity: 20,
+            snapshots: VecDeque::new(),
+            selected: None,
+        }
+    }
+}
+
+impl Rewind {
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.selected = None;
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use rand::RngCore;
+
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// Each use of randomness gets its own stream, so adding a call somewhere doesn't shift the others.
+#[derive(Clone, Copy)]
+pub enum RngStream {
+    Map,
+    AutoSpawn,
+    ShrinkMap,
+    Special,
+    Events,
+    Throw,
+    Split,
+    Collision,
+    Bot,
+    OnDeath,
+}
+
+// Streams only depend on (seed, tick, stream, key): never on the thread computing it.
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRng {
+    pub seed: u64,
+    pub tick: u64,
+}
+
+impl Default for GameRng {
+    fn default() -> GameRng {
+        GameRng::new(rand::random())
+    }
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> GameRng {
+        GameRng {
+            seed,
+            tick: 0,
+        }
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.tick = 0;
+    }
+
+    pub fn reset(&mut self) {
+        self.tick = 0;
+    }
+
+    pub fn advance(&mut self) {
+        self.tick += 1;
+    }
+
+    pub fn stream(&self, stream: RngStream, key: u64) -> StreamRng {
+        let mut state = mix(self.seed);
+        state = mix(state ^ self.tick);
+        state = mix(state ^ stream as u64);
+        state = mix(state ^ key);
+        StreamRng {
+            state,
+        }
+    }
+}
+
+// SplitMix64
+#[derive(Clone)]
+pub struct StreamRng {
+    state: u64,
+}
+
+impl RngCore for StreamRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[inline]
+pub(crate) fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(GOLDEN_GAMMA);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -3,9 +3,12 @@ pub mod colors;
 use crate::prelude::*;
 use crate::game::entity::EntityColor;
 use crate::game::entity::EntityTimer;
+use crate::game::player::TeamSettings;
 
 use crate::game::entity::RATIO_MASS;
 
+use euclid::default::{Point2D, Vector2D};
+use rand::Rng;
 use std::ops::Range;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
@@ -180,6 +183,7 @@ pub enum SpawnPosition {
     Exact(i32, i32),
     Random,
     RandomRange(Range<i32>, Range<i32>),
+    Distribution(crate::game::map::generator::Distribution),
 }
 
 impl SpawnPosition {
@@ -195,6 +199,19 @@ impl SpawnPosition {
             range_x.end = range_x.end.max(range_x.start + 1);
             range_y.end = range_y.end.max(range_y.start + 1);
         }
+        if let SpawnPosition::Distribution(distribution) = self {
+            distribution.validate();
+        }
+    }
+
+    // None when the distribution found no position
+    pub fn sample(&self, game: &Game, rng: &mut impl Rng) -> Option<Point2D<i32>> {
+        match self {
+            SpawnPosition::Exact(x, y) => Some(Point2D::new(*x, *y)),
+            SpawnPosition::Random => Some(Point2D::new(rng.gen_range(0..game.map.max().width), rng.gen_range(0..game.map.max().height))),
+            SpawnPosition::RandomRange(range_x, range_y) => Some(Point2D::new(rng.gen_range(range_x.start..range_x.end), rng.gen_range(range_y.start..range_y.end))),
+            SpawnPosition::Distribution(distribution) => distribution.sample(game, rng),
+        }
     }
 }
 
@@ -232,6 +249,21 @@ pub enum AutoSpawnEntityTexture {
     Random(Vec<usize>),
 }
 
+impl AutoSpawnEntityTexture {
+    pub fn validate(&mut self) {
+        if let AutoSpawnEntityTexture::Random(vec) = self {
+            if vec.is_empty() { vec.push(0); } // No texture
+        }
+    }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        match self {
+            AutoSpawnEntityTexture::CustomIndex(index) => *index = f(*index),
+            AutoSpawnEntityTexture::Random(vec) => vec.iter_mut().for_each(|index| *index = f(*index)),
+        }
+    }
+}
+
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
 #[derive(Clone)]
 pub enum AutoSpawnEntityColor {
@@ -248,19 +280,34 @@ impl AutoSpawnEntityColor {
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
 #[derive(Clone)]
-pub struct AutoSpawnSettings {
-    pub amount: usize,
+pub struct AutoSpawnSettings { // A spawn rule: template of the entities, where, how many and when
+    pub amount: usize, // Entities per spawn
     pub position: SpawnPosition,
     pub mass: AutoSpawnMass,
     pub color: AutoSpawnEntityColor,
     pub texture: AutoSpawnEntityTexture,
     pub timer: EntityTimer,
     pub characteristics: EntityCharacteristics,
+    pub every: u64, // Ticks between two spawns
+    pub max: Option<usize>, // Population cap: entities of this rule alive (see Game::spawned)
+    pub schedule: Option<Range<u64>>, // Ticks of the game when the rule spawns
+    pub condition: Option<SpawnCondition>,
 }
 
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
 #[derive(Clone)]
+pub enum SpawnCondition {
+    EntitiesBelow(usize), // Entities on the map
+    EntitiesAbove(usize),
+    RuleBelow(usize, usize), // Entities alive added by a rule: index of the rule in Settings::auto_spawn, amount
+    RuleAbove(usize, usize),
+}
+
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[derive(Clone)]
 pub struct ThrownFoodInfo {
     pub mass_minimum_to_throw: i64,
     pub mass_self_added: i64,
@@ -274,20 +321,35 @@ pub struct ThrownFoodInfo {
     pub characteristics_entity_thrown: EntityCharacteristics,
 }
 
+impl ThrownFoodInfo {
+    pub fn validate(&mut self) {
+        self.power = (*self.power.start()).max(0)..=(*self.power.end()).max((*self.power.start()).max(0));
+        self.angle = (*self.angle.start())..=(*self.angle.end()).max(*self.angle.start());
+        self.color.validate();
+        self.texture.validate();
+        self.characteristics_entity_thrown.validate();
+    }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        self.texture.map_textures(f);
+        self.characteristics_entity_thrown.map_textures(f);
+    }
+}
+
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize, reflect::Reflect))]
+#[cfg_attr(feature = "serialize", serde(default))] // Map definitions only give the fields changed from the default
 #[derive(Clone)]
 pub struct Settings {
     pub background_color: [f32;4],
     pub matrix_color: [f32;4],
+    pub wall_color: [f32;4],
     pub local_player: usize,
     pub local_player_characteristics: EntityCharacteristics,
-    pub local_player_food_settings: ThrownFoodInfo, //TODO: per player instead ? or per entity ?
-    pub unit_speed: f32,
-    pub unit_speed_split: f32,
     pub collision_speed: f32,
-    pub max_split: usize, //TODO: change. per player instead ?
+    pub eat_ratio: f32, // Mass ratio needed to eat a cell of another player
+    pub teams: Vec<TeamSettings>, // PlayerInfo::team is an index in this list
     pub max_cells_spawn: usize,
-    pub auto_spawn: AutoSpawnSettings, // TODO: make it in an array for multiple possibility
+    pub auto_spawn: Vec<AutoSpawnSettings>, // Rules applied in order each tick, up to max_cells_spawn
     pub camera_initial: f32,
     pub max_camera: f32,
     pub special: bool, //TODO: delete
@@ -298,19 +360,93 @@ impl Settings {
         if self.local_player > game.players.len() { self.local_player = game.players.len() - 1 }
         
         self.local_player_characteristics.validate();
+        self.eat_ratio = self.eat_ratio.max(1.0);
 
-        self.local_player_food_settings.power = (*self.local_player_food_settings.power.start()).max(0)..=(*self.local_player_food_settings.power.end()).max((*self.local_player_food_settings.power.start()).max(0));
-        self.local_player_food_settings.angle = (*self.local_player_food_settings.angle.start())..=(*self.local_player_food_settings.angle.end()).max(*self.local_player_food_settings.angle.start());
-        self.local_player_food_settings.color.validate();
-        self.local_player_food_settings.characteristics_entity_thrown.validate();
-
-        self.auto_spawn.position.validate(game);
-        self.auto_spawn.color.validate();
-        self.auto_spawn.characteristics.validate();
-        self.auto_spawn.mass.validate();
+        for rule in self.auto_spawn.iter_mut() {
+            rule.position.validate(game);
+            rule.color.validate();
+            rule.texture.validate();
+            rule.characteristics.validate();
+            rule.mass.validate();
+            rule.every = rule.every.max(1);
+        }
 
         self.max_camera = self.max_camera.max(20.0);
     }
+
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        self.local_player_characteristics.map_textures(f);
+        for rule in self.auto_spawn.iter_mut() {
+            rule.map_textures(f);
+        }
+    }
+}
+
+impl AutoSpawnSettings {
+    pub fn map_textures(&mut self, f: &mut dyn FnMut(usize) -> usize) {
+        self.texture.map_textures(f);
+        self.characteristics.map_textures(f);
+    }
+
+    // Whether the rule spawns at this tick. `spawned`: entities alive of each rule (see Game::spawned)
+    pub fn active(&self, tick: u64, entities: usize, spawned: &[usize]) -> bool {
+        if tick % self.every.max(1) != 0 { return false }
+        if let Some(schedule) = &self.schedule {
+            if !schedule.contains(&tick) { return false }
+        }
+        match self.condition {
+            None => true,
+            Some(SpawnCondition::EntitiesBelow(amount)) => entities < amount,
+            Some(SpawnCondition::EntitiesAbove(amount)) => entities > amount,
+            Some(SpawnCondition::RuleBelow(rule, amount)) => spawned.get(rule).map_or(false, |count| *count < amount),
+            Some(SpawnCondition::RuleAbove(rule, amount)) => spawned.get(rule).map_or(false, |count| *count > amount),
+        }
+    }
+
+    // A neutral entity spawned with these settings, None when the position couldn't be found, is in a wall or out of a circular map
+    pub fn entity(&self, game: &Game, rng: &mut impl Rng) -> Option<EntityInfo> {
+        let position = self.position.sample(game, rng)?;
+        if !game.map.contains(position) || game.map.walls.contains(position) { return None }
+
+        let mass = match &self.mass {
+            AutoSpawnMass::Exact(mass) => { *mass }
+            AutoSpawnMass::Random(vec) => {
+                let index = rng.gen_range(0..vec.len());
+                vec[index]
+            }
+            AutoSpawnMass::RandomRange(range) => {
+                rng.gen_range(range.start..=range.end)
+            }
+        };
+        let mass = mass.max(self.characteristics.mass_min).min(self.characteristics.mass_max);
+
+        let color = match &self.color {
+            AutoSpawnEntityColor::Custom(color) => { *color }
+            AutoSpawnEntityColor::Random(vec) => {
+                let index = rng.gen_range(0..vec.len());
+                vec[index]
+            }
+        };
+
+        let texture = match &self.texture {
+            AutoSpawnEntityTexture::CustomIndex(index) => { *index }
+            AutoSpawnEntityTexture::Random(vec) => {
+                let index = rng.gen_range(0..vec.len());
+                vec[index]
+            }
+        };
+
+        Some(EntityInfo {
+            player: 0,
+            position,
+            speed: Vector2D::new(0.0, 0.0),
+            mass,
+            characteristics: self.characteristics.clone(),
+            timer: self.timer.clone(),
+            color,
+            texture,
+        })
+    }
 }
 
 impl Default for Settings {
@@ -320,55 +456,70 @@ impl Default for Settings {
             background_color: [0.0, 0.0, 0.0, 1.0],
             //background_color: [1.0, 1.0, 1.0, 1.0],
             matrix_color: [1.0, 1.0, 1.0, 1.0],
+            wall_color: [0.5, 0.5, 0.6, 1.0],
             local_player: 1,
             local_player_characteristics: EntityCharacteristics::default(),
-            local_player_food_settings: ThrownFoodInfo {
-                mass_minimum_to_throw: RATIO_MASS * 10,
-                mass_self_added: -RATIO_MASS * 2,
-                mass_entity_thrown: RATIO_MASS * 2,
-                throw_ratio: 1,
-                power: 500..=500,
-                angle: 0.0..=0.0,
-                color: crate::game::entity::ThrownEntityColor::Same,
-                texture: crate::game::entity::ThrownEntityTexture::Same,
-                timer: EntityTimer::default(),
-                characteristics_entity_thrown: EntityCharacteristics {
-                    killer: false,
-                    collide: false,
-                    mass_min: RATIO_MASS * 2,
-                    mass_max: RATIO_MASS * 200,
-                    mass_evolution: None,
-                    on_death: None,
-                    throw_entity: None,
-                    inertia: 20,
-                    ..Default::default()
-                }
-            },
-            unit_speed: 5_000.0,
-            unit_speed_split: 0.05,
             collision_speed: 1.0,
-            max_split: 64,
+            eat_ratio: 1.33,
+            teams: Vec::new(),
             max_cells_spawn: 50_000,
-            auto_spawn: AutoSpawnSettings {
-                amount: 100,
-                position: SpawnPosition::Random,
-                mass: AutoSpawnMass::Exact(RATIO_MASS * 1),
-                color: AutoSpawnEntityColor::Random(Vec::from(DEFAULT_COLOR_RANDOM_UNIFORM)),
-                texture: AutoSpawnEntityTexture::Random(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]),
-                //texture: AutoSpawnEntityTexture::CustomIndex(13),
-                timer: EntityTimer::default(),
-                characteristics: EntityCharacteristics {
-                        killer: false,
-                        collide: false,
-                        mass_min: RATIO_MASS * 1,
-                        mass_max: RATIO_MASS * 1_000_000,
-                        mass_evolution: None,
-                        ..Default::default()
-                    },
-            },
+            auto_spawn: vec![AutoSpawnSettings::default()],
             camera_initial: 50.0,
             max_camera: 5_000.0,
             special: false,
         }
     }
-}
\ No newline at end of file
+}
+
+impl Default for ThrownFoodInfo {
+    fn default() -> ThrownFoodInfo {
+        ThrownFoodInfo {
+            mass_minimum_to_throw: RATIO_MASS * 10,
+            mass_self_added: -RATIO_MASS * 2,
+            mass_entity_thrown: RATIO_MASS * 2,
+            throw_ratio: 1,
+            power: 500..=500,
+            angle: 0.0..=0.0,
+            color: crate::game::entity::ThrownEntityColor::Same,
+            texture: crate::game::entity::ThrownEntityTexture::Same,
+            timer: EntityTimer::default(),
+            characteristics_entity_thrown: EntityCharacteristics {
+                killer: false,
+                collide: false,
+                mass_min: RATIO_MASS * 2,
+                mass_max: RATIO_MASS * 200,
+                mass_evolution: None,
+                on_death: None,
+                throw_entity: None,
+                inertia: 20,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+impl Default for AutoSpawnSettings {
+    fn default() -> AutoSpawnSettings {
+        AutoSpawnSettings {
+            amount: 100,
+            position: SpawnPosition::Random,
+            mass: AutoSpawnMass::Exact(RATIO_MASS * 1),
+            color: AutoSpawnEntityColor::Random(Vec::from(DEFAULT_COLOR_RANDOM_UNIFORM)),
+            texture: AutoSpawnEntityTexture::Random(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]),
+            //texture: AutoSpawnEntityTexture::CustomIndex(13),
+            timer: EntityTimer::default(),
+            characteristics: EntityCharacteristics {
+                killer: false,
+                collide: false,
+                mass_min: RATIO_MASS * 1,
+                mass_max: RATIO_MASS * 1_000_000,
+                mass_evolution: None,
+                ..Default::default()
+            },
+            every: 1,
+            max: None,
+            schedule: None,
+            condition: None,
+        }
+    }
+}
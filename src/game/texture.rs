@@ -0,0 +1,109 @@
+// Textures known by the game, by name. Entities keep the index in this list
+// Maps save the names of the list (GameSerialize::textures) and are remapped with it when loaded,
+// so adding a texture doesn't change the skins of saved maps
+
+pub const DEFAULT_TEXTURES: [&str; 15] = [
+    "default",
+    "skin_1",
+    "skin_2",
+    "skin_3",
+    "skin_4",
+    "skin_5",
+    "skin_6",
+    "skin_7",
+    "skin_8",
+    "skin_9",
+    "skin_10",
+    "skin_11",
+    "skin_12",
+    "skin_13",
+    "skin_14",
+];
+
+#[derive(Clone, Debug)]
+pub struct TextureRegistry {
+    names: Vec<String>,
+}
+
+impl Default for TextureRegistry {
+    fn default() -> TextureRegistry {
+        TextureRegistry {
+            names: DEFAULT_TEXTURES.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+impl TextureRegistry {
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|texture| texture == name)
+    }
+
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names.get(index).map(|name| name.as_str())
+    }
+
+    // New textures go at the end, indices already given never change
+    pub fn register(&mut self, name: &str) -> usize {
+        match self.index(name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_owned());
+                self.names.len() - 1
+            }
+        }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
+// Index in the texture table of a file -> index in the registry
+// An empty table is a file from before the names: indices are the ones of the default textures
+pub struct TextureRemap<'a> {
+    names: &'a [String],
+    table: Vec<Option<usize>>,
+    missing: Vec<String>,
+}
+
+impl<'a> TextureRemap<'a> {
+    pub fn new(names: &'a [String], registry: &TextureRegistry) -> TextureRemap<'a> {
+        let table = if names.is_empty() {
+            DEFAULT_TEXTURES.iter().map(|name| registry.index(name)).collect()
+        } else {
+            names.iter().map(|name| registry.index(name)).collect()
+        };
+        TextureRemap {
+            names,
+            table,
+            missing: Vec::new(),
+        }
+    }
+
+    pub fn get(&mut self, index: usize) -> usize {
+        match self.table.get(index) {
+            Some(Some(new_index)) => *new_index,
+            Some(None) => {
+                let name = match self.names.get(index) {
+                    Some(name) => format!("'{}'", name),
+                    None => format!("'{}'", DEFAULT_TEXTURES[index]),
+                };
+                if !self.missing.contains(&name) { self.missing.push(name); }
+                0
+            }
+            None => {
+                let name = format!("index {} (not in the texture table of the file)", index);
+                if !self.missing.contains(&name) { self.missing.push(name); }
+                0
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        if self.missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("missing textures: {}", self.missing.join(", ")))
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use crate::prelude::*;
 
-use crate::game::entity::EntityFlags;
+use crate::game::{AddEntityKey, AddEntityOrigin};
+use crate::game::entity::{DeathSpeed, EntityFlags, MassTransferTarget, PendingRespawn};
+use crate::game::map::fields_between;
+use crate::game::player::PlayerAction;
+use crate::game::rng::StreamRng;
 use crate::utils::BufferChoice; //TODO: bad
 
 use euclid::default::{Point2D, Vector2D};
@@ -9,6 +13,19 @@ use std::sync::Arc;
 pub struct CacheEntitySolver;
 
 impl CacheEntitySolver {
+    fn receive(game: &Game, entity_index: usize, buffer: BufferChoice) -> Vec<EntityAction> {
+        let mut actions: Vec<(usize, EntityAction)> = match buffer {
+            BufferChoice::First => game.entities.receive_buffer(entity_index).collect(),
+            BufferChoice::Second => game.entities.receive_buffer2(entity_index).collect(),
+        };
+        // Buffers are filled by several threads: their order depends on scheduling.
+        // Same sender and same key: same action, their order doesn't matter
+        if game.step.thread_independent {
+            actions.sort_by_key(|(sender, action)| (*sender, action.canonical_key()));
+        }
+        actions.into_iter().map(|(_, action)| action).collect()
+    }
+
     pub fn solve(game_ptr: *mut Game, entity_index: usize, buffer: BufferChoice) {
         let game = unsafe { &mut *game_ptr };
         let entity = unsafe { &mut (*game_ptr).entities.core[entity_index] };
@@ -18,9 +35,13 @@ impl CacheEntitySolver {
         let entity_timer = unsafe { &mut (*game_ptr).entities.timer[entity_index] };
         let entity_flags = unsafe { &mut (*game_ptr).entities.flags[entity_index] };
         let drawable_entity = unsafe { &mut (*game_ptr).entities.drawable_entities[entity_index] };
+        // Entities added by this one during the tick, see AddEntityKey. The buffers are applied in several passes
+        if entity.added.0 != game.rng.tick { entity.added = (game.rng.tick, 0); }
+        let mut added = entity.added.1;
+        let mut split_rng = None; // One stream for the splits of this pass, keyed by `added`: a split of another pass draws other pieces
         match buffer {
             BufferChoice::First => {
-                for action in game.entities.receive_buffer(entity_index) {
+                for action in Self::receive(game, entity_index, BufferChoice::First) {
                     use EntityAction::*;
                     match action {
                         AddCollisionRatioTime(time) => {
@@ -47,12 +68,10 @@ impl CacheEntitySolver {
                             y = y.min(1_000_000_000).max(-1_000_000_000);
                             entity_position.x += x;
                             entity_position.y += y;
+                            let radius = game.entities.get_radius(entity_index);
+                            game.map.walls.push_out(entity_position, entity_speed, radius, entity_flags.contains(EntityFlags::BOUNCE));
 
-                            if entity_flags.contains(EntityFlags::BOUNCE) {
-                                crate::game_solver::entity_solver::position_solver::bounce(entity_position, entity_speed);
-                            } else {
-                                crate::game_solver::entity_solver::position_solver::not_bounce(entity_position, entity_speed);
-                            }
+                            crate::game_solver::entity_solver::position_solver::edge(&game.map, entity_position, entity_speed, entity_flags.contains(EntityFlags::BOUNCE));
                             
                             entity_flags.insert(EntityFlags::MOVED);
                             drawable_entity.position = *entity_position;
@@ -128,11 +147,14 @@ impl CacheEntitySolver {
                             drawable_entity.color = unsafe { std::mem::transmute(color.center) };
                             drawable_entity.color_2 = unsafe { std::mem::transmute(color.edge) };
                         }
+                        Fed(x, y) => {
+                            entity.fed = Some(Vector2D::new(x, y));
+                        }
                         Split(count) => {
-                            let mut rng = rand::thread_rng();
+                            let rng = split_rng.get_or_insert_with(|| game.rng.stream(RngStream::Split, (entity_index as u64) << 32 | added as u64));
                             use rand::Rng;
                             for i in 0..count {
-                                if game.players[entity.player].entities.len() + i >= game.settings.max_split { break; } //TODO: good ? Not good: Not atomic!! What if multiple Split at the same time
+                                if game.players[entity.player].entities.len() + i >= game.players[entity.player].settings.max_split { break; } //TODO: good ? Not good: Not atomic!! What if multiple Split at the same time
                                 let max_mass_taken = *entity_mass / 2;
                                 if max_mass_taken > entity.characteristics.mass_min {
                                     let mass_taken = rng.gen_range(entity.characteristics.mass_min..max_mass_taken);
@@ -160,19 +182,21 @@ impl CacheEntitySolver {
                                         texture: entity.index.texture,
                                     };
                                     //game.buffer.send(GameAction::AddEntity(Box::new(new_entity_info)));
-                                    game.buffer_add_entity.send(Box::new(new_entity_info));
+                                    let key = AddEntityKey { source: entity.index.unique_id, origin: AddEntityOrigin::Split, sequence: added };
+                                    added += 1;
+                                    game.buffer_add_entity.send((key, Box::new(new_entity_info)));
                                 }
                             }
                         }
                     }
                 }
                 if let Some(killer_index) = entity.killed {
-                    game.entities.send_buffer2(entity_index, EntityAction::KilledConfirmed(killer_index));
+                    game.entities.send_buffer2(entity_index, entity_index, EntityAction::KilledConfirmed(killer_index));
                 }
             }
     
             BufferChoice::Second => {
-                for action in game.entities.receive_buffer2(entity_index) {
+                for action in Self::receive(game, entity_index, BufferChoice::Second) {
                     use EntityAction::*;
                     match action {
                         MulSpeed(x, y) => {
@@ -183,25 +207,41 @@ impl CacheEntitySolver {
                             if entity_speed.y.abs() < 0.1 { entity_speed.y = 0.0; } 
                         }
                         KilledConfirmed(mut killer_index) => { //TODO: Reminder: 2 Cells can't kill each other -> infinite loop
-                            if killer_index == entity.index.main { continue } //TODO: Killed by itself
+                            if killer_index == entity.index.main { // End of its lifetime
+                                if let Some(on_death) = entity.characteristics.on_death.as_ref() {
+                                    let mut rng = game.rng.stream(RngStream::OnDeath, entity_index as u64);
+                                    Self::on_death(game, entity_index, None, on_death, &mut rng, &mut added);
+                                }
+                                continue
+                            }
                             let mut killer = &game.entities.core[killer_index];
                             loop {
                                 if let Some(new_killer) = killer.killed {
-                                    if new_killer == killer_index { game.entities.send_buffer(killer.index.main, EntityAction::AddMass(*entity_mass)); break } //TODO: Killed by itself. Badly done !!
+                                    if new_killer == killer_index { game.entities.send_buffer(entity_index, killer.index.main, EntityAction::AddMass(*entity_mass)); break } //TODO: Killed by itself. Badly done !!
                                     killer_index = new_killer;
                                     killer = &game.entities.core[killer_index];
                                     continue
                                 }
-                                game.entities.send_buffer(killer.index.main, EntityAction::AddMass(*entity_mass));
+                                game.entities.send_buffer(entity_index, killer.index.main, EntityAction::AddMass(*entity_mass));
                                 break
                             }
-                            if let Some(on_death) = entity.characteristics.on_death.clone() {
-                                match on_death {
-                                    OnDeathEffect::Split(count) => {
-                                        if !killer.characteristics.can_split_on_kill { return }
-                                        game.entities.send_buffer(killer.index.main, EntityAction::Split(count));
-                                    }
-                                }
+                            if killer.player != entity.player && game.teammates(killer.player, entity.player).is_none() {
+                                game.players[killer.player].buffer.send(PlayerAction::AteCell(entity.player));
+                            }
+                            if let Some(virus) = entity.characteristics.virus.as_ref() {
+                                // Popped: split as much as the player of the eater can
+                                if killer.player != entity.player { game.entities.send_buffer(entity_index, killer.index.main, EntityAction::Split(virus.pieces)); }
+                            } else if killer.characteristics.virus.is_some() && killer.player == entity.player {
+                                let direction = if *entity_speed != Vector2D::zero() {
+                                    *entity_speed
+                                } else {
+                                    game.map.delta(*entity_position, game.entities.position[killer.index.main]).to_f32()
+                                };
+                                game.entities.send_buffer(entity_index, killer.index.main, EntityAction::Fed(direction.x, direction.y));
+                            }
+                            if let Some(on_death) = entity.characteristics.on_death.as_ref() {
+                                let mut rng = game.rng.stream(RngStream::OnDeath, entity_index as u64);
+                                Self::on_death(game, entity_index, Some(killer.index.main), on_death, &mut rng, &mut added);
                             }
                         }
                         _ => {
@@ -211,5 +251,137 @@ impl CacheEntitySolver {
                 }
             }
         }
+        entity.added.1 = added;
+    }
+
+    // Entities are added through the buffers, killer is None at the end of the lifetime of the entity
+    fn on_death(game: &Game, entity_index: usize, killer_index: Option<usize>, effect: &OnDeathEffect, rng: &mut StreamRng, added: &mut usize) {
+        use rand::Rng;
+        let entity = &game.entities.core[entity_index];
+        let mut key = || {
+            *added += 1;
+            AddEntityKey { source: entity.index.unique_id, origin: AddEntityOrigin::OnDeath, sequence: *added - 1 }
+        };
+        let entity_position = game.entities.position[entity_index];
+        let entity_mass = game.entities.mass[entity_index];
+        let clamp = |position: Point2D<i32>| game.map.confine(position);
+        match effect {
+            OnDeathEffect::Split(count) => {
+                let killer = match killer_index {
+                    Some(killer_index) => &game.entities.core[killer_index],
+                    None => return,
+                };
+                if !killer.characteristics.can_split_on_kill { return }
+                game.entities.send_buffer(entity_index, killer.index.main, EntityAction::Split(*count));
+            }
+            OnDeathEffect::Spawn(info) => {
+                let count = info.entities.len() * info.amount;
+                let rotation = rng.gen_range(0.0..360.0);
+                for (i, template) in info.entities.iter().cycle().take(count).enumerate() {
+                    if template.player >= game.players.len() { continue }
+                    let speed = match info.speed {
+                        DeathSpeed::Radial(speed) => Vector2D::from_angle_and_length(euclid::Angle::degrees(rotation + 360.0 * i as f32 / count as f32), speed),
+                        DeathSpeed::Directional(angle, speed) => Vector2D::from_angle_and_length(euclid::Angle::degrees(angle), speed),
+                        DeathSpeed::Same => game.entities.speed[entity_index],
+                    };
+                    game.buffer_add_entity.send((key(), Box::new(EntityInfo {
+                        position: clamp(entity_position + template.position.to_vector()),
+                        speed: template.speed + speed,
+                        ..template.clone()
+                    })));
+                }
+            }
+            OnDeathEffect::Explode(info) => {
+                if info.particles == 0 { return }
+                let mass = (entity_mass as f32 * info.mass_ratio) as i64 / info.particles as i64;
+                for _ in 0..info.particles {
+                    let characteristics = match &info.characteristics {
+                        ThrownEntityCharacteristics::Same => EntityCharacteristics {
+                            on_death: None, // Particles don't explode again
+                            ..entity.characteristics.clone()
+                        },
+                        ThrownEntityCharacteristics::Custom(characteristics) => *characteristics.clone(),
+                        ThrownEntityCharacteristics::CustomIndex(index) => game.entities_characteristics[*index].clone(),
+                    };
+                    let direction = euclid::Angle::degrees(rng.gen_range(0.0..360.0));
+                    let speed = rng.gen_range(info.speed.start..info.speed.end);
+                    let color = info.color.pick(entity.color, rng);
+                    let texture = info.texture.pick(entity.index.texture, rng);
+                    game.buffer_add_entity.send((key(), Box::new(EntityInfo {
+                        player: entity.player,
+                        position: entity_position,
+                        speed: Vector2D::from_angle_and_length(direction, speed),
+                        mass: mass.max(characteristics.mass_min).min(characteristics.mass_max),
+                        color,
+                        texture,
+                        timer: EntityTimer {
+                            lifetime_left: Some(info.lifetime_left),
+                            ..Default::default()
+                        },
+                        characteristics,
+                    })));
+                }
+            }
+            OnDeathEffect::TransferMass(info) => {
+                let mass = (entity_mass as f32 * info.ratio) as i64;
+                match info.target {
+                    MassTransferTarget::Killer => {
+                        if let Some(killer_index) = killer_index {
+                            game.entities.send_buffer(entity_index, killer_index, EntityAction::AddMass(mass));
+                        }
+                    }
+                    MassTransferTarget::Nearby(radius) => {
+                        let matrix = &game.map.matrix_simple;
+                        let total = game.map.size_total();
+                        let reach = radius.min(total.width.max(total.height)) + 1;
+                        let xs = fields_between(entity_position.x - reach, entity_position.x + reach, total.width, matrix.size_field, game.map.wrap());
+                        let ys = fields_between(entity_position.y - reach, entity_position.y + reach, total.height, matrix.size_field, game.map.wrap());
+                        let mut nearby: Vec<usize> = Vec::new();
+                        for &x in xs.iter() {
+                            for &y in ys.iter() {
+                                for cell in matrix[x][y].iter() {
+                                    let other = cell.entity;
+                                    if other == entity_index || game.players[game.entities.core[other].player].kind == PlayerKind::Neutral { continue }
+                                    // On a torus the other cell can be on the other side of a border
+                                    let delta = game.map.delta(entity_position, game.entities.position[other]);
+                                    if delta.to_f32().length() > radius as f32 || game.map.walls.blocks(entity_position, entity_position + delta) { continue }
+                                    nearby.push(other);
+                                }
+                            }
+                        }
+                        if nearby.is_empty() { return }
+                        let share = mass / nearby.len() as i64;
+                        for other in nearby {
+                            game.entities.send_buffer(entity_index, other, EntityAction::AddMass(share));
+                        }
+                    }
+                }
+            }
+            OnDeathEffect::Respawn(info) => {
+                if info.lives == Some(0) { return }
+                let characteristics = EntityCharacteristics {
+                    on_death: entity.characteristics.on_death.as_ref().and_then(OnDeathEffect::respawned),
+                    ..entity.characteristics.clone()
+                };
+                game.buffer_respawn.send((key(), PendingRespawn {
+                    tick: game.rng.tick + info.delay,
+                    info: EntityInfo {
+                        player: entity.player,
+                        position: clamp(info.position.unwrap_or(entity_position)),
+                        speed: Vector2D::zero(),
+                        mass: info.mass.unwrap_or(entity_mass).max(characteristics.mass_min).min(characteristics.mass_max),
+                        color: entity.color,
+                        texture: entity.index.texture,
+                        timer: info.timer.clone(),
+                        characteristics,
+                    },
+                }));
+            }
+            OnDeathEffect::Multiple(effects) => {
+                for effect in effects.iter() {
+                    Self::on_death(game, entity_index, killer_index, effect, rng, added);
+                }
+            }
+        }
     }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 
 
 use crate::prelude::*;
+use crate::game::{AddEntityKey, AddEntityOrigin};
+use crate::game::entity::EntityFlags;
 
 use crate::new_timer_monothread;
 use std::sync::atomic::AtomicUsize;
@@ -19,22 +21,33 @@ impl<'a> CacheGameSolver<'a> {
     
     pub fn solve(&mut self) {
         let game_bis = unsafe { &*(self.game as *const Game) };
-        new_timer_monothread!(_t, "apply_cache_game");
+        new_timer_monothread!(self.game, _t, "apply_cache_game");
         {
-            new_timer_monothread!(_bench_add, "apply_cache_game_add");
-            let add_entity_infos:Vec<Box<EntityInfo>> = self.game.buffer_add_entity.receive().collect();
-            for info in add_entity_infos {
+            new_timer_monothread!(self.game, _bench_add, "apply_cache_game_add");
+            let mut add_entity_infos: Vec<(AddEntityKey, Box<EntityInfo>)> = self.game.buffer_add_entity.receive().collect();
+            if self.game.step.thread_independent {
+                add_entity_infos.sort_unstable_by_key(|(key, _)| *key);
+            }
+            for (key, info) in add_entity_infos {
                 self.game.new_entity(*info);
+                if key.origin == AddEntityOrigin::Throw {
+                    let entity = self.game.entities.len() - 1;
+                    self.game.entities.flags[entity].insert(EntityFlags::THROWN);
+                }
             }
         }
         {
-            new_timer_monothread!(_bench_delete, "apply_cache_game_delete");
-            let kill_entity_infos:Vec<Weak<AtomicUsize>> = self.game.buffer_kill_entity.receive().collect();
+            new_timer_monothread!(self.game, _bench_delete, "apply_cache_game_delete");
+            let mut kill_entity_infos:Vec<Weak<AtomicUsize>> = self.game.buffer_kill_entity.receive().collect();
+            if self.game.step.thread_independent {
+                kill_entity_infos.sort_by_key(|entity_index| entity_index.upgrade().map(|index| index.load(Ordering::Relaxed)));
+            }
             for entity_index in kill_entity_infos {
                 let index = entity_index.upgrade().expect("Bug Kill entity twice").load(Ordering::Relaxed);
                 let entity = unsafe { &mut *(&mut self.game.entities.core[index] as *mut EntityCore) };
                 let player = unsafe { &mut *(&mut self.game.players[entity.player] as *mut Player) };
                 self.game.id_generator.remove(entity.index.unique_id);
+                self.game.spawned_removed(entity.spawn_rule);
                 self.game.map.delete_entity(&self.game.entities, entity);
                 player.entities.swap_remove(entity.index.player);
                 if player.entities.len() != entity.index.player {
@@ -53,11 +66,11 @@ impl<'a> CacheGameSolver<'a> {
     }
     /*
     pub fn solve_multithread(&mut self) {
-        new_timer_monothread!(_t, "apply_cache_game");
-        new_timer_monothread!(bench_delete, "apply_cache_game_delete");
+        new_timer_monothread!(self.game, _t, "apply_cache_game");
+        new_timer_monothread!(self.game, bench_delete, "apply_cache_game_delete");
         self.delete_entities_multithread3();
         drop(bench_delete);
-        new_timer_monothread!(bench_add, "apply_cache_game_add");
+        new_timer_monothread!(self.game, bench_add, "apply_cache_game_add");
         self.add_entities_multithread2();
         drop(bench_add);
     }
@@ -160,7 +173,7 @@ impl<'a> CacheGameSolver<'a> {
         let new_max_len = self.game.entities.len() - amount_deleted;
         let entities_to_replace = Arc::new(Mutex::new(Vec::with_capacity(amount_deleted)));
         {
-            new_timer_monothread!(_t, "to_replace");
+            new_timer_monothread!(self.game, _t, "to_replace");
             let atomic_count = Arc::new(AtomicUsize::new(0));
             let step = 128;
             for _ in 0..self.game.threadpool.num_threads().max(1) {
@@ -199,7 +212,7 @@ impl<'a> CacheGameSolver<'a> {
         let amout_to_replace = entities_to_replace.len();
         let mut entities_to_move = Vec::with_capacity(amout_to_replace);
         {
-            new_timer_monothread!(_t, "to_move");
+            new_timer_monothread!(self.game, _t, "to_move");
             let atomic_len_entities = AtomicUsize::new(self.game.entities.len());
             {
                 let max = atomic_len_entities.fetch_sub(amount_deleted, Ordering::Relaxed);
@@ -213,7 +226,7 @@ impl<'a> CacheGameSolver<'a> {
             assert!(entities_to_move.len() == entities_to_replace.len());
         }
         {
-            new_timer_monothread!(_t, "move");
+            new_timer_monothread!(self.game, _t, "move");
 
             let atomic_count = Arc::new(AtomicUsize::new(0));
             let step = 128;
@@ -252,7 +265,7 @@ impl<'a> CacheGameSolver<'a> {
             self.game.threadpool.sync_spin()
         }
         {
-            new_timer_monothread!(_t, "drop");
+            new_timer_monothread!(self.game, _t, "drop");
             for i in new_max_len..self.game.entities.len() {
                 unsafe { std::ptr::drop_in_place(&mut self.game.entities.core[i]); } // TODO: No dealloc
                 /*
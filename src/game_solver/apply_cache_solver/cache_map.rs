@@ -16,7 +16,7 @@ impl<'a> CacheMapSolver<'a> {
         }
     }
     pub fn solve(&mut self) { //TODO: Still monothread / also maybe delete this ?
-        new_timer_monothread!(_t, "apply_cache_map");
+        new_timer_monothread!(self.game, _t, "apply_cache_map");
         let game_bis = unsafe { &*(self.game as *const Game) };
         let mut entity_killed_matrix = Vec::new();
         let mut entity_killed_matrix_physics = Vec::new();
@@ -54,7 +54,7 @@ impl<'a> CacheMapSolver<'a> {
                 }
             }
         }
-        new_timer_monothread!(_t, "apply_cache_map_kill");
+        new_timer_monothread!(self.game, _t, "apply_cache_map_kill");
         self.kill_entity(entity_killed_matrix, entity_killed_matrix_physics);
     }
 
@@ -1,5 +1,5 @@
 use crate::game::Game;
-use crate::game::player::PlayerAction;
+use crate::game::player::{PlayerAction, PlayerKind};
 use crate::new_timer_monothread;
 use std::sync::atomic::Ordering;
 
@@ -15,7 +15,8 @@ impl<'a> CachePlayerSolver<'a> {
     }
 
     pub fn solve(&mut self) {
-        new_timer_monothread!(_t, "apply_cache_player");
+        new_timer_monothread!(self.game, _t, "apply_cache_player");
+        let mut eaten = Vec::new(); // (killer, victim)
         for index_player in 0..self.game.players.len() {
             let mut entity_killed = Vec::new();
             let player = &mut self.game.players[index_player];
@@ -37,6 +38,15 @@ impl<'a> CachePlayerSolver<'a> {
                             player.entities[entity.index.player] = entity.index.main;
                         }
                     }
+
+                    PlayerAction::AteCell(victim) => {
+                        player.stats.cells_eaten += 1;
+                        eaten.push((index_player, victim));
+                    }
+
+                    PlayerAction::Decayed(mass) => {
+                        player.stats.mass_decayed += mass;
+                    }
                 }
             }
             entity_killed.sort_unstable_by( |a, b| if a < b { std::cmp::Ordering::Greater } else { std::cmp::Ordering::Less } ); // Invert sort: Necessary in order to be sure that a potential moved index with swap_remove is a valid index.
@@ -47,5 +57,32 @@ impl<'a> CachePlayerSolver<'a> {
                 }
             }
         }
+        self.update_stats(eaten);
+    }
+
+    // Once the entities of the players are up to date: mass, deaths and the players eaten this tick
+    fn update_stats(&mut self, mut eaten: Vec<(usize, usize)>) {
+        let mut died = Vec::new();
+        let entities_mass = &self.game.entities.mass;
+        for (index_player, player) in self.game.players.iter_mut().enumerate() {
+            let mass: i64 = player.entities.iter().map(|entity| entities_mass[*entity]).sum();
+            let stats = &mut player.stats;
+            stats.mass = mass;
+            stats.peak_mass = stats.peak_mass.max(mass);
+            if !player.entities.is_empty() {
+                stats.ticks_alive += 1;
+            } else if stats.alive {
+                stats.deaths += 1;
+                // The food of the neutral player running out isn't a player eaten
+                if player.kind != PlayerKind::Neutral { died.push(index_player); }
+            }
+            stats.alive = !player.entities.is_empty();
+        }
+        if died.is_empty() { return }
+        eaten.sort_unstable();
+        eaten.dedup();
+        for (killer, victim) in eaten {
+            if died.contains(&victim) { self.game.players[killer].stats.players_eaten += 1; }
+        }
     }
 }
\ No newline at end of file
@@ -35,7 +35,7 @@ impl<'a> ApplyCacheSolver<'a> {
 
     fn apply_cache_entities(&mut self) { //TODO: change this
         let game = unsafe_ptr::UnsafePtr::new(self.game);
-        new_timer_monothread!(_t, "apply_cache_entity");
+        new_timer_monothread!(self.game, _t, "apply_cache_entity");
         
         self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), 512, | index: usize | {
             if self.game.entities.buffer_is_some(index) {
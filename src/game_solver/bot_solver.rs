@@ -0,0 +1,162 @@
+use crate::prelude::*;
+
+use crate::game::bot::BotSettings;
+use crate::game::map::fields_between;
+use super::player_solver::PlayerSolver;
+
+use crate::new_timer_monothread;
+
+use euclid::default::{Point2D, Vector2D};
+use rand::Rng;
+
+const SPLIT_REACH: f32 = 4.0; // Distance reached by a split, in radius of the cell
+
+// Decisions of the bots, every `reaction` ticks: flee the bigger cells, else chase the smaller ones, else eat the nearest food.
+// Teammates are neither threats nor preys
+pub struct BotSolver<'a> {
+    game: &'a mut Game,
+}
+
+enum Decision {
+    Flee(Point2D<i32>, Point2D<i32>), // Target, nearest threat
+    Chase(Point2D<i32>, bool), // Prey, in reach of a split
+    Eat(Point2D<i32>),
+    Wander,
+}
+
+impl<'a> BotSolver<'a> {
+    pub fn new(game: &'a mut Game) -> BotSolver<'a> {
+        BotSolver {
+            game,
+        }
+    }
+
+    pub fn solve(&mut self) {
+        new_timer_monothread!(self.game, _t, "bots");
+        for player in 0..self.game.players.len() {
+            let settings = match &self.game.players[player].kind {
+                PlayerKind::Bot(settings) => settings.clone(),
+                _ => continue,
+            };
+            // Offset by the player, the bots don't all think at the same tick
+            if (self.game.rng.tick + player as u64) % settings.reaction.max(1) != 0 { continue }
+            let mut rng = self.game.rng.stream(RngStream::Bot, player as u64);
+
+            if self.game.players[player].entities.is_empty() {
+                if let Some(mass) = settings.respawn {
+                    let max = self.game.map.max();
+                    let position = Point2D::new(rng.gen_range(0..max.width), rng.gen_range(0..max.height));
+                    PlayerSolver::new(self.game, player).respawn(position, mass);
+                }
+                continue
+            }
+
+            let target = match self.decide(player, &settings) {
+                Decision::Flee(target, threat) => {
+                    if rng.gen::<f32>() < settings.throw_chance {
+                        let threat = aimed(threat, settings.aim, &mut rng);
+                        PlayerSolver::new(self.game, player).throw(threat, &mut rng);
+                    }
+                    target
+                }
+                Decision::Chase(prey, in_reach) => {
+                    let prey = aimed(prey, settings.aim, &mut rng);
+                    if in_reach && rng.gen::<f32>() < settings.split_chance {
+                        PlayerSolver::new(self.game, player).split(prey);
+                    }
+                    prey
+                }
+                Decision::Eat(food) => aimed(food, settings.aim, &mut rng),
+                Decision::Wander => self.wander(player, &mut rng),
+            };
+
+            let target = self.game.map.confine(target);
+            for entity_index in self.game.players[player].entities.iter() {
+                self.game.entities.direction[*entity_index] = Some(target);
+            }
+        }
+    }
+
+    // Looks around the biggest cell of the bot
+    fn decide(&self, player: usize, settings: &BotSettings) -> Decision {
+        let game = &*self.game;
+        let cells = &game.players[player].entities;
+        let biggest = match cells.iter().max_by_key(|entity| game.entities.mass[**entity]) {
+            Some(biggest) => *biggest,
+            None => return Decision::Wander,
+        };
+        let mass = game.entities.mass[biggest] as f32;
+        let position = game.entities.position[biggest];
+        let radius = game.entities.get_radius(biggest);
+        let view = radius + settings.view as f32;
+        let eat_ratio = game.settings.eat_ratio;
+
+        let matrix = &game.map.matrix_simple;
+        let (total, reach) = (game.map.size_total(), view as i32 + 1);
+        let xs = fields_between(position.x - reach, position.x + reach, total.width, matrix.size_field, game.map.wrap());
+        let ys = fields_between(position.y - reach, position.y + reach, total.height, matrix.size_field, game.map.wrap());
+
+        let mut flee: Vector2D<f32> = Vector2D::zero();
+        let mut threat: Option<(f32, Point2D<i32>)> = None;
+        let mut prey: Option<(f32, Point2D<i32>, f32)> = None;
+        let mut food: Option<(f32, Point2D<i32>)> = None;
+        for &x in xs.iter() {
+            for &y in ys.iter() {
+                for cell in matrix[x][y].iter() {
+                    let other = &game.entities.core[cell.entity];
+                    if other.player == player || game.teammates(player, other.player).is_some() { continue }
+                    if other.characteristics.virus.is_some() { continue } //TODO: avoid them when big enough to be popped
+                    let other_position = position + game.map.delta(position, cell.position);
+                    let distance = (other_position - position).to_f32().length();
+                    if distance > view { continue }
+                    let other_mass = game.entities.mass[cell.entity] as f32;
+
+                    if other.characteristics.killer && other_mass > mass * eat_ratio {
+                        if !settings.flee { continue }
+                        // Away from the threats, the nearest ones count more
+                        flee += (position - other_position).to_f32() / distance.max(1.0).powi(2);
+                        if threat.map_or(true, |(nearest, _)| distance < nearest) { threat = Some((distance, other_position)); }
+                        continue
+                    }
+                    if other.characteristics.invincible || mass <= other_mass * eat_ratio { continue }
+                    if game.players[other.player].kind == PlayerKind::Neutral {
+                        if food.map_or(true, |(nearest, _)| distance < nearest) { food = Some((distance, other_position)); }
+                    } else if settings.chase {
+                        if prey.map_or(true, |(nearest, _, _)| distance < nearest) { prey = Some((distance, other_position, other_mass)); }
+                    }
+                }
+            }
+        }
+
+        if let Some((_, threat)) = threat {
+            if flee.length() == 0.0 { flee = Vector2D::new(1.0, 0.0); }
+            let target = position.to_f32() + flee.normalize() * view;
+            return Decision::Flee(target.to_i32(), threat)
+        }
+        if let Some((distance, prey, prey_mass)) = prey {
+            // Each half must still be able to eat the prey
+            let in_reach = mass / 2.0 > prey_mass * eat_ratio
+                && distance < radius * SPLIT_REACH
+                && cells.len() < game.players[player].settings.max_split;
+            return Decision::Chase(prey, in_reach)
+        }
+        if let Some((_, food)) = food { return Decision::Eat(food) }
+        Decision::Wander
+    }
+
+    // Keeps going to the same place until reached, then picks another one
+    fn wander(&self, player: usize, rng: &mut impl Rng) -> Point2D<i32> {
+        let entity_index = self.game.players[player].entities[0];
+        let position = self.game.entities.position[entity_index];
+        if let Some(direction) = self.game.entities.direction[entity_index] {
+            if self.game.map.distance(position, direction) > self.game.entities.get_radius(entity_index) { return direction }
+        }
+        let max = self.game.map.max();
+        self.game.map.confine(Point2D::new(rng.gen_range(0..=max.width), rng.gen_range(0..=max.height)))
+    }
+}
+
+fn aimed(target: Point2D<i32>, aim: i32, rng: &mut impl Rng) -> Point2D<i32> {
+    if aim <= 0 { return target }
+    target + Vector2D::new(rng.gen_range(-aim..=aim), rng.gen_range(-aim..=aim))
+}
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use crate::game::player::LEADERBOARD_SIZE;
 use crate::new_timer_monothread;
 use threadpool::utils::ParallelIterator;
 
@@ -16,7 +17,7 @@ impl<'a> DrawingBufferSolver<'a> {
     }
     #[allow(dead_code)]
     pub fn solve_multithread(&mut self) { // TODO: Refactor it
-        new_timer_monothread!(_t, "synchronize_drawing_buffer");
+        new_timer_monothread!(self.game, _t, "synchronize_drawing_buffer");
         //if self.game.update_count % 5 != 0 { return }
         let data = unsafe { self.game.drawable.set_with_ptr() };
         data.entities.clear();
@@ -68,11 +69,13 @@ impl<'a> DrawingBufferSolver<'a> {
         data.state = state;
         data.local_player = local_player;
         data.background_color = self.game.settings.background_color;
+        data.leaderboard = self.game.leaderboard(LEADERBOARD_SIZE);
+        data.local_player_stats = self.game.players[self.game.settings.local_player].stats.clone();
         //unsafe { self.game.drawable.change_ptr_last_set() };
     }
 
     pub fn solve_2(&mut self) {
-        new_timer_monothread!(_t, "synchronize_drawing_buffer");
+        new_timer_monothread!(self.game, _t, "synchronize_drawing_buffer");
         let game = unsafe_ptr::UnsafePtr::new(self.game);
 
         let data = unsafe { self.game.drawable.set_with_ptr() };
@@ -98,6 +101,8 @@ impl<'a> DrawingBufferSolver<'a> {
         data.state = state;
         data.local_player = local_player;
         data.background_color = self.game.settings.background_color;
+        data.leaderboard = self.game.leaderboard(LEADERBOARD_SIZE);
+        data.local_player_stats = self.game.players[self.game.settings.local_player].stats.clone();
     }
 
     fn draw_color_pression(&self) {
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use crate::game::map::Map;
 use euclid::default::Point2D;
 
 pub struct CollisionsDetectionSolverInner<'a> {
@@ -27,7 +28,7 @@ impl<'a> CollisionsDetectionSolverInner<'a> {
             let matrix_physics_xy = &matrix[x][y];
             for cell in matrix_physics_xy.iter() {
                 if cell.entity <= entity.index.main { continue }
-                if !test_collide((entity_position, entity_mass), (cell.position, cell.mass)) { continue }
+                if !test_collide(&game.map, (entity_position, entity_mass), (cell.position, cell.mass)) { continue }
                 if !check_entity(game, self.entity.index.main, cell.entity) { continue }
                 entities.push(cell.entity);
             }
@@ -40,7 +41,7 @@ impl<'a> CollisionsDetectionSolverInner<'a> {
     
                 let matrix_physics_xy = &matrix[x][y];
                 for cell in matrix_physics_xy.iter() {
-                    if !test_collide((entity_position, entity_mass), (cell.position, cell.mass)) { continue }
+                    if !test_collide(&game.map, (entity_position, entity_mass), (cell.position, cell.mass)) { continue }
                     if !check_entity(game, self.entity.index.main, cell.entity) { continue }
                     entities.push(cell.entity);
                 }
@@ -59,11 +60,11 @@ impl<'a> CollisionsDetectionSolverInner<'a> {
 }
 
 #[inline]
-fn test_collide((entity_position, entity_mass): (Point2D<i32>, f32), (other_position, other_mass): (Point2D<i32>, f32)) -> bool {
+fn test_collide(map: &Map, (entity_position, entity_mass): (Point2D<i32>, f32), (other_position, other_mass): (Point2D<i32>, f32)) -> bool {
     let entity_radius = (entity_mass / std::f32::consts::PI).sqrt();
     let other_radius = (other_mass / std::f32::consts::PI).sqrt();
     let distance_to_collide = entity_radius + other_radius;
-    let distance = (entity_position - other_position).to_f32().length();
+    let distance = map.distance(other_position, entity_position);
 
     if distance < distance_to_collide { return true } else { return false }
 }
@@ -76,7 +77,7 @@ fn check_entity(game: &Game, entity_index: usize, other_index: usize) -> bool {
     let other = &game.entities.core[other_index];
     let other_timer = &game.entities.timer[other_index];
 
-    if other.player != entity.player { return false }
+    if other.player != entity.player && !game.teammates(entity.player, other.player).map_or(false, |team| team.merge) { return false }
     //if entity_player.kind == PlayerKind::Player && entity_timer.mergeable == None && other_timer.mergeable == None { return false } //TODO: Check if correct
     if entity_timer.mergeable == None && other_timer.mergeable == None && !entity.characteristics.collide_when_mergeable && !other.characteristics.collide_when_mergeable { return false } //TODO: Check if correct
     if entity_timer.collision.is_some() || other_timer.collision.is_some() { return false }
@@ -93,7 +94,7 @@ fn prepare_collide(game: &Game, entity_index: usize, other_index: usize) {
     let entity_position = game.entities.position[entity_index];
     let other_position = game.entities.position[other.index.main];
     let distance_to_collide = game.entities.get_radius(entity.index.main) + game.entities.get_radius(other_index);
-    let distance = (entity_position - other_position).to_f32().length();
+    let distance = game.map.distance(other_position, entity_position);
 
     if distance < distance_to_collide {
         //entity.colliding_info.colliding_pression_new.set(entity.colliding_info.colliding_pression_new.get() + (distance_to_collide - distance) / distance_to_collide);
@@ -23,7 +23,7 @@ impl<'a> CollisionsSolver<'a> {
     }
 
     pub fn solve(&self) {
-        new_timer_monothread!(_t, "detection");
+        new_timer_monothread!(self.game, _t, "detection");
         //let entities_colliding = &self.game.entities;
         self.init_cache_solver();
 
@@ -39,10 +39,10 @@ impl<'a> CollisionsSolver<'a> {
             self.game.entities.init_colliding_info(entity.index.main);
         });
 
-        drop(_t); new_timer_monothread!(_t, "compute");
-        for _ in 0..5 { //TODO: not sure about this
+        drop(_t); new_timer_monothread!(self.game, _t, "compute");
+        for iteration in 0..5 { //TODO: not sure about this
             self.game.threadpool.compute_iter_each_thread_join(entities_colliding, self.step, |entity| {
-                CollisionsReactionSolver::new(entity, self.game).solve();
+                CollisionsReactionSolver::new(entity, self.game, iteration).solve();
             });
 
             self.game.threadpool.compute_iter_each_thread_join(entities_colliding, self.step, |entity| {
@@ -60,8 +60,8 @@ impl<'a> CollisionsSolver<'a> {
             let new_speed = (new_position - old_position).to_f32() / 2.0 + old_speed;
             entity.colliding_info.clear_buffer_collider();
             entity.colliding_info.shrink_to_fit_buffer_collider();
-            self.game.entities.send_buffer(entity.index.main, EntityAction::AddPosition(new_position.x - old_position.x, new_position.y - old_position.y));
-            self.game.entities.send_buffer(entity.index.main, EntityAction::AddSpeed(new_speed.x - old_speed.x, new_speed.y - old_speed.y));
+            self.game.entities.send_buffer(entity.index.main, entity.index.main, EntityAction::AddPosition(new_position.x - old_position.x, new_position.y - old_position.y));
+            self.game.entities.send_buffer(entity.index.main, entity.index.main, EntityAction::AddSpeed(new_speed.x - old_speed.x, new_speed.y - old_speed.y));
         });
     }
 
@@ -5,13 +5,28 @@ use euclid::default::{Point2D, Vector2D};
 pub struct CollisionsReactionSolver<'a> {
     entity: &'a EntityCore,
     game: &'a Game,
+    iteration: usize,
 }
 
 impl<'a> CollisionsReactionSolver<'a> {
-    pub fn new(entity: &'a EntityCore, game: &'a Game) -> CollisionsReactionSolver<'a> {
+    pub fn new(entity: &'a EntityCore, game: &'a Game, iteration: usize) -> CollisionsReactionSolver<'a> {
         CollisionsReactionSolver {
             entity: entity,
             game: game,
+            iteration,
+        }
+    }
+
+    fn rng(&self) -> crate::game::rng::StreamRng {
+        let key = (self.entity.index.main as u64) << 8 | self.iteration as u64;
+        self.game.rng.stream(RngStream::Collision, key)
+    }
+
+    fn colliders(&self) -> Vec<usize> {
+        if self.game.step.thread_independent {
+            self.entity.colliding_info.entities_colliding_sorted()
+        } else {
+            self.entity.colliding_info.entities_colliding().iter().copied().collect()
         }
     }
 
@@ -29,7 +44,9 @@ impl<'a> CollisionsReactionSolver<'a> {
         let mut new_pression = 1.0;
         let mut total_moving = Vector2D::zero();
         let mut total_speed = Vector2D::zero();
-        for other_index in entity.colliding_info.entities_colliding().iter() {
+        use rand::Rng;
+        let mut rng = self.rng();
+        for other_index in self.colliders().iter() {
             let other = &self.game.entities.core[*other_index];
             let other_mass = self.game.entities.mass[*other_index];
             let other_radius = self.game.entities.get_radius(*other_index);
@@ -40,7 +57,7 @@ impl<'a> CollisionsReactionSolver<'a> {
 
             // Compute distance
             //let distance = (entity.position - other.position).to_f32();
-            let distance = (entity.colliding_info.colliding_position.get() - other.colliding_info.colliding_position.get()).to_f32();
+            let distance = self.game.map.delta(other.colliding_info.colliding_position.get(), entity.colliding_info.colliding_position.get()).to_f32();
             let distance_difference = distance_to_collide - distance.length(); if distance_difference < 0.0 { continue };
             new_pression += (distance_difference / distance_to_collide) * 2.0 * pression.sqrt();
 
@@ -56,9 +73,6 @@ impl<'a> CollisionsReactionSolver<'a> {
             
             // Speed with mass ratio
             moving *= ratio_count;
-
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
             
             if distance.x.abs() < 5.0 {
                 moving.x = rng.gen_range(-5.0..5.0) * ratio_count;
@@ -88,12 +102,15 @@ impl<'a> CollisionsReactionSolver<'a> {
             let total_moving = round_vector(total_moving);
             let total_speed = total_moving / 2.0;
             entity.colliding_info.colliding_position_new.set(entity.colliding_info.colliding_position.get() + total_moving.to_i32());
-            entity.colliding_info.colliding_position_new.set(
-                Point2D::new(
-                entity.colliding_info.colliding_position_new.get().x.max(0).min(self.game.map.max().width),
-                entity.colliding_info.colliding_position_new.get().y.max(0).min(self.game.map.max().height)
-                )
-            );
+            // On a torus the position is wrapped when the move is applied
+            if !self.game.map.wrap() {
+                entity.colliding_info.colliding_position_new.set(
+                    Point2D::new(
+                    entity.colliding_info.colliding_position_new.get().x.max(0).min(self.game.map.max().width),
+                    entity.colliding_info.colliding_position_new.get().y.max(0).min(self.game.map.max().height)
+                    )
+                );
+            }
             // TODO: speed badly calculated (and not used by the way)
             entity.colliding_info.colliding_speed_new.set(entity.colliding_info.colliding_speed.get() + total_speed);
         }
@@ -108,7 +125,9 @@ impl<'a> CollisionsReactionSolver<'a> {
         let mut total_count = 0;
         let mut total_moving = Vector2D::zero();
         let mut total_speed = Vector2D::zero();
-        for other_index in entity.colliding_info.entities_colliding().iter() {
+        use rand::Rng;
+        let mut rng = self.rng();
+        for other_index in self.colliders().iter() {
             total_count += 1;
             let other = &self.game.entities.core[*other_index];
             let other_mass = self.game.entities.mass[*other_index];
@@ -116,7 +135,7 @@ impl<'a> CollisionsReactionSolver<'a> {
             let distance_to_collide = entity_radius + other_radius;
             // Compute distance
             //let distance = (entity.position - other.position).to_f32();
-            let distance = (entity.colliding_info.colliding_position.get() - other.colliding_info.colliding_position.get()).to_f32();
+            let distance = self.game.map.delta(other.colliding_info.colliding_position.get(), entity.colliding_info.colliding_position.get()).to_f32();
             let ratio_mass = (other_mass as f32 / (entity_mass as f32 + other_mass as f32) as f32).min(0.99);
             let distance_difference = distance_to_collide - distance.length(); if distance_difference < 0.0 { continue };
             let max_speed = distance_difference;
@@ -129,8 +148,6 @@ impl<'a> CollisionsReactionSolver<'a> {
             // Speed with mass ratio
             moving *= ratio_mass;
 
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
             if distance.x.abs() < 5.0 {
                 moving.x = rng.gen_range(-5.0..5.0) * ratio_mass;
             }
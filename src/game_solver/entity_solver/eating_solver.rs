@@ -1,4 +1,7 @@
 use crate::prelude::*;
+use crate::game::map::{Map, fields_between};
+
+use crate::game::entity::EntityFlags;
 
 use euclid::default::Point2D;
 
@@ -27,23 +30,20 @@ impl<'a> EatingSolver<'a> {
         let entity = self.entity;
         let game = self.game;
 
-        let (x_field, y_field, _z) = game.entities.index_matrix_simple[entity.index.main].xyz();
         let radius = self.game.entities.get_radius(entity.index.main);
-        let scope_field = (radius / game.map.matrix_simple.size_field as f32) as i32 + 1;
-        
-        let x_min = (x_field as i32 - scope_field).max(0);
-        let x_max = (x_field as i32 + scope_field).min(game.map.matrix_simple.size.width - 1);
-        let y_min = (y_field as i32 - scope_field).max(0);
-        let y_max = (y_field as i32 + scope_field).min(game.map.matrix_simple.size.height - 1);
-
         let entity_position = game.entities.position[entity.index.main];
 
-        for x in x_min..=x_max { // Don't change ..= to .. !! We need to take x_max !
-            for y in y_min..=y_max {
-                for cell in game.map.matrix_simple[x as usize][y as usize].iter() { //TODO: optimize by storing info of entity directly in Matrix ?? More cache efficient
+        // Fields around the entity, on the other side of the map too on a torus
+        let (total, size_field, reach) = (game.map.size_total(), game.map.matrix_simple.size_field, radius as i32 + 1);
+        let xs = fields_between(entity_position.x - reach, entity_position.x + reach, total.width, size_field, game.map.wrap());
+        let ys = fields_between(entity_position.y - reach, entity_position.y + reach, total.height, size_field, game.map.wrap());
+
+        for &x in xs.iter() {
+            for &y in ys.iter() {
+                for cell in game.map.matrix_simple[x][y].iter() { //TODO: optimize by storing info of entity directly in Matrix ?? More cache efficient
                     if cell.entity == entity.index.main { continue }
                     let other = &game.entities.core[cell.entity];
-                    if !check_position(entity_position, radius, cell.position) { continue }
+                    if !check_position(&game.map, entity_position, radius, cell.position) { continue }
                     manage_kill(game, entity, other);
                 }
             }
@@ -62,7 +62,7 @@ impl<'a> EatingSolver<'a> {
             for cell in game.map.matrix_simple[x as usize][y as usize].iter() { //TODO: optimize by storing info of entity directly in Matrix ?? More cache efficient
                 if cell.entity == entity.index.main { continue }
                 let other = &game.entities.core[cell.entity];
-                if !check_position(entity_position, radius, cell.position) { continue }
+                if !check_position(&game.map, entity_position, radius, cell.position) { continue }
                 manage_kill(game, entity, other);
             }
         }
@@ -70,8 +70,8 @@ impl<'a> EatingSolver<'a> {
 }
 
 #[inline]
-fn check_position(position_1: Point2D<i32>, radius_1: f32, position_2: Point2D<i32>) -> bool {
-    return (position_1 - position_2).to_f32().length() < radius_1
+fn check_position(map: &Map, position_1: Point2D<i32>, radius_1: f32, position_2: Point2D<i32>) -> bool {
+    return map.distance(position_2, position_1) < radius_1
 }
     
 fn manage_kill(game: &Game, entity: &EntityCore, other: &EntityCore) { //TODO: bad name and bad design ?
@@ -83,7 +83,15 @@ fn manage_kill(game: &Game, entity: &EntityCore, other: &EntityCore) { //TODO: b
     let other_mass = game.entities.mass[other.index.main];
     let other_timer = &game.entities.timer[other.index.main];
 
-    if entity.player == other.player {
+    let team = game.teammates(entity.player, other.player);
+    if let Some(virus) = entity.characteristics.virus.as_ref() {
+        // Only fed by the mass thrown into it
+        if entity.player != other.player || other.characteristics.virus.is_some() { return }
+        if other_mass > virus.feed_mass_max || !game.entities.flags[other.index.main].contains(EntityFlags::THROWN) { return }
+        killed = true;
+    } else if other.characteristics.virus.is_some() && entity.player == other.player {
+        return
+    } else if entity.player == other.player || team.map_or(false, |team| team.merge) {
         if !other.characteristics.mergeable { return }
         if entity_timer.mergeable == None && other_timer.mergeable == None {
             if entity_mass > other_mass {
@@ -96,7 +104,8 @@ fn manage_kill(game: &Game, entity: &EntityCore, other: &EntityCore) { //TODO: b
         }
     } else {
         if other.characteristics.invincible == true { return }
-        if entity_mass as f32 > other_mass as f32 * 1.33 {
+        if team.map_or(false, |team| !team.feed) { return }
+        if entity_mass as f32 > other_mass as f32 * game.settings.eat_ratio {
             killed = true;
         }
     }
@@ -107,8 +116,10 @@ fn manage_kill(game: &Game, entity: &EntityCore, other: &EntityCore) { //TODO: b
         let radius_entity = game.entities.get_radius(entity.index.main);
         let radius_other = game.entities.get_radius(other.index.main);
         let scope = radius_entity - 0.5 * radius_other;
-        if (entity_position - other_position).to_f32().length() < scope {
-            game.entities.send_buffer(other.index.main, EntityAction::Killed(entity.index.main));
+        // On a torus the other entity can be on the other side of a border
+        let delta = game.map.delta(entity_position, other_position);
+        if delta.to_f32().length() < scope && !game.map.walls.blocks(entity_position, entity_position + delta) {
+            game.entities.send_buffer(entity.index.main, other.index.main, EntityAction::Killed(entity.index.main));
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use crate::game::Game;
 use crate::game::entity::EntityAction;
 use crate::game::entity::DistanceRatio;
+use crate::game::map::fields_between;
 
 use euclid::default::Point2D;
 use euclid::default::Vector2D;
@@ -29,19 +30,14 @@ impl<'a> GravitySolver<'a> {
                 apply_gravity(self.game, &info, self.entity_index, entity_position, entity_mass, other);
             }
         } else {
-            let distance = info.distance_limit.end;
-            let (x_field, y_field, _z) = self.game.entities.index_matrix_simple[self.entity_index].xyz();
-            let scope_field = (distance / self.game.map.matrix_simple.size_field as f32) as i32 + 1;
-            
-            let x_min = (x_field as i32 - scope_field).max(0) as usize;
-            let x_max = (x_field as i32 + scope_field).min(self.game.map.matrix_simple.size.width - 1) as usize;
-            let y_min = (y_field as i32 - scope_field).max(0) as usize;
-            let y_max = (y_field as i32 + scope_field).min(self.game.map.matrix_simple.size.height - 1) as usize;
-
-            
-            for x in x_min..=x_max { // Don't change ..= to .. !! We need to take x_max !
-                for y in y_min..=y_max {
-                    for cell in self.game.map.matrix_simple[x][y].iter() { //TODO: optimize by storing info of entity directly in Matrix ?? More cache efficient
+            let map = &self.game.map;
+            let (total, size_field, reach) = (map.size_total(), map.matrix_simple.size_field, info.distance_limit.end.min(1_000_000_000.0) as i32 + 1);
+            let xs = fields_between(entity_position.x - reach, entity_position.x + reach, total.width, size_field, map.wrap());
+            let ys = fields_between(entity_position.y - reach, entity_position.y + reach, total.height, size_field, map.wrap());
+
+            for &x in xs.iter() {
+                for &y in ys.iter() {
+                    for cell in map.matrix_simple[x][y].iter() { //TODO: optimize by storing info of entity directly in Matrix ?? More cache efficient
                         apply_gravity(self.game, &info, self.entity_index, entity_position, entity_mass, cell.entity);
                     }
                 }
@@ -52,7 +48,7 @@ impl<'a> GravitySolver<'a> {
             let other_position = game.entities.position[other_index];
             if other_index == entity_index { return }
             if !game.entities.flags[other_index].contains(crate::game::entity::EntityFlags::MOVABLE) { return }
-            let distance = entity_position - other_position; if distance == euclid::default::Vector2D::zero() { return }
+            let distance = game.map.delta(other_position, entity_position); if distance == euclid::default::Vector2D::zero() { return }
             let distance_f32 = distance.to_f32();
             let distance_length = distance_f32.length();
 
@@ -72,7 +68,7 @@ impl<'a> GravitySolver<'a> {
 
             //if velocity.length() < info.speed_accepted_min { return; }
 
-            game.entities.send_buffer(other_index, EntityAction::AddSpeed(velocity.x, velocity.y));
+            game.entities.send_buffer(entity_index, other_index, EntityAction::AddSpeed(velocity.x, velocity.y));
             //unsafe { (*(&game.entities.speed[other_index] as *const _ as *mut Vector2D<f32>)) += velocity; }
         }
     }
@@ -92,7 +88,7 @@ impl<'a> GravitySolver<'a> {
             let other_mass = self.game.entities.mass[other_index];
             let other_gravity_info = self.game.entities.core[other_index].characteristics.gravity.as_ref().unwrap();
 
-            let distance = other_position - entity_position; if distance == euclid::default::Vector2D::zero() { continue }
+            let distance = self.game.map.delta(entity_position, other_position); if distance == euclid::default::Vector2D::zero() { continue }
             let distance_f32 = distance.to_f32();
             let distance_length = distance_f32.length();
 
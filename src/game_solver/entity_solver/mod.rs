@@ -41,7 +41,7 @@ impl<'a> EntitySolver<'a> {
     }
 
     pub fn solve(&mut self) {
-        new_timer_monothread!(_t, "update_entities");
+        new_timer_monothread!(self.game, _t, "update_entities");
         //self.solve_before_collisions();
         self.solve_before_collisions_2();
         self.solve_collisions();
@@ -50,7 +50,7 @@ impl<'a> EntitySolver<'a> {
 
     #[allow(dead_code)]
     fn solve_before_collisions(&mut self) {
-        new_timer_monothread!(_t, "update_entities_before_collisions");
+        new_timer_monothread!(self.game, _t, "update_entities_before_collisions");
         let game = unsafe_ptr::UnsafePtr::new(self.game);
         self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
             let game = unsafe { game.ref_mut() };
@@ -85,12 +85,12 @@ impl<'a> EntitySolver<'a> {
     }
     
     fn solve_collisions(&mut self) {
-        new_timer_monothread!(_t, "update_entities_collisions");
+        new_timer_monothread!(self.game, _t, "update_entities_collisions");
         CollisionsSolver::new(self.game, self.step).solve();
     }
 
     fn solve_position(&mut self) {
-        new_timer_monothread!(_t, "update_entities_solve_position");
+        new_timer_monothread!(self.game, _t, "update_entities_solve_position");
         let game = unsafe_ptr::UnsafePtr::new(self.game);
         self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
             let game = unsafe { game.ref_mut() };
@@ -101,17 +101,17 @@ impl<'a> EntitySolver<'a> {
 
     #[allow(dead_code)]
     fn solve_before_collisions_2(&mut self) {
-        new_timer_monothread!(_t, "update_entities_before_collisions");
+        new_timer_monothread!(self.game, _t, "update_entities_before_collisions");
         let game = unsafe_ptr::UnsafePtr::new(self.game);
         {
-            new_timer_monothread!(_t, "update_entities_no_interaction");
+            new_timer_monothread!(self.game, _t, "update_entities_no_interaction");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
                 NoInteractionsSolver::new(index, self.game).solve();
             });
         }
         /*
         {
-            new_timer_monothread!(_t, "update_entities_gravity_prepare");
+            new_timer_monothread!(self.game, _t, "update_entities_gravity_prepare");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
                 if self.game.entities.flags[index].contains(EntityFlags::GRAVITY) {
                     self.gravity_entities.lock().push(index);
@@ -119,7 +119,7 @@ impl<'a> EntitySolver<'a> {
             });
         }
         {
-            new_timer_monothread!(_t, "update_entities_gravity_execute");
+            new_timer_monothread!(self.game, _t, "update_entities_gravity_execute");
             let entities_with_gravity = self.gravity_entities.lock().clone();
             if !entities_with_gravity.is_empty() {
                 self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
@@ -130,7 +130,7 @@ impl<'a> EntitySolver<'a> {
         }
         */
         {
-            new_timer_monothread!(_t, "update_entities_gravity");
+            new_timer_monothread!(self.game, _t, "update_entities_gravity");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
                 if self.game.entities.flags[index].contains(EntityFlags::GRAVITY) {
                     GravitySolver::new(index, self.game).solve_3();
@@ -138,7 +138,7 @@ impl<'a> EntitySolver<'a> {
             });
         }
         {
-            new_timer_monothread!(_t, "update_entities_velocity");
+            new_timer_monothread!(self.game, _t, "update_entities_velocity");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
                 let game = unsafe { game.ref_mut() };
                 if game.entities.speed[index] == Vector2D::zero() && game.entities.direction[index] == None { return }
@@ -146,7 +146,7 @@ impl<'a> EntitySolver<'a> {
             });
         }
         {
-            new_timer_monothread!(_t, "update_entities_throw");
+            new_timer_monothread!(self.game, _t, "update_entities_throw");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
                 let entity_core = &self.game.entities.core[index];
                 //if entity_core.characteristics.throw_entity.is_some() {
@@ -156,7 +156,7 @@ impl<'a> EntitySolver<'a> {
             });
         }
         {
-            new_timer_monothread!(_t, "update_entities_eat");
+            new_timer_monothread!(self.game, _t, "update_entities_eat");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
                 let entity_core = &self.game.entities.core[index];
                 //if entity_core.characteristics.killer == true {
@@ -166,7 +166,7 @@ impl<'a> EntitySolver<'a> {
             });
         }
         {
-            new_timer_monothread!(_t, "update_entities_special");
+            new_timer_monothread!(self.game, _t, "update_entities_special");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), self.step, |index| {
                 let entity_special = &self.game.entities.special[index];
                 if !entity_special.is_empty() {
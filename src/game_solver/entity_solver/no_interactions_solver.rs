@@ -1,5 +1,8 @@
-use crate::game::entity::EntityAction;
-use crate::game::Game;
+use crate::game::entity::{EntityAction, EntityInfo, EntityTimer};
+use crate::game::{AddEntityKey, AddEntityOrigin, Game};
+use crate::game::player::{PlayerAction, PlayerKind};
+
+use euclid::default::Vector2D;
 
 pub struct NoInteractionsSolver<'a> {
     game: &'a Game,
@@ -20,6 +23,7 @@ impl<'a> NoInteractionsSolver<'a> {
         self.update_lifetime();
         self.update_timer();
         self.update_mass();
+        self.update_virus();
     }
 
     #[inline]
@@ -36,22 +40,22 @@ impl<'a> NoInteractionsSolver<'a> {
     pub fn update_timer(&self) {
         let entity_timer = &self.game.entities.timer[self.entity_index];
         if entity_timer.mergeable.is_some() {
-            self.game.entities.send_buffer(self.entity_index, EntityAction::AddMergeableTime(-1));
+            self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddMergeableTime(-1));
         }
         if entity_timer.inertia.is_some() {
-            self.game.entities.send_buffer(self.entity_index, EntityAction::AddInertiaTime(-1));
+            self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddInertiaTime(-1));
         }
         if entity_timer.collision.is_some() {
-            self.game.entities.send_buffer(self.entity_index, EntityAction::AddCollisionTime(-1));
+            self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddCollisionTime(-1));
         }
         if entity_timer.collision_ratio.is_some() {
-            self.game.entities.send_buffer(self.entity_index, EntityAction::AddCollisionRatioTime(-1));
+            self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddCollisionRatioTime(-1));
         }
         if let Some(lifetime) = entity_timer.lifetime_left { //TODO: So bad!!
             if lifetime == 0 {
-                self.game.entities.send_buffer(self.entity_index, EntityAction::Killed(self.entity_index));
+                self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::Killed(self.entity_index));
             } else {
-                self.game.entities.send_buffer(self.entity_index, EntityAction::AddLifetimeLeftTime(-1));
+                self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddLifetimeLeftTime(-1));
             }
         }
     }
@@ -64,7 +68,42 @@ impl<'a> NoInteractionsSolver<'a> {
             let entity_mass = self.game.entities.mass[self.entity_index];
             if ratio == 1.0 { return }
             let new_mass = (entity_mass as f32 * ratio) as i64;
-            self.game.entities.send_buffer(self.entity_index, EntityAction::AddMass(new_mass - entity_mass));
+            self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddMass(new_mass - entity_mass));
+            let entity = &self.game.entities.core[self.entity_index];
+            let decayed = entity_mass - new_mass.max(entity.characteristics.mass_min);
+            if decayed > 0 && self.game.players[entity.player].kind != PlayerKind::Neutral {
+                self.game.players[entity.player].buffer.send(PlayerAction::Decayed(decayed));
+            }
         }
     }
-}
\ No newline at end of file
+
+    // Fed enough: half of the virus is fired in the direction of the last feed
+    #[inline]
+    pub fn update_virus(&self) {
+        let entity = &self.game.entities.core[self.entity_index];
+        let virus = match entity.characteristics.virus.as_ref() {
+            Some(virus) => virus,
+            None => return,
+        };
+        let entity_mass = self.game.entities.mass[self.entity_index];
+        if entity_mass < virus.fire_mass { return }
+        let mut direction = entity.fed.unwrap_or_else(|| Vector2D::new(1.0, 0.0));
+        if direction.length() == 0.0 { direction = Vector2D::new(1.0, 0.0); }
+        let direction = direction.normalize();
+
+        let mass_fired = entity_mass / 2;
+        let position = self.game.entities.position[self.entity_index] + (direction * self.game.entities.get_radius(self.entity_index)).to_i32();
+        self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddMass(-mass_fired));
+        let key = AddEntityKey { source: entity.index.unique_id, origin: AddEntityOrigin::Virus, sequence: 0 };
+        self.game.buffer_add_entity.send((key, Box::new(EntityInfo {
+            player: entity.player,
+            position,
+            speed: direction * virus.fire_speed,
+            mass: mass_fired,
+            color: entity.color,
+            texture: entity.index.texture,
+            timer: EntityTimer::default(),
+            characteristics: entity.characteristics.clone(),
+        })));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::prelude::*;
 
-use crate::game::entity::EntityFlags;
+use crate::game::entity::{EdgePolicy, EntityFlags};
+use crate::game::map::{Map, MapTopology};
 
 use euclid::default::Point2D;
+use euclid::default::Size2D;
 use euclid::default::Vector2D;
 
 pub struct PositionSolver<'a> {
@@ -21,80 +23,116 @@ impl<'a> PositionSolver<'a> {
 
     #[inline]
     pub fn solve(&mut self) {
+        let map = &self.game.map;
+        let radius = self.game.entities.get_radius(self.entity_index);
         let entity_position = &mut self.game.entities.position[self.entity_index];
         let entity_speed = &mut self.game.entities.speed[self.entity_index];
         let entity_bounce = self.game.entities.flags[self.entity_index].contains(EntityFlags::BOUNCE);
 
         *entity_speed = entity_speed.min(Vector2D::new(1_000_000_000.0, 1_000_000_000.0)).max(Vector2D::new(-1_000_000_000.0, -1_000_000_000.0));
-        *entity_position += entity_speed.to_i32();
-        
-        if entity_bounce {
-            bounce(entity_position, entity_speed);
-        } else {
-            not_bounce(entity_position, entity_speed);
-        }
+        map.walls.solve(entity_position, entity_speed, radius, entity_bounce);
+
+        let outside = !map.contains(*entity_position);
+        edge(map, entity_position, entity_speed, entity_bounce);
 
         self.game.entities.flags[self.entity_index].insert(EntityFlags::MOVED);
         self.game.entities.drawable_entities[self.entity_index].position = *entity_position;
+
+        // Killed by itself: as at the end of its lifetime
+        if outside && self.game.entities.core[self.entity_index].characteristics.edge == EdgePolicy::Despawn {
+            self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::Killed(self.entity_index));
+        }
+    }
+}
+
+// Brings back in the map an entity going out of it
+#[inline]
+pub fn edge(map: &Map, entity_position: &mut Point2D<i32>, entity_speed: &mut Vector2D<f32>, entity_bounce: bool) {
+    match map.topology {
+        MapTopology::Rectangle => {
+            if entity_bounce {
+                bounce(entity_position, entity_speed, map.max());
+            } else {
+                not_bounce(entity_position, entity_speed, map.max());
+            }
+        }
+        MapTopology::Torus => {
+            *entity_position = map.confine(*entity_position);
+        }
+        MapTopology::Circle => {
+            let (center, radius) = map.circle();
+            let offset = (*entity_position - center).to_f32();
+            let length = offset.length();
+            if length <= radius { return }
+            let normal = offset / length;
+            let normal_speed = entity_speed.dot(normal);
+            if entity_bounce {
+                *entity_position = center + (normal * (2.0 * radius - length).max(0.0)).to_i32();
+                if normal_speed > 0.0 { *entity_speed -= normal * normal_speed * 2.0 }
+            } else {
+                *entity_position = map.confine(*entity_position);
+                if normal_speed > 0.0 { *entity_speed -= normal * normal_speed }
+            }
+        }
     }
 }
 
 #[inline]
-pub fn bounce(entity_position: &mut Point2D<i32>, entity_speed: &mut Vector2D<f32>) {
+pub fn bounce(entity_position: &mut Point2D<i32>, entity_speed: &mut Vector2D<f32>, map_max: Size2D<i32>) {
     if entity_position.x <= 0{
         entity_position.x *= -1;
         entity_speed.x = entity_speed.x.abs();
-    } else if entity_position.x >= crate::APP.get().game.map.max().width {
-        entity_position.x = crate::APP.get().game.map.max().width - (entity_position.x - crate::APP.get().game.map.max().width);
+    } else if entity_position.x >= map_max.width {
+        entity_position.x = map_max.width - (entity_position.x - map_max.width);
         entity_speed.x = -entity_speed.x.abs();
     }
     if entity_position.y <= 0 {
         entity_position.y *= -1;
         entity_speed.y = entity_speed.y.abs();
-    } else if entity_position.y >= crate::APP.get().game.map.max().height {
-        entity_position.y = crate::APP.get().game.map.max().height - (entity_position.y - crate::APP.get().game.map.max().height);
+    } else if entity_position.y >= map_max.height {
+        entity_position.y = map_max.height - (entity_position.y - map_max.height);
         entity_speed.y = -entity_speed.y.abs();
     }
 
-    entity_position.x = entity_position.x.min(crate::APP.get().game.map.max().width);
+    entity_position.x = entity_position.x.min(map_max.width);
     entity_position.x = entity_position.x.max(0);
-    entity_position.y = entity_position.y.min(crate::APP.get().game.map.max().height);
+    entity_position.y = entity_position.y.min(map_max.height);
     entity_position.y = entity_position.y.max(0);
 }
 
 #[inline]
 #[allow(dead_code)]
-pub fn bounce_basic(entity_position: &mut Point2D<i32>, entity_speed: &mut Vector2D<f32>) {
+pub fn bounce_basic(entity_position: &mut Point2D<i32>, entity_speed: &mut Vector2D<f32>, map_max: Size2D<i32>) {
     if entity_position.x <= 0 { 
         entity_position.x = 0;
         entity_speed.x = entity_speed.x.abs();
-    } else if entity_position.x >= crate::APP.get().game.map.max().width {
-        entity_position.x = crate::APP.get().game.map.max().width;
+    } else if entity_position.x >= map_max.width {
+        entity_position.x = map_max.width;
         entity_speed.x = -entity_speed.x.abs();
     }
     if entity_position.y <= 0 {
         entity_position.y = 0;
         entity_speed.y = entity_speed.y.abs();
-    } else if entity_position.y >= crate::APP.get().game.map.max().height {
-        entity_position.y = crate::APP.get().game.map.max().height;
+    } else if entity_position.y >= map_max.height {
+        entity_position.y = map_max.height;
         entity_speed.y = -entity_speed.y.abs();
     }
 }
 
 #[inline]
-pub fn not_bounce(entity_position: &mut Point2D<i32>, entity_speed: &mut Vector2D<f32>) {
+pub fn not_bounce(entity_position: &mut Point2D<i32>, entity_speed: &mut Vector2D<f32>, map_max: Size2D<i32>) {
     if entity_position.x <= 0 { 
         entity_position.x = 0;
         entity_speed.x = 0.0;
-    } else if entity_position.x >= crate::APP.get().game.map.max().width {
-        entity_position.x = crate::APP.get().game.map.max().width;
+    } else if entity_position.x >= map_max.width {
+        entity_position.x = map_max.width;
         entity_speed.x = 0.0;
     }
     if entity_position.y <= 0 {
         entity_position.y = 0;
         entity_speed.y = 0.0;
-    } else if entity_position.y >= crate::APP.get().game.map.max().height {
-        entity_position.y = crate::APP.get().game.map.max().height;
+    } else if entity_position.y >= map_max.height {
+        entity_position.y = map_max.height;
         entity_speed.y = 0.0;
     }
 }
\ No newline at end of file
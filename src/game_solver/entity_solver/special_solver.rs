@@ -11,6 +11,11 @@ thread_local! {
     };
 }
 
+thread_local! {
+    static GAME: std::cell::Cell<*const Game> = std::cell::Cell::new(std::ptr::null());
+    static ENTITY: std::cell::Cell<usize> = std::cell::Cell::new(0); // Entity running the script, sender of its actions
+}
+
 thread_local! {
     pub static ITERATOR_LIST: std::cell::RefCell<IteratorList> = {
         std::cell::RefCell::new(IteratorList::new())
@@ -96,6 +101,12 @@ impl<'a> SpecialSolver<'a> {
     }
 
     pub fn solve(&mut self) -> Result<()> {
+        GAME.with( |game| {
+            game.set(self.game as *const Game);
+        });
+        ENTITY.with( |entity| {
+            entity.set(self.entity);
+        });
         WASM.with( |wasm| {
             wasm.instance_fn_start.as_ref().unwrap().call(self.entity as u64).unwrap();
         });
@@ -107,52 +118,61 @@ impl<'a> SpecialSolver<'a> {
     }
 }
 
+// Game of the SpecialSolver running on this thread, used by the wasm host functions
+fn game() -> &'static Game {
+    GAME.with( |game| unsafe { &*game.get() })
+}
+
+fn entity() -> usize {
+    ENTITY.with( |entity| entity.get())
+}
+
 fn add_function_to_wasm(store: &wasmtime::Store) -> HashMap<&'static str, wasmtime::Func> {
     let mut funcs = HashMap::new();
     
     funcs.insert("num_entities", wasmtime::Func::wrap(store, move || {
-        crate::APP.get().game.entities.len() as u64
+        game().entities.len() as u64
     }));
 
     funcs.insert("get_position_x", wasmtime::Func::wrap(store, |index: u64| {
-        let position = crate::APP.get().game.entities.position[index as usize];
+        let position = game().entities.position[index as usize];
         return position.x;
     }));
     funcs.insert("get_position_y", wasmtime::Func::wrap(store, |index: u64| {
-        let position = crate::APP.get().game.entities.position[index as usize];
+        let position = game().entities.position[index as usize];
         return position.y;
     }));
     funcs.insert("get_speed_x", wasmtime::Func::wrap(store, |index: u64| {
-        let speed = crate::APP.get().game.entities.speed[index as usize];
+        let speed = game().entities.speed[index as usize];
         return speed.x;
     }));
     funcs.insert("get_speed_y", wasmtime::Func::wrap(store, |index: u64| {
-        let speed = crate::APP.get().game.entities.speed[index as usize];
+        let speed = game().entities.speed[index as usize];
         return speed.y;
     }));
     funcs.insert("get_mass", wasmtime::Func::wrap(store, |index: u64| {
-        let mass = crate::APP.get().game.entities.mass[index as usize];
+        let mass = game().entities.mass[index as usize];
         return mass;
     }));
     funcs.insert("get_color", wasmtime::Func::wrap(store, |index: u64| {
-        let color = crate::APP.get().game.entities.core[index as usize].color;
+        let color = game().entities.core[index as usize].color;
         return unsafe { std::mem::transmute::<crate::game::entity::EntityColor, u64>(color) };
     }));
 
 
     funcs.insert("add_position", wasmtime::Func::wrap(store, move |index: u64, position_x: i32, position_y: i32| {
-        crate::APP.get().game.entities.send_buffer(index as usize, EntityAction::AddPosition(position_x, position_y));
+        game().entities.send_buffer(entity(), index as usize, EntityAction::AddPosition(position_x, position_y));
     }));
     funcs.insert("add_speed", wasmtime::Func::wrap(store, move |index: u64, speed_x: f32, speed_y: f32| {
-        crate::APP.get().game.entities.send_buffer(index as usize, EntityAction::AddSpeed(speed_x, speed_y));
+        game().entities.send_buffer(entity(), index as usize, EntityAction::AddSpeed(speed_x, speed_y));
     }));
     funcs.insert("set_color", wasmtime::Func::wrap(store, move |index: u64, color_center: u32, color_edge: u32| {
-        crate::APP.get_mut().game.entities.send_buffer(index as usize, EntityAction::SetColor( unsafe { std::mem::transmute([color_center, color_edge]) } ));
+        game().entities.send_buffer(entity(), index as usize, EntityAction::SetColor( unsafe { std::mem::transmute([color_center, color_edge]) } ));
     }));
 
     
     funcs.insert("iter_entities", wasmtime::Func::wrap(store, || {
-        let entities_iterator = 0..crate::APP.get().game.entities.len() as u64;
+        let entities_iterator = 0..game().entities.len() as u64;
         let mut iterator_id = 0;
         ITERATOR_LIST.with(|list| {
             iterator_id = list.borrow_mut().add_iterator_entities(entities_iterator);
@@ -161,7 +181,7 @@ fn add_function_to_wasm(store: &wasmtime::Store) -> HashMap<&'static str, wasmti
     }));
 
     funcs.insert("iter_entities_near", wasmtime::Func::wrap(store, |entity_index: u64, distance: f32| {
-        let entities_iterator_near = IteratorEntitiesNear::new(&crate::APP.get().game, entity_index as usize, distance);
+        let entities_iterator_near = IteratorEntitiesNear::new(game(), entity_index as usize, distance);
         let mut iterator_id = 0;
         ITERATOR_LIST.with(|list| {
             iterator_id = list.borrow_mut().add_iterator_entities_near(entities_iterator_near);
@@ -207,7 +227,7 @@ impl IteratorList {
                 result = self.list_entities[id_array[1] as usize].next();
             },
             1 => {
-                result = self.list_entities_near[id_array[1] as usize].next(&crate::APP.get().game);
+                result = self.list_entities_near[id_array[1] as usize].next(game());
             }
             _ => {panic!()}
         }
@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use crate::game::entity::ThrownEntityColor;
+use crate::game::{AddEntityKey, AddEntityOrigin};
 
 use euclid::Angle;
 use euclid::default::{Point2D, Rotation2D, Vector2D};
@@ -29,7 +29,7 @@ impl<'a> ThrowingEntitySolver<'a> {
         
         if entity_mass > info.mass_minimum_to_throw {
             use rand::Rng;
-            let mut rng = rand::thread_rng();
+            let mut rng = game.rng.stream(RngStream::Throw, entity.index.main as u64);
 
             let quantity = ((entity_mass as f32).sqrt() * 0.001 * info.throw_ratio) as i32 + 1;
             /* //TODO
@@ -38,7 +38,7 @@ impl<'a> ThrowingEntitySolver<'a> {
             let rest_quantity_i32 = if rest_quantity > rng.gen() { 1 } else { 0 };
             let quantity = quantity.trunc() as i32 + rest_quantity_i32;
             */
-            for _ in 0..quantity {
+            for sequence in 0..quantity.max(0) as usize {
                 let angle = Angle::degrees(rng.gen_range(info.direction.start..info.direction.end));
                 let direction = Rotation2D::new(angle).transform_vector(Vector2D::new(1.0, 0.0));
                 let power = rng.gen_range(info.power.start..info.power.end) as f32;
@@ -51,31 +51,8 @@ impl<'a> ThrowingEntitySolver<'a> {
                     ThrownEntityCharacteristics::CustomIndex(index) => { game.entities_characteristics[*index].clone() }
                 };
 
-                let color = match &info.color {
-                    ThrownEntityColor::Same => {
-                        entity.color
-                    }
-                    ThrownEntityColor::Custom(color) => {
-                        *color
-                    }
-                    ThrownEntityColor::Random(vec) => {
-                        let index = rng.gen_range(0..vec.len());
-                        vec[index]
-                    }
-                };
-
-                let texture = match &info.texture {
-                    ThrownEntityTexture::Same => {
-                        entity.index.texture
-                    }
-                    ThrownEntityTexture::CustomIndex(index) => {
-                        *index
-                    }
-                    ThrownEntityTexture::Random(vec) => {
-                        let index = rng.gen_range(0..vec.len());
-                        vec[index]
-                    }
-                };
+                let color = info.color.pick(entity.color, &mut rng);
+                let texture = info.texture.pick(entity.index.texture, &mut rng);
                 let new_entity_info = EntityInfo { // TODO: First in entity buffer, and only if not dead ( Throw entity only if the thrower is not dead, to avoid having infinite creation if it is eated )
                     player: 0,
                     position: Point2D::new(position.x, position.y),
@@ -87,8 +64,9 @@ impl<'a> ThrowingEntitySolver<'a> {
                     texture: texture,
                 };
                 //game.buffer.send(GameAction::AddEntity(Box::new(new_entity_info)));
-                game.buffer_add_entity.send(Box::new(new_entity_info));
-                self.game.entities.send_buffer(entity.index.main, EntityAction::AddMass(info.mass_self_added));
+                let key = AddEntityKey { source: entity.index.unique_id, origin: AddEntityOrigin::Throw, sequence };
+                game.buffer_add_entity.send((key, Box::new(new_entity_info)));
+                self.game.entities.send_buffer(entity.index.main, entity.index.main, EntityAction::AddMass(info.mass_self_added));
             }
         }
     }
@@ -20,7 +20,7 @@ impl<'a> VelocitySolver<'a> {
     pub fn solve(&self) {
         if let Some(new_speed) = self.compute_speed() {
             let old_entity_speed = self.game.entities.speed[self.entity_index];
-            self.game.entities.send_buffer(self.entity_index, EntityAction::AddSpeed(new_speed.x - old_entity_speed.x, new_speed.y - old_entity_speed.y));
+            self.game.entities.send_buffer(self.entity_index, self.entity_index, EntityAction::AddSpeed(new_speed.x - old_entity_speed.x, new_speed.y - old_entity_speed.y));
         }
     }
 
@@ -55,7 +55,7 @@ impl<'a> VelocitySolver<'a> {
         if let Some(direction) = entity_direction {
             //let entity_core = &self.game.entities.core[self.entity_index];
             let entity_mass = self.game.entities.mass[self.entity_index];
-            let distance = *direction - self.game.entities.position[self.entity_index];
+            let distance = self.game.map.delta(self.game.entities.position[self.entity_index], *direction);
             if distance == Vector2D::zero() { return Vector2D::zero(); }
             let distance_f32 = distance.to_f32();
             let distance_length = distance_f32.length();
@@ -65,7 +65,8 @@ impl<'a> VelocitySolver<'a> {
             let delta_speed = distance_length.min(distance_before_slow) / distance_before_slow;
 
             // Compute speed
-            let speed = delta_speed * self.game.settings.unit_speed / ((entity_mass as f32).log10());
+            let player = &self.game.players[self.game.entities.core[self.entity_index].player];
+            let speed = delta_speed * player.settings.unit_speed / ((entity_mass as f32).log10());
             
             // Compute moving
             let angle = distance_f32.normalize();
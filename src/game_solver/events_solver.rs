@@ -2,7 +2,8 @@ use std::{borrow::Borrow};
 
 use crate::prelude::*;
 
-use crate::game::entity::entities::Entities;
+use crate::game::replay::{InputKey, TickInput};
+use crate::gui::Gui;
 use crate::window::Events;
 use crate::new_timer_monothread;
 use crate::renderer::Camera;
@@ -13,28 +14,35 @@ use winit::event::VirtualKeyCode;
 pub struct EventsSolver<'a> {
     game: &'a mut Game,
     events: &'a mut Events,
+    gui: &'a Gui,
     camera: Camera,
 }
 
 impl<'a> EventsSolver<'a> {
-    pub fn new(game: &'a mut Game, events: &'a mut Events, camera: Camera) -> EventsSolver<'a> {
+    pub fn new(game: &'a mut Game, events: &'a mut Events, gui: &'a Gui, camera: Camera) -> EventsSolver<'a> {
         EventsSolver {
             game,
             events,
+            gui,
             camera
         }
     }
 
-    pub fn solve(&mut self) {
+    // Returns the input of the local player when playing
+    pub fn solve(&mut self) -> Option<TickInput> {
+        let mut input = None;
         match self.game.state {
             GameState::MainMenu => {
                 
             }
             GameState::Editor => {
                 self.check_events_editor();
+            }
+            GameState::Results => {
+
             }
             GameState::Playing => {
-                self.check_events_playing();
+                input = Some(self.check_events_playing());
                 
                 #[cfg(not(feature = "shipping"))]
                 if *crate::DEBUG.get() == true {
@@ -43,114 +51,54 @@ impl<'a> EventsSolver<'a> {
             }
         }
         self.events.buffer_events.clear();
+        input
     }
 
-    fn check_events_playing(&mut self) {
-        new_timer_monothread!(_t, "check_events");
-        let local_player = unsafe { & *(&self.game.players[self.game.settings.local_player] as *const Player) };
-        let entities = unsafe { &*(&self.game.entities as *const Entities) };
+    fn check_events_playing(&mut self) -> TickInput {
+        let mut input = TickInput::default();
 
-        match self.game.gui.state.try_borrow().unwrap().clone() {
-            crate::game::gui::GUIState::Open(_) => {
-                for entity_index in local_player.entities.iter() {
-                    self.game.entities.direction[*entity_index] = None;
-                }
-                return
+        match self.gui.state.try_borrow().unwrap().clone() {
+            crate::gui::GUIState::Open(_) => {
+                input.gui_open = true;
             }
             _ => {}
         }
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
 
         // If mouse is not pointing GUI, update the position of mouse in the world. NECESSARY!!
-        if !self.events.mouse_events.mouse_on_gui {
+        if !input.gui_open && !self.events.mouse_events.mouse_on_gui {
             let size = *self.events.resize_events.size.lock().unwrap();
             self.events.mouse_events.update_mouse_position_world(size, &self.camera);
-            for entity_index in local_player.entities.iter() {
-                self.game.entities.direction[*entity_index] = Some(self.events.mouse_events.mouse_position_world);
-            }
         }
+        input.mouse_position_world = self.events.mouse_events.mouse_position_world;
+        input.mouse_on_gui = self.events.mouse_events.mouse_on_gui;
 
-        for (event, _camera_and_position_world) in self.events.buffer_events.iter() {
+        for (event, camera_and_position_world) in self.events.buffer_events.iter() {
             use input_events::event::ButtonEventKind;
             match event {
                 input_events::event::Event::Keyboard(event) => {
-                    let (_camera, position_world) = _camera_and_position_world.as_ref().unwrap();
                     use input_events::event::KeyboardButton;
                     if event.kind == ButtonEventKind::Pressed {
-                        match event.button {
-                            KeyboardButton::R => {
-                                if local_player.entities.is_empty() {
-                                    self.game.new_entity(EntityInfo {
-                                        player: 1,
-                                        position: Point2D::new(rng.gen_range(0..self.game.map.max().width), rng.gen_range(0..self.game.map.max().height)),
-                                        speed: Vector2D::new(0.0, 0.0),
-                                        mass: 1_000_000,
-                                        characteristics: self.game.settings.local_player_characteristics.clone(),
-                                        timer: EntityTimer::default(),
-                                        color: self.game.players[self.game.settings.local_player].cell_default_color,
-                                        texture: self.game.players[self.game.settings.local_player].cell_default_texture,
-                                    });
-                                }
-                            }
-                            KeyboardButton::Space => {
-                                for i in 0..local_player.entities.len() {
-                                    if local_player.entities.len() < self.game.settings.max_split {
-                                        let entity_index = local_player.entities[i];
-                                        let mut entity: EntityRefMut = unsafe { std::mem::transmute(self.game.entities.get_mut(entity_index)) };
-                                        let entity_core = &mut self.game.entities.core[entity_index];
-                                        let entity_position = self.game.entities.position[entity_index];
-                                        let entity_speed = self.game.entities.speed[entity_index];
-                                        let entity_mass = self.game.entities.mass[entity_index];
-                                        let entity_radius = entity.get_radius();
-                                        let entity_timer = &mut self.game.entities.timer[entity_index];
-                                        let mut entity_info = None;
-                                        if entity_mass > 20_000_000 {
-                                            let radius = entity_radius;
-                                            let direction = Vector2D::new(position_world.x, position_world.y);
-                                            let mut speed = (direction - entity_position.to_vector()).to_f32();
-                                            if speed.length() == 0.0 { speed.x = 1.0; }
-                                            let ratio_speed = entity_radius / speed.length();
-                                            speed *= ratio_speed * self.game.settings.unit_speed_split;
-                                            let ratio_position = (radius / 2.0) / speed.length();
-                                            entity_info = Some(EntityInfo {
-                                                player: 1,
-                                                position: Point2D::new(entity_position.x + (speed.x * ratio_position) as i32, entity_position.y + (speed.y * ratio_position) as i32),
-                                                speed: Vector2D::new(speed.x + entity_speed.x, speed.y + entity_speed.y),
-                                                //speed: Vector2D::new(speed.x + entity.speed.x, speed.y + entity.speed.y),
-                                                mass: entity_mass / 2,
-                                                characteristics: entity_core.characteristics.clone(),
-                                                timer: EntityTimer {
-                                                    collision: Some(1),
-                                                    collision_ratio: Some(10),
-                                                    mergeable: Some(1_000),
-                                                    inertia: Some(20),
-                                                    ..Default::default()
-                                                },
-                                                color: entity_core.color,
-                                                texture: entity_core.index.texture,
-                                            });
-                                            entity_timer.mergeable = Some(1_000); // TODO: in buffer instead
-                                        }
-                                        if let Some(entity_info) = entity_info {
-                                            /*
-                                            entity.buffer.send(EntityAction::AddMass(-entity.mass / 2));
-                                            self.game.buffer_add_entity.send(Box::new(entity_info));
-                                            */
-                                            *entity.mass_mut() /= 2;
-                                            self.game.entities.drawable_entities[entity_index].mass = entity.mass() as f32;
-                                            self.game.map.update_entity(entities, entity_core); //TODO: Not good. But needed if multiple spaces in one update. Find another solution
-                                            self.game.new_entity(entity_info);
-                                        }
-                                    }
-                                }
-                            }
-                            _ => {  }
-                        }
+                        let key = match event.button {
+                            KeyboardButton::R => InputKey::R,
+                            KeyboardButton::Space => InputKey::Space,
+                            KeyboardButton::E => InputKey::E,
+                            KeyboardButton::Up => InputKey::Up,
+                            KeyboardButton::Down => InputKey::Down,
+                            KeyboardButton::Add => InputKey::Add,
+                            KeyboardButton::Subtract => InputKey::Subtract,
+                            KeyboardButton::Multiply => InputKey::Multiply,
+                            KeyboardButton::Divide => InputKey::Divide,
+                            _ => continue
+                        };
+                        let (_camera, position_world) = camera_and_position_world.as_ref().unwrap();
+                        input.pressed.push((key, *position_world));
                     }
                 }
-                input_events::event::Event::MouseButton(_event) => {
-                    
+                input_events::event::Event::MouseButton(event) => {
+                    if event.button == input_events::event::MouseButton::Right && event.kind == ButtonEventKind::Pressed {
+                        let (_camera, position_world) = camera_and_position_world.as_ref().unwrap();
+                        input.pressed.push((InputKey::MouseRight, *position_world));
+                    }
                 }
                 input_events::event::Event::MouseMoved(_event) => {
                     
@@ -161,153 +109,33 @@ impl<'a> EventsSolver<'a> {
             }
         }
 
-        if self.events.input_events.state.keyboard.is_pressed(VirtualKeyCode::W) {
-            let throw_food_info = self.game.settings.local_player_food_settings.clone();
-            for i in 0..local_player.entities.len() {
-                for _ in 0..throw_food_info.throw_ratio {
-                    let entity_index = local_player.entities[i];
-                    let entity: EntityRefMut = unsafe { std::mem::transmute(self.game.entities.get_mut(entity_index)) };
-                    let entity_core: &EntityCore = unsafe { std::mem::transmute(&self.game.entities.core[entity_index]) };
-                    let entity_position = self.game.entities.position[entity_index];
-                    let mut entity_info = None;
-                    if entity.mass() > throw_food_info.mass_minimum_to_throw {
-                        self.game.entities.send_buffer(entity_index, EntityAction::AddMass(throw_food_info.mass_self_added));
-                        let radius = entity.get_radius();
-                        let direction_point = Point2D::new(self.events.mouse_events.mouse_position_world.x, self.events.mouse_events.mouse_position_world.y);
-                        let mut direction = (direction_point - entity_position).to_f32();
-                        let direction_angle = direction.angle_from_x_axis();
-                        let direction_speed_angle = euclid::Angle::degrees(direction_angle.to_degrees() + rng.gen_range(throw_food_info.angle.clone()));
-                        let direction_speed = Vector2D::from_angle_and_length(direction_speed_angle, 1.0);
-
-                        if direction.length() == 0.0 { direction.x = 1.0; }
-                        direction = direction.normalize();
-
-                        let ratio_speed = rng.gen_range(throw_food_info.power.clone()) as f32 * ((entity.mass() as f32).log10());
-                        let speed = direction_speed * ratio_speed;
-
-                        let color = match &throw_food_info.color {
-                            crate::game::entity::ThrownEntityColor::Same => {
-                                entity_core.color
-                            }
-                            crate::game::entity::ThrownEntityColor::Custom(color) => {
-                                *color
-                            }
-                            crate::game::entity::ThrownEntityColor::Random(vec) => {
-                                let index = rng.gen_range(0..vec.len());
-                                vec[index]
-                            }
-                        };
-
-                        let ratio_position = radius + 10.0;
-                        entity_info = Some(EntityInfo {
-                            player: 0,
-                            position: Point2D::new(entity_position.x + (direction.x * ratio_position) as i32, entity_position.y + (direction.y * ratio_position) as i32),
-                            speed: Vector2D::new(speed.x, speed.y),
-                            mass: throw_food_info.mass_entity_thrown,
-                            characteristics: throw_food_info.characteristics_entity_thrown.clone(),
-                            timer: throw_food_info.timer.clone(),
-                            color: color,
-                            texture: entity_core.index.texture,
-                        });
-                    }
-                    if let Some(entity_info) = entity_info {
-                        self.game.new_entity(entity_info);
-                    }
-                }
-            }
+        input.throwing = self.events.input_events.state.keyboard.is_pressed(VirtualKeyCode::W);
+        #[cfg(not(feature = "shipping"))]
+        {
+            input.debug = *crate::DEBUG.get();
+            input.debug_add_mass = input.debug && self.events.input_events.state.keyboard.is_pressed(VirtualKeyCode::Z);
         }
+        input
     }
 
     #[cfg(not(feature = "shipping"))]
     fn check_events_playing_debug(&mut self) {
-        let local_player = unsafe { & *(&self.game.players[self.game.settings.local_player] as *const Player) };
-        let entities = unsafe { &*(&self.game.entities as *const Entities) };
-
-        for (event, camera_and_position_world) in self.events.buffer_events.iter() {
-            use input_events::event::ButtonEventKind;
-            match event {
-                input_events::event::Event::Keyboard(event) => {
-                    use input_events::event::KeyboardButton;
-                    if event.kind == ButtonEventKind::Pressed {
-                        match event.button {
-                            KeyboardButton::E => { 
-                                for entity_index in self.game.players[self.game.settings.local_player].entities.iter() {
-                                    self.game.entities.timer[*entity_index].mergeable = None;
-                                }
-                            }
-                            KeyboardButton::R => {
-                                for entity_index in local_player.entities.iter() {
-                                    let mut entity = self.game.entities.get_mut(*entity_index);
-                                    *entity.mass_mut() = 1_000_000;
-                                    self.game.map.update_entity(entities, entity.core_mut());
-                                    self.game.entities.drawable_entities[*entity_index].mass = entity.mass() as f32;
-                                }
-                            }
-                            
-                            KeyboardButton::K => {
-                                
-                            }
-
-                            KeyboardButton::Up => { self.game.threadpool.set_threads(self.game.threadpool.num_threads() + 1); }
-                            KeyboardButton::Down => { self.game.threadpool.set_threads(self.game.threadpool.num_threads().max(1) - 1); }
-
-                            KeyboardButton::Add => { self.game.settings.unit_speed += 100.0; }
-                            KeyboardButton::Subtract => { self.game.settings.unit_speed -= 100.0; }
-                            KeyboardButton::Multiply => { self.game.settings.unit_speed *= 2.0; }
-                            KeyboardButton::Divide => { self.game.settings.unit_speed /= 2.0; }
-                            _ => {  }
-                        }
-                    }
-                }
-                input_events::event::Event::MouseButton(event) => {
-                    match event.button {
-                        input_events::event::MouseButton::Right => {
-                            if event.kind == input_events::event::ButtonEventKind::Pressed {
-                                for i in local_player.entities.iter() {
-                                    let entity = &mut self.game.entities.core[*i];
-                                    let entity_position = &mut self.game.entities.position[*i];
-                                    let position = camera_and_position_world.as_ref().unwrap().1.clone();
-                                    //entity.buffer.send(EntityAction::AddPosition(position.x.max(0).min(self.game.map.max().x) - entity.position.x, position.y.max(0).min(self.game.map.max().y) - entity.position.y));
-                                    entity_position.x = position.x.max(0).min(self.game.map.max().width);
-                                    entity_position.y = position.y.max(0).min(self.game.map.max().height);
-                                    self.game.map.update_entity(entities, entity);
-                                    self.game.entities.update_drawable(*i);
-                                }
-                            }
-                        }
-                        _ => {  }
-                    }
-                }
-                input_events::event::Event::MouseMoved(_event) => {
-                    
-                }
-                input_events::event::Event::MouseWheel(_event) => {
-                    
-                }
-            }
-        }
-        if self.events.input_events.state.keyboard.is_pressed(VirtualKeyCode::Z) {
-            let local_player = &self.game.players[self.game.settings.local_player];
-            for i in local_player.entities.iter() {
-                let entity = &mut self.game.entities.get_mut(*i);
-                *entity.mass_mut() += 100_000_000;
-                self.game.entities.drawable_entities[*i].mass = entity.mass() as f32;
-            }
-        }
-        
         #[cfg(feature = "serialize")]
         (|| {
-            if self.events.input_events.state.keyboard.take(VirtualKeyCode::S) { self.game.to_binary_file(); }
-            if self.events.input_events.state.keyboard.take(VirtualKeyCode::Q) { self.game.from_binary_file(); }
-            if self.events.input_events.state.keyboard.take(VirtualKeyCode::O) { self.game.to_ron_file(); }
-            if self.events.input_events.state.keyboard.take(VirtualKeyCode::P) { self.game.from_ron_file(); }
+            let keyboard = &mut self.events.input_events.state.keyboard;
+            let result = if keyboard.take(VirtualKeyCode::S) { Some(self.game.save_map("maps/Map_test.binmap")) }
+                else if keyboard.take(VirtualKeyCode::Q) { Some(self.game.load_map("maps/Map_test.binmap")) }
+                else if keyboard.take(VirtualKeyCode::O) { Some(self.game.save_map("maps/Map_test.ronmap")) }
+                else if keyboard.take(VirtualKeyCode::P) { Some(self.game.load_map("maps/Map_test.ronmap")) }
+                else { None };
+            if let Some(Err(err)) = result { self.gui.show_message(err); }
         })();
 
         if self.events.input_events.state.keyboard.take(VirtualKeyCode::D) { self.game.state = GameState::Editor }
     }
 
     fn check_events_editor(&mut self) {
-        new_timer_monothread!(_t, "check_events");
+        new_timer_monothread!(self.game, _t, "check_events");
         /*
         if self.events.input_events.state.keyboard.is_pressed(VirtualKeyCode::Up) { APP.get_mut().renderer.camera_future.y -= (10.0 * self.camera.size) as i32; }
         if self.events.input_events.state.keyboard.is_pressed(VirtualKeyCode::Down) { APP.get_mut().renderer.camera_future.y += (10.0 * self.camera.size) as i32; }
@@ -367,13 +195,21 @@ impl<'a> EventsSolver<'a> {
             if let input_events::event::Event::MouseButton(button_event) = &event.0 {
                 if button_event.button == input_events::event::MouseButton::Left {
                     if button_event.kind == input_events::event::ButtonEventKind::Pressed {
-                        let size = self.game.gui.state_2.borrow().size_width_editor as i32;
+                        let size = self.gui.state_2.borrow().size_width_editor as i32;
                         if button_event.location.0 > size && button_event.location.0 < APP.get().window.window.inner_size().width as i32 - size {
                             if self.game.editor_state.tab == 1 || self.game.editor_state.tab == 2 {
                                 self.game.editor_state.selected = None;
                                 self.events.mouse_events.update_mouse_position_world(self.events.resize_events.size.lock().unwrap().clone(), &self.camera);
                                 self.game.editor_state.selection = Some(self.events.mouse_events.mouse_position_world);
                             }
+                            if self.game.editor_state.tab == 3 {
+                                self.events.mouse_events.update_mouse_position_world(self.events.resize_events.size.lock().unwrap().clone(), &self.camera);
+                                self.game.editor_state.image_import.origin = self.events.mouse_events.mouse_position_world;
+                            }
+                            if self.game.editor_state.tab == 4 {
+                                self.events.mouse_events.update_mouse_position_world(self.events.resize_events.size.lock().unwrap().clone(), &self.camera);
+                                self.game.editor_state.wall_points.push(self.events.mouse_events.mouse_position_world);
+                            }
                             if self.game.editor_state.tab == 0 {
                                 if !self.game.editor_state.hovered && self.game.editor_state.new_entity_on_click {
                                     self.events.mouse_events.update_mouse_position_world(self.events.resize_events.size.lock().unwrap().clone(), &self.camera);
@@ -0,0 +1,123 @@
+use crate::prelude::*;
+
+#[cfg(not(feature = "shipping"))]
+use crate::game::entity::entities::Entities;
+use crate::game::replay::{InputKey, TickInput};
+use super::player_solver::PlayerSolver;
+
+use euclid::default::Point2D;
+
+// Applies the input of the local player: from the window, or from a replay
+pub struct InputSolver<'a> {
+    game: &'a mut Game,
+    input: &'a TickInput,
+}
+
+impl<'a> InputSolver<'a> {
+    pub fn new(game: &'a mut Game, input: &'a TickInput) -> InputSolver<'a> {
+        InputSolver {
+            game,
+            input,
+        }
+    }
+
+    pub fn solve(&mut self) {
+        self.solve_playing();
+
+        #[cfg(not(feature = "shipping"))]
+        if self.input.debug {
+            self.solve_playing_debug();
+        }
+    }
+
+    fn solve_playing(&mut self) {
+        let local_player = unsafe { & *(&self.game.players[self.game.settings.local_player] as *const Player) };
+
+        if self.input.gui_open {
+            for entity_index in local_player.entities.iter() {
+                self.game.entities.direction[*entity_index] = None;
+            }
+            return
+        }
+        use rand::Rng;
+        let mut rng = self.game.rng.stream(RngStream::Events, 0);
+
+        if !self.input.mouse_on_gui {
+            for entity_index in local_player.entities.iter() {
+                self.game.entities.direction[*entity_index] = Some(self.input.mouse_position_world);
+            }
+        }
+
+        let local_player_index = self.game.settings.local_player;
+        for (key, position_world) in self.input.pressed.iter() {
+            match key {
+                InputKey::R => {
+                    if local_player.entities.is_empty() {
+                        let position = Point2D::new(rng.gen_range(0..self.game.map.max().width), rng.gen_range(0..self.game.map.max().height));
+                        PlayerSolver::new(self.game, local_player_index).respawn(position, 1_000_000);
+                    }
+                }
+                InputKey::Space => {
+                    PlayerSolver::new(self.game, local_player_index).split(*position_world);
+                }
+                _ => {  }
+            }
+        }
+
+        if self.input.throwing {
+            PlayerSolver::new(self.game, local_player_index).throw(self.input.mouse_position_world, &mut rng);
+        }
+    }
+
+    #[cfg(not(feature = "shipping"))]
+    fn solve_playing_debug(&mut self) {
+        let local_player = unsafe { & *(&self.game.players[self.game.settings.local_player] as *const Player) };
+        let entities = unsafe { &*(&self.game.entities as *const Entities) };
+
+        for (key, position_world) in self.input.pressed.iter() {
+            match key {
+                InputKey::E => {
+                    for entity_index in self.game.players[self.game.settings.local_player].entities.iter() {
+                        self.game.entities.timer[*entity_index].mergeable = None;
+                    }
+                }
+                InputKey::R => {
+                    for entity_index in local_player.entities.iter() {
+                        let mut entity = self.game.entities.get_mut(*entity_index);
+                        *entity.mass_mut() = 1_000_000;
+                        self.game.map.update_entity(entities, entity.core_mut());
+                        self.game.entities.drawable_entities[*entity_index].mass = entity.mass() as f32;
+                    }
+                }
+
+                InputKey::Up => { self.game.threadpool.set_threads(self.game.threadpool.num_threads() + 1); }
+                InputKey::Down => { self.game.threadpool.set_threads(self.game.threadpool.num_threads().max(1) - 1); }
+
+                InputKey::Add => { self.game.players[self.game.settings.local_player].settings.unit_speed += 100.0; }
+                InputKey::Subtract => { self.game.players[self.game.settings.local_player].settings.unit_speed -= 100.0; }
+                InputKey::Multiply => { self.game.players[self.game.settings.local_player].settings.unit_speed *= 2.0; }
+                InputKey::Divide => { self.game.players[self.game.settings.local_player].settings.unit_speed /= 2.0; }
+
+                InputKey::MouseRight => {
+                    for i in local_player.entities.iter() {
+                        let entity = &mut self.game.entities.core[*i];
+                        let entity_position = &mut self.game.entities.position[*i];
+                        //entity.buffer.send(EntityAction::AddPosition(position.x.max(0).min(self.game.map.max().x) - entity.position.x, position.y.max(0).min(self.game.map.max().y) - entity.position.y));
+                        *entity_position = self.game.map.confine(*position_world);
+                        self.game.map.update_entity(entities, entity);
+                        self.game.entities.update_drawable(*i);
+                    }
+                }
+                _ => {  }
+            }
+        }
+        if self.input.debug_add_mass {
+            let local_player = &self.game.players[self.game.settings.local_player];
+            for i in local_player.entities.iter() {
+                let entity = &mut self.game.entities.get_mut(*i);
+                *entity.mass_mut() += 100_000_000;
+                self.game.entities.drawable_entities[*i].mass = entity.mass() as f32;
+            }
+        }
+    }
+}
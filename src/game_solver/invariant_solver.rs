@@ -0,0 +1,143 @@
+use crate::prelude::*;
+
+// Checks the bookkeeping between entities, players and the map. Slow, only for debugging
+pub struct InvariantSolver<'a> {
+    game: &'a Game,
+    pass: &'static str,
+    check_position: bool, // matrix_simple cells are only up to date after MapSolver
+}
+
+impl<'a> InvariantSolver<'a> {
+    pub fn new(game: &'a Game, pass: &'static str, check_position: bool) -> InvariantSolver<'a> {
+        InvariantSolver {
+            game,
+            pass,
+            check_position,
+        }
+    }
+
+    pub fn solve(&self) -> Result<(), String> {
+        self.check_players()?;
+        for index in 0..self.game.entities.len() {
+            self.check_entity(index)?;
+        }
+        self.check_map_count()
+    }
+
+    fn error(&self, index: usize, field: &str, message: String) -> String {
+        let unique_id = self.game.entities.core[index].index.unique_id;
+        format!("after {}: entity {} (unique_id {}), {}: {}", self.pass, index, unique_id, field, message)
+    }
+
+    fn check_entity(&self, index: usize) -> Result<(), String> {
+        let entities = &self.game.entities;
+        let core = &entities.core[index];
+
+        if core.index.main != index {
+            return Err(self.error(index, "index.main", format!("is {}", core.index.main)))
+        }
+        let main_ptr = core.index.main_ptr.load(std::sync::atomic::Ordering::Relaxed);
+        if main_ptr != index {
+            return Err(self.error(index, "index.main_ptr", format!("is {}", main_ptr)))
+        }
+
+        let player = match self.game.players.get(core.player) {
+            Some(player) => player,
+            None => return Err(self.error(index, "player", format!("{} doesn't exist ({} players)", core.player, self.game.players.len()))),
+        };
+        match player.entities.get(core.index.player) {
+            Some(&entity) if entity == index => {}
+            Some(&entity) => return Err(self.error(index, "index.player", format!("players[{}].entities[{}] is {}", core.player, core.index.player, entity))),
+            None => return Err(self.error(index, "index.player", format!("{} is out of players[{}].entities ({} entities)", core.index.player, core.player, player.entities.len()))),
+        }
+
+        let matrix_simple = &self.game.map.matrix_simple;
+        let (x, y, z) = entities.index_matrix_simple[index].xyz();
+        if x >= matrix_simple.size.width as usize || y >= matrix_simple.size.height as usize || !matrix_simple[x][y].contains(z) {
+            return Err(self.error(index, "index_matrix_simple", format!("({}, {}, {}) is empty", x, y, z)))
+        }
+        let content = &matrix_simple[x][y][z];
+        if content.entity != index {
+            return Err(self.error(index, "index_matrix_simple", format!("({}, {}, {}) holds entity {}", x, y, z, content.entity)))
+        }
+        if self.check_position {
+            let position = entities.position[index];
+            let (x_position, y_position) = ((position.x / matrix_simple.size_field) as usize, (position.y / matrix_simple.size_field) as usize);
+            if (x, y) != (x_position, y_position) {
+                return Err(self.error(index, "index_matrix_simple", format!("in cell ({}, {}) but position {:?} is in cell ({}, {})", x, y, position, x_position, y_position)))
+            }
+        }
+
+        if core.characteristics.collide {
+            let inside = &core.index.matrix_physics.inside;
+            if inside.locations.len() == 0 {
+                return Err(self.error(index, "index.matrix_physics", "collide but no cell".to_owned()))
+            }
+            let matrix_list = &self.game.map.matrix_physics.matrix_list;
+            if inside.index < 0 || inside.index as usize >= matrix_list.len() {
+                return Err(self.error(index, "index.matrix_physics.inside.index", format!("{} doesn't exist", inside.index)))
+            }
+            let matrix = self.game.map.matrix_physics.matrix_with_index(inside.index as usize);
+            let size = matrix.get_size_matrix();
+            for &(x, y, z) in inside.locations.iter() {
+                if x >= size.width as usize || y >= size.height as usize || !matrix[x][y].contains(z) {
+                    return Err(self.error(index, "index.matrix_physics", format!("matrix {} ({}, {}, {}) is empty", inside.index, x, y, z)))
+                }
+                if matrix[x][y][z].entity != index {
+                    return Err(self.error(index, "index.matrix_physics", format!("matrix {} ({}, {}, {}) holds entity {}", inside.index, x, y, z, matrix[x][y][z].entity)))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_players(&self) -> Result<(), String> {
+        let entities_len = self.game.entities.len();
+        for (player_index, player) in self.game.players.iter().enumerate() {
+            for (index_player, &entity) in player.entities.iter().enumerate() {
+                if entity >= entities_len {
+                    return Err(format!("after {}: players[{}].entities[{}] is {}, out of entities ({})", self.pass, player_index, index_player, entity, entities_len))
+                }
+                let core = &self.game.entities.core[entity];
+                if core.player != player_index || core.index.player != index_player {
+                    return Err(self.error(entity, "player", format!("in players[{}].entities[{}] but points to players[{}].entities[{}]", player_index, index_player, core.player, core.index.player)))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Every entity points to its cell, so more content than entities means a cell was never removed
+    fn check_map_count(&self) -> Result<(), String> {
+        let matrix_simple = &self.game.map.matrix_simple;
+        let mut count = 0;
+        for x in 0..matrix_simple.size.width as usize {
+            for y in 0..matrix_simple.size.height as usize {
+                count += matrix_simple[x][y].len();
+            }
+        }
+        if count != self.game.entities.len() {
+            return Err(format!("after {}: matrix_simple holds {} entities instead of {}", self.pass, count, self.game.entities.len()))
+        }
+
+        let mut count = 0;
+        for matrix in self.game.map.matrix_physics.matrix_list.iter() {
+            let size = matrix.get_size_matrix();
+            for x in 0..size.width as usize {
+                for y in 0..size.height as usize {
+                    count += matrix[x][y].len();
+                }
+            }
+        }
+        let expected: usize = self.game.entities.core.iter()
+            .filter(|core| core.characteristics.collide)
+            .map(|core| core.index.matrix_physics.inside.locations.len())
+            .sum();
+        if count != expected {
+            return Err(format!("after {}: matrix_physics holds {} cell entries instead of {}", self.pass, count, expected))
+        }
+
+        Ok(())
+    }
+}
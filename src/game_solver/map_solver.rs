@@ -1,11 +1,10 @@
 use crate::prelude::*;
+use crate::game::AddEntityKey;
 use crate::game::entity::entities::Entities;
-use crate::game::entity::EntityFlags;
-use crate::game::settings::AutoSpawnEntityColor;
+use crate::game::entity::{EntityFlags, PendingRespawn};
 
 use crate::new_timer_monothread;
 
-use euclid::default::{Point2D, Vector2D};
 use threadpool::utils::ParallelIterator;
 
 pub struct MapSolver<'a> {
@@ -20,61 +19,59 @@ impl<'a> MapSolver<'a> {
     }
 
     pub fn solve(&mut self) { // TODO: multithread
-        new_timer_monothread!(_t, "update_map");
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        for _ in 0..self.game.settings.auto_spawn.amount { //TODO: do it before cache_game ? as a special Entity ?
-            if self.game.entities.len() > self.game.settings.max_cells_spawn as usize { continue }
-            use crate::game::settings::{AutoSpawnEntityTexture, AutoSpawnMass, SpawnPosition};
-            let position = match self.game.settings.auto_spawn.position.clone() {
-                SpawnPosition::Exact(x, y) => { Point2D::new(x, y) }
-                SpawnPosition::Random => { Point2D::new(rng.gen_range(0..self.game.map.max().width), rng.gen_range(0..self.game.map.max().height)) }
-                SpawnPosition::RandomRange(range_x, range_y) => { Point2D::new(rng.gen_range(range_x.start..range_x.end), rng.gen_range(range_y.start..range_y.end)) }
-            };
+        new_timer_monothread!(self.game, _t, "update_map");
+        self.respawn();
+        self.auto_spawn();
+        //self.update_map();
+        self.update_map_multithread_2();
+        self.shrink_map();
+        
+    }
 
-            let mass = match self.game.settings.auto_spawn.mass.clone() {
-                AutoSpawnMass::Exact(mass) => { mass }
-                AutoSpawnMass::Random(vec) => {
-                    let index = rng.gen_range(0..vec.len());
-                    vec[index]
-                }
-                AutoSpawnMass::RandomRange(range) => {
-                    rng.gen_range(range.start..=range.end)
-                }
-            };
-            let mass = mass.max(self.game.settings.auto_spawn.characteristics.mass_min).min(self.game.settings.auto_spawn.characteristics.mass_max);
+    // Entities back from OnDeathEffect::Respawn once their delay is over
+    fn respawn(&mut self) {
+        let mut respawns: Vec<(AddEntityKey, PendingRespawn)> = self.game.buffer_respawn.receive().collect();
+        if self.game.step.thread_independent {
+            respawns.sort_unstable_by_key(|(key, _)| *key);
+        }
+        self.game.respawns.extend(respawns.into_iter().map(|(_, respawn)| respawn));
+        if self.game.respawns.is_empty() { return }
+
+        let tick = self.game.rng.tick;
+        let (ready, waiting): (Vec<PendingRespawn>, Vec<PendingRespawn>) = std::mem::take(&mut self.game.respawns).into_iter().partition(|respawn| respawn.tick <= tick);
+        self.game.respawns = waiting;
+        for respawn in ready {
+            if respawn.info.player >= self.game.players.len() { continue }
+            self.game.new_entity(respawn.info);
+        }
+    }
 
-            let color = match self.game.settings.auto_spawn.color.clone() {
-                AutoSpawnEntityColor::Custom(color) => { color }
-                AutoSpawnEntityColor::Random(vec) => {
-                    let index = rng.gen_range(0..vec.len());
-                    vec[index]
-                }
+    fn auto_spawn(&mut self) { //TODO: do it before cache_game ? as a special Entity ?
+        let tick = self.game.rng.tick;
+        if self.game.spawned.len() != self.game.settings.auto_spawn.len() {
+            // Rules changed without Game::spawn_rules_moved
+            self.game.count_spawned();
+        }
+        let rules = std::mem::take(&mut self.game.settings.auto_spawn);
+        let spawned = self.game.spawned.clone(); // Conditions see the counts of the start of the tick
+        for (index, rule) in rules.iter().enumerate() {
+            if !rule.active(tick, self.game.entities.len(), &spawned) { continue }
+            let amount = match rule.max {
+                Some(max) => rule.amount.min(max.saturating_sub(spawned[index])),
+                None => rule.amount,
             };
-
-            let texture = match self.game.settings.auto_spawn.texture.clone() {
-                AutoSpawnEntityTexture::CustomIndex(index) => { index }
-                AutoSpawnEntityTexture::Random(vec) => {
-                    let index = rng.gen_range(0..vec.len());
-                    vec[index]
+            let mut rng = self.game.rng.stream(RngStream::AutoSpawn, index as u64);
+            for _ in 0..amount {
+                if self.game.entities.len() > self.game.settings.max_cells_spawn as usize { break }
+                if let Some(info) = rule.entity(self.game, &mut rng) {
+                    self.game.new_entity(info); //TODO: with GameAction instead ?
+                    let entity = self.game.entities.len() - 1;
+                    self.game.entities.core[entity].spawn_rule = Some(index);
+                    self.game.spawned[index] += 1;
                 }
-            };
-
-            self.game.new_entity(EntityInfo { //TODO: with GameAction instead ?
-                player: 0,
-                position,
-                speed: Vector2D::new(0.0, 0.0),
-                mass,
-                characteristics: self.game.settings.auto_spawn.characteristics.clone(),
-                timer: self.game.settings.auto_spawn.timer.clone(),
-                color,
-                texture,
-            });
+            }
         }
-        //self.update_map();
-        self.update_map_multithread_2();
-        self.shrink_map();
-        
+        self.game.settings.auto_spawn = rules;
     }
 
     #[allow(dead_code)]
@@ -99,7 +96,7 @@ impl<'a> MapSolver<'a> {
     #[allow(dead_code)]
     fn update_map_multithread(&mut self) {
         let game = unsafe_ptr::UnsafePtr::new(self.game);
-        new_timer_monothread!(_t, "1");
+        new_timer_monothread!(self.game, _t, "1");
 
         self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), 512, |index: usize| {
             let game = unsafe { game.ref_mut() };
@@ -122,7 +119,7 @@ impl<'a> MapSolver<'a> {
     fn update_map_multithread_2(&mut self) {
         let game = unsafe_ptr::UnsafePtr::new(self.game);
         {
-            new_timer_monothread!(_t, "1");
+            new_timer_monothread!(self.game, _t, "1");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), 512, |index: usize| {
                 let game = unsafe { game.ref_mut() };
                 let moved = game.entities.flags[index].contains(EntityFlags::MOVED);
@@ -146,7 +143,7 @@ impl<'a> MapSolver<'a> {
         }
 
         {
-            new_timer_monothread!(_t, "2");
+            new_timer_monothread!(self.game, _t, "2");
             self.game.threadpool.compute_range_each_thread_join(0..self.game.entities.len(), 512, |index: usize| {
                 let game = unsafe { game.ref_mut() };
                 if game.entities.flags[index].contains(EntityFlags::MATRIX_SIMPLE_TO_CHANGE) {
@@ -158,9 +155,9 @@ impl<'a> MapSolver<'a> {
     }
 
     fn shrink_map(&mut self) {
-        new_timer_monothread!(_t, "shrink_map");
+        new_timer_monothread!(self.game, _t, "shrink_map");
         use rand::Rng;
-        let mut rng = rand::thread_rng();
+        let mut rng = self.game.rng.stream(RngStream::ShrinkMap, 0);
         for _ in 0..100 {
             let i = rng.gen_range(0..self.game.map.matrix_simple.size.width);
             let j = rng.gen_range(0..self.game.map.matrix_simple.size.height);
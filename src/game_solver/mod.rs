@@ -1,47 +1,90 @@
+#[cfg(feature = "graphics")]
 mod events_solver;
+mod input_solver;
+mod player_solver;
+mod bot_solver;
+mod mode_solver;
 mod entity_solver;
 mod apply_cache_solver;
 mod map_solver;
 mod drawing_buffer_solver;
 mod smooth_wait_solver;
+#[cfg(not(feature = "shipping"))]
+mod invariant_solver;
 
+#[cfg(feature = "graphics")]
 use events_solver::EventsSolver;
+use input_solver::InputSolver;
+use bot_solver::BotSolver;
+use mode_solver::ModeSolver;
 use entity_solver::EntitySolver;
 use apply_cache_solver::ApplyCacheSolver;
 use map_solver::MapSolver;
 use drawing_buffer_solver::DrawingBufferSolver;
 use smooth_wait_solver::SmoothWaitSolver;
+#[cfg(not(feature = "shipping"))]
+use invariant_solver::InvariantSolver;
 
-use crate::renderer::Camera;
 use crate::prelude::*;
+use crate::game::StepMode;
+use crate::game::replay::TickInput;
+#[cfg(feature = "graphics")]
+use crate::gui::Gui;
+#[cfg(feature = "graphics")]
+use crate::renderer::Camera;
+#[cfg(feature = "graphics")]
 use crate::window::Events;
 
 #[macro_export]
 macro_rules! new_timer_monothread {
-    ($timer: ident, $name: expr) => {
-        $crate::new_timer_monothread_generic!($crate::APP.get().game.benchmark, $timer, $name);
+    ($game: expr, $timer: ident, $name: expr) => {
+        $crate::new_timer_monothread_generic!($game.benchmark, $timer, $name);
     };
 }
 
+#[cfg(feature = "graphics")]
+pub struct Frontend<'a> {
+    pub events: &'a mut Events,
+    pub gui: &'a Gui,
+    pub camera: Camera,
+}
+
 pub struct GameSolver<'a> {
     game: &'a mut Game,
-    events: &'a mut Events,
-    camera: Camera,
+    #[cfg(feature = "graphics")]
+    frontend: Option<Frontend<'a>>,
     instant: std::time::Instant,
 }
 
 impl<'a> GameSolver<'a> {
-    pub fn new(game: &'a mut Game, events: &'a mut Events, camera: Camera) -> GameSolver<'a> {
+    /// Headless solver: no events, no gui and no waiting between ticks.
+    pub fn new(game: &'a mut Game) -> GameSolver<'a> {
+        GameSolver {
+            game,
+            #[cfg(feature = "graphics")]
+            frontend: None,
+            instant: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(feature = "graphics")]
+    pub fn with_frontend(game: &'a mut Game, frontend: Frontend<'a>) -> GameSolver<'a> {
         GameSolver {
             game,
-            events,
-            camera,
+            frontend: Some(frontend),
             instant: std::time::Instant::now(),
         }
     }
 
+    fn is_headless(&self) -> bool {
+        #[cfg(feature = "graphics")]
+        return self.frontend.is_none();
+        #[cfg(not(feature = "graphics"))]
+        return true;
+    }
+
     pub fn solve(&mut self) {
-        new_timer_monothread!(_t, "total");
+        new_timer_monothread!(self.game, _t, "total");
         match self.game.state {
             GameState::MainMenu => {
                 
@@ -53,14 +96,26 @@ impl<'a> GameSolver<'a> {
                 self.update_drawing_buffer();
             }
             GameState::Playing => {
+                let input = self.check_events();
+                if !self.game.step.paused {
+                    self.apply_input(input);
+                }
+                self.update_gui();
+                if !self.game.step.paused {
+                    self.udpate_world();
+                }
+                self.update_drawing_buffer();
+            }
+            GameState::Results => {
                 self.check_events();
                 self.update_gui();
-                self.udpate_world();
                 self.update_drawing_buffer();
+                self.next_round();
             }
         }
         drop(_t); // hack to update the benchmark time here
         
+        self.count_overrun();
         self.smooth_wait();
         
         self.game.benchmark.save();
@@ -69,35 +124,112 @@ impl<'a> GameSolver<'a> {
         self.game.step.actual_count = if self.game.step.actual_count < 1_000 { self.game.step.actual_count + 1 } else { 0 };
     }
 
-    fn check_events(&mut self) {
-        EventsSolver::new(self.game, self.events, self.camera.clone()).solve();
+    fn check_events(&mut self) -> Option<TickInput> {
+        #[cfg(feature = "graphics")]
+        if let Some(frontend) = self.frontend.as_mut() {
+            return EventsSolver::new(self.game, frontend.events, frontend.gui, frontend.camera.clone()).solve();
+        }
+        None
+    }
+
+    fn apply_input(&mut self, input: Option<TickInput>) {
+        new_timer_monothread!(self.game, _t, "check_events");
+        #[cfg(feature = "serialize")]
+        let input = crate::game::replay::next_input(self.game, input);
+        if let Some(input) = input {
+            InputSolver::new(self.game, &input).solve();
+        }
     }
 
     fn update_gui(&mut self) {
-        self.game.gui.update(self.game);
+        #[cfg(feature = "graphics")]
+        if let Some(frontend) = self.frontend.as_ref() {
+            frontend.gui.update(self.game);
+        }
     }
 
     fn udpate_world(&mut self) {
+        BotSolver::new(self.game).solve();
         EntitySolver::new(self.game).solve();
         ApplyCacheSolver::new(self.game).solve();
+        self.check_invariants("apply_cache", false);
         MapSolver::new(self.game).solve();
+        self.check_invariants("map", true);
         self.special();
+        self.game.rng.advance();
+        ModeSolver::new(self.game).solve();
+        self.world_hash();
+        self.rewind_save();
+    }
+
+    // After the results of a round, headless doesn't wait. Not while rewinding: the next round clears the snapshots
+    fn next_round(&mut self) {
+        if self.game.step.paused { return }
+        let ready = match self.game.session.as_ref() {
+            Some(session) => !session.finished() && (self.is_headless() || session.results_shown()),
+            None => false,
+        };
+        if !ready { return }
+        if let Err(err) = crate::game::mode::Session::start_round(self.game) {
+            self.game.step.session_error = Some(format!("round {} not started: {}", self.game.session.as_ref().map_or(0, |session| session.round), err));
+            self.game.session = None;
+            self.game.state = GameState::Playing;
+        }
+    }
+
+    #[cfg(not(feature = "shipping"))]
+    fn check_invariants(&mut self, pass: &'static str, check_position: bool) {
+        if !crate::DEBUG_SETTINGS.get().check_invariants { return }
+        new_timer_monothread!(self.game, _t, "check_invariants");
+        if let Err(err) = InvariantSolver::new(self.game, pass, check_position).solve() {
+            if self.game.step.invariant_error.is_none() { self.game.step.invariant_error = Some(format!("at tick {}, {}", self.game.rng.tick, err)); }
+            self.game.step.paused = true;
+        }
+    }
+
+    #[cfg(feature = "shipping")]
+    fn check_invariants(&mut self, _pass: &'static str, _check_position: bool) {
+    }
+
+    fn world_hash(&mut self) {
+        new_timer_monothread!(self.game, _t, "world_hash");
+        self.game.step.world_hash = crate::game::hash::world_hash(self.game);
+        #[cfg(feature = "serialize")]
+        crate::game::replay::check_hash(self.game);
+    }
+
+    fn rewind_save(&mut self) {
+        new_timer_monothread!(self.game, _t, "rewind");
+        self.game.rewind_save();
     }
 
     fn update_drawing_buffer(&mut self) {
+        if self.is_headless() { return }
         DrawingBufferSolver::new(self.game).solve_2(); // TODO: store the buffer during all the process ?
     }
     
+    fn count_overrun(&mut self) {
+        // Counted here and not in the wait, so headless runs count them too
+        if let StepMode::Fixed(duration) = self.game.step.mode {
+            let elapsed = self.instant.elapsed();
+            if elapsed > duration {
+                self.game.step.overruns += 1;
+                self.game.step.last_overrun = elapsed - duration;
+            }
+        }
+    }
+
     fn smooth_wait(&mut self) {
+        if self.is_headless() { return }
         SmoothWaitSolver::new(self.game, self.instant.clone()).solve();
     }
 
     fn special(&mut self) {
         if !self.game.settings.special { return }
+        use rand::Rng;
+        let mut rng = self.game.rng.stream(RngStream::Special, 0);
         for _ in 0..1_000 {
             if self.game.entities.len() > 200_000 { continue }
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
             let position = euclid::default::Point2D::new(
                 rng.gen_range(0..self.game.map.size.width * crate::game::map::RATIO_POSITION),
                 rng.gen_range(0..self.game.map.size.height * crate::game::map::RATIO_POSITION),
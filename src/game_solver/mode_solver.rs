@@ -0,0 +1,97 @@
+use crate::prelude::*;
+
+use crate::game::mode::{ModeKind, RoundResult, Session, Side};
+
+use crate::new_timer_monothread;
+
+// Scores and win conditions of the session after each tick, the end of a round shows its results (GameState::Results)
+pub struct ModeSolver<'a> {
+    game: &'a mut Game,
+}
+
+impl<'a> ModeSolver<'a> {
+    pub fn new(game: &'a mut Game) -> ModeSolver<'a> {
+        ModeSolver {
+            game,
+        }
+    }
+
+    pub fn solve(&mut self) {
+        new_timer_monothread!(self.game, _t, "mode");
+        let mut session = match self.game.session.take() {
+            Some(session) => session,
+            None => return,
+        };
+        if let ModeKind::KingOfTheHill(_) = session.mode.kind {
+            if let Some(side) = self.hill_holder(&session) { session.add_score(side); }
+        }
+
+        let standings = session.standings(self.game);
+        let winner = match &session.mode.kind {
+            ModeKind::FreeForAll => None,
+            ModeKind::LastManStanding => {
+                let mut alive = standings.iter().filter(|standing| standing.alive);
+                match (alive.next(), alive.next()) {
+                    (Some(last), None) if standings.len() > 1 => Some(Some(last.side)),
+                    (None, _) if !standings.is_empty() => Some(None),
+                    _ => None,
+                }
+            }
+            ModeKind::TargetMass(mass) => standings.iter().find(|standing| standing.mass >= *mass).map(|standing| Some(standing.side)),
+            ModeKind::KingOfTheHill(hill) => standings.iter().find(|standing| standing.score >= hill.score).map(|standing| Some(standing.side)),
+        };
+        let time_out = session.time_left(self.game) == Some(0);
+        let winner = match winner {
+            Some(winner) => Some(winner),
+            // The best one, unless it is a tie
+            None if time_out => Some(match (standings.first(), standings.get(1)) {
+                (Some(first), Some(second)) if first.score == second.score && first.mass == second.mass => None,
+                (Some(first), _) => Some(first.side),
+                (None, _) => None,
+            }),
+            None => None,
+        };
+
+        if let Some(winner) = winner {
+            session.results.push(RoundResult {
+                round: session.round,
+                ticks: session.ticks(self.game),
+                winner,
+                standings,
+            });
+            session.ended = Some(std::time::Instant::now());
+            self.game.state = GameState::Results;
+        }
+        self.game.session = Some(session);
+    }
+
+    // The side with the most mass in the hill, none if tied
+    fn hill_holder(&self, session: &Session) -> Option<Side> {
+        let hill = match &session.mode.kind {
+            ModeKind::KingOfTheHill(hill) => hill,
+            _ => return None,
+        };
+        let game = &*self.game;
+        let center = hill.center(game);
+        let mut masses: Vec<(Side, i64)> = Vec::new();
+        for (index, player) in game.players.iter().enumerate() {
+            if player.kind == PlayerKind::Neutral { continue }
+            let side = Side::of(game, index);
+            let mass: i64 = player.entities.iter()
+                .filter(|entity| game.map.distance(center, game.entities.position[**entity]) < hill.radius as f32)
+                .map(|entity| game.entities.mass[*entity])
+                .sum();
+            if mass == 0 { continue }
+            match masses.iter_mut().find(|(other, _)| *other == side) {
+                Some((_, total)) => *total += mass,
+                None => masses.push((side, mass)),
+            }
+        }
+        masses.sort_by(|a, b| b.1.cmp(&a.1));
+        match (masses.first(), masses.get(1)) {
+            (Some(first), Some(second)) if first.1 == second.1 => None,
+            (Some(first), _) => Some(first.0),
+            (None, _) => None,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::prelude::*;
+
+use crate::game::entity::EntityFlags;
+use crate::game::entity::entities::Entities;
+
+use euclid::default::{Point2D, Vector2D};
+use rand::Rng;
+
+// Actions of a player on all its cells, with the settings of the player: from the input of the local player, or from a bot
+pub struct PlayerSolver<'a> {
+    game: &'a mut Game,
+    player: usize,
+}
+
+impl<'a> PlayerSolver<'a> {
+    pub fn new(game: &'a mut Game, player: usize) -> PlayerSolver<'a> {
+        PlayerSolver {
+            game,
+            player,
+        }
+    }
+
+    // New cell when the player has none, unless the mode of the session forbids it
+    pub fn respawn(&mut self, position: Point2D<i32>, mass: i64) {
+        if !self.game.players[self.player].entities.is_empty() { return }
+        if !self.game.session.as_ref().map_or(true, |session| session.mode.respawn()) { return }
+        self.game.new_entity(EntityInfo {
+            player: self.player,
+            position,
+            speed: Vector2D::new(0.0, 0.0),
+            mass,
+            characteristics: self.game.settings.local_player_characteristics.clone(),
+            timer: EntityTimer::default(),
+            color: self.game.players[self.player].cell_default_color,
+            texture: self.game.players[self.player].cell_default_texture,
+        });
+    }
+
+    pub fn split(&mut self, target: Point2D<i32>) {
+        let player = unsafe { & *(&self.game.players[self.player] as *const Player) };
+        let entities = unsafe { &*(&self.game.entities as *const Entities) };
+
+        for i in 0..player.entities.len() {
+            if player.entities.len() < player.settings.max_split {
+                let entity_index = player.entities[i];
+                let mut entity: EntityRefMut = unsafe { std::mem::transmute(self.game.entities.get_mut(entity_index)) };
+                let entity_core = &mut self.game.entities.core[entity_index];
+                let entity_position = self.game.entities.position[entity_index];
+                let entity_speed = self.game.entities.speed[entity_index];
+                let entity_mass = self.game.entities.mass[entity_index];
+                let entity_radius = entity.get_radius();
+                let entity_timer = &mut self.game.entities.timer[entity_index];
+                let mut entity_info = None;
+                if entity_mass > 20_000_000 {
+                    let radius = entity_radius;
+                    let mut speed = self.game.map.delta(entity_position, target).to_f32();
+                    if speed.length() == 0.0 { speed.x = 1.0; }
+                    let ratio_speed = entity_radius / speed.length();
+                    speed *= ratio_speed * player.settings.unit_speed_split;
+                    let ratio_position = (radius / 2.0) / speed.length();
+                    entity_info = Some(EntityInfo {
+                        player: self.player,
+                        position: Point2D::new(entity_position.x + (speed.x * ratio_position) as i32, entity_position.y + (speed.y * ratio_position) as i32),
+                        speed: Vector2D::new(speed.x + entity_speed.x, speed.y + entity_speed.y),
+                        //speed: Vector2D::new(speed.x + entity.speed.x, speed.y + entity.speed.y),
+                        mass: entity_mass / 2,
+                        characteristics: entity_core.characteristics.clone(),
+                        timer: EntityTimer {
+                            collision: Some(1),
+                            collision_ratio: Some(10),
+                            mergeable: Some(1_000),
+                            inertia: Some(20),
+                            ..Default::default()
+                        },
+                        color: entity_core.color,
+                        texture: entity_core.index.texture,
+                    });
+                    entity_timer.mergeable = Some(1_000); // TODO: in buffer instead
+                }
+                if let Some(entity_info) = entity_info {
+                    /*
+                    entity.buffer.send(EntityAction::AddMass(-entity.mass / 2));
+                    self.game.buffer_add_entity.send(Box::new(entity_info));
+                    */
+                    *entity.mass_mut() /= 2;
+                    self.game.entities.drawable_entities[entity_index].mass = entity.mass() as f32;
+                    self.game.map.update_entity(entities, entity_core); //TODO: Not good. But needed if multiple spaces in one update. Find another solution
+                    self.game.new_entity(entity_info);
+                }
+            }
+        }
+    }
+
+    pub fn throw(&mut self, target: Point2D<i32>, rng: &mut impl Rng) {
+        let player = unsafe { & *(&self.game.players[self.player] as *const Player) };
+        let throw_food_info = player.settings.food.clone();
+        for i in 0..player.entities.len() {
+            for _ in 0..throw_food_info.throw_ratio {
+                let entity_index = player.entities[i];
+                let entity: EntityRefMut = unsafe { std::mem::transmute(self.game.entities.get_mut(entity_index)) };
+                let entity_core: &EntityCore = unsafe { std::mem::transmute(&self.game.entities.core[entity_index]) };
+                let entity_position = self.game.entities.position[entity_index];
+                let mut entity_info = None;
+                if entity.mass() > throw_food_info.mass_minimum_to_throw {
+                    self.game.entities.send_buffer(entity_index, entity_index, EntityAction::AddMass(throw_food_info.mass_self_added));
+                    let radius = entity.get_radius();
+                    let mut direction = self.game.map.delta(entity_position, target).to_f32();
+                    let direction_angle = direction.angle_from_x_axis();
+                    let direction_speed_angle = euclid::Angle::degrees(direction_angle.to_degrees() + rng.gen_range(throw_food_info.angle.clone()));
+                    let direction_speed = Vector2D::from_angle_and_length(direction_speed_angle, 1.0);
+
+                    if direction.length() == 0.0 { direction.x = 1.0; }
+                    direction = direction.normalize();
+
+                    let ratio_speed = rng.gen_range(throw_food_info.power.clone()) as f32 * ((entity.mass() as f32).log10());
+                    let speed = direction_speed * ratio_speed;
+
+                    let color = throw_food_info.color.pick(entity_core.color, rng);
+                    let texture = throw_food_info.texture.pick(entity_core.index.texture, rng);
+
+                    let ratio_position = radius + 10.0;
+                    entity_info = Some(EntityInfo {
+                        player: 0,
+                        position: Point2D::new(entity_position.x + (direction.x * ratio_position) as i32, entity_position.y + (direction.y * ratio_position) as i32),
+                        speed: Vector2D::new(speed.x, speed.y),
+                        mass: throw_food_info.mass_entity_thrown,
+                        characteristics: throw_food_info.characteristics_entity_thrown.clone(),
+                        timer: throw_food_info.timer.clone(),
+                        color: color,
+                        texture: texture,
+                    });
+                }
+                if let Some(entity_info) = entity_info {
+                    self.game.new_entity(entity_info);
+                    let entity = self.game.entities.len() - 1;
+                    self.game.entities.flags[entity].insert(EntityFlags::THROWN);
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::game::Game;
+use crate::game::StepMode;
 
 use std::time::Duration;
 
@@ -29,7 +30,13 @@ impl<'a> SmoothWaitSolver<'a> {
 
         let elapsed = self.instant.elapsed();
         let old_time = self.game.step.last_duration;
-        {
+        if let StepMode::Fixed(duration) = self.game.step.mode {
+            // Too slow: don't stretch the tick, the overrun is already counted
+            if elapsed <= duration {
+                self.wait(duration, duration);
+            }
+            self.game.step.last_duration = duration;
+        } else {
             let mut done = false;
             let mut new_time = self.game.step.last_duration;
             for &step in self.game.step.duration_vec.iter() {
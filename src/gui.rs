@@ -20,6 +20,8 @@ pub enum Action {
     Options,
     GraphicsOptions,
     Map(Option<String>),
+    Modes,
+    Files,
     Quit
 }
 
@@ -84,6 +86,10 @@ pub struct Gui {
     pub renderer_data: Mutex<GuiRendererData>,
     pub debug_info: RefCell<GuiDebugInfo>, //TODO: bad
     actions: RefCell<Vec<GuiAction>>,
+    file_path: RefCell<imgui::ImString>,
+    message: RefCell<Option<String>>, // Shown until closed, e.g. save/load errors
+    session_error: RefCell<Option<String>>, // Last GameStep::session_error shown
+    mode: RefCell<crate::game::mode::ModeSettings>, // Mode of the next session, see Action::Modes
 }
 
 impl Drop for Gui {
@@ -150,11 +156,15 @@ impl Gui {
             renderer_data,
             debug_info,
             actions: RefCell::new(Vec::new()),
+            file_path: RefCell::new(imgui::ImString::with_capacity(256)),
+            message: RefCell::new(None),
+            session_error: RefCell::new(None),
+            mode: RefCell::new(crate::game::mode::ModeSettings::from_name(crate::game::mode::MODES[0]).unwrap()),
         }
     }
 
     pub fn update(&self, game: &Game) { // TODO: refactor this
-        new_timer_monothread!(_t, "GUI");
+        new_timer_monothread!(crate::APP.get().game, _t, "GUI");
         self.handle_events_2();
         let mut egui = self.egui.lock();
         let egui_input = egui.winit_backend.take();
@@ -178,6 +188,9 @@ impl Gui {
         #[cfg(not(feature = "shipping"))]
         self.update_debug_menu(&ui, size, game);
         self.update_ingame_menu(&ui, size);
+        self.update_session(&ui, size, game);
+        self.update_leaderboard(&ui, size, game);
+        self.update_message(&ui, size);
 
         imgui.ui = Some(ui);
         
@@ -253,9 +266,172 @@ impl Gui {
         self.actions.borrow_mut().clear();
     }
 
+    pub fn show_message(&self, text: String) {
+        eprintln!("{}", text);
+        *self.message.borrow_mut() = Some(text);
+    }
+
+    fn update_message(&self, ui: &imgui::Ui, size_window: Size2D<i32>) {
+        let text = match self.message.borrow().clone() {
+            Some(text) => text,
+            None => return,
+        };
+        let window = imgui::Window::new(imgui::im_str!("Message"));
+        window
+            .position([size_window.width as f32 / 2.0 - 200.0, size_window.height as f32 - 100.0], imgui::Condition::Always)
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .size([400.0, 80.0], imgui::Condition::Always)
+            .build(&ui, || {
+                ui.text_wrapped(&imgui::ImString::new(text));
+                if ui.button(imgui::im_str!("OK"), [100.0, 20.0]) { *self.message.borrow_mut() = None; }
+                update_hovered(ui);
+            });
+    }
+
+    // From the last drawable game: the GUI doesn't need to read the players
+    fn update_leaderboard(&self, ui: &imgui::Ui, size_window: Size2D<i32>, game: &Game) {
+        if game.state == GameState::Results { return }
+        let (leaderboard, local_player, local_player_stats) = {
+            let drawable = game.drawable.get_last();
+            (drawable.leaderboard.clone(), game.settings.local_player, drawable.local_player_stats.clone())
+        };
+        if leaderboard.is_empty() { return }
+        let window = imgui::Window::new(imgui::im_str!("Leaderboard"));
+        window
+            .position([size_window.width as f32 - 10.0, size_window.height as f32 - 10.0], imgui::Condition::Always)
+            .position_pivot([1.0, 1.0])
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .bg_alpha(0.5)
+            .always_auto_resize(true)
+            .build(&ui, || {
+                ui.text("Leaderboard");
+                ui.separator();
+                for (rank, entry) in leaderboard.iter().enumerate() {
+                    let text = format!("{}. {}: {}", rank + 1, entry.name, entry.stats.mass);
+                    if entry.player == local_player {
+                        ui.text_colored([1.0, 0.85, 0.0, 1.0], text);
+                    } else {
+                        ui.text(text);
+                    }
+                }
+                ui.separator();
+                let stats = &local_player_stats;
+                ui.text(format!("mass: {} (peak {})", stats.mass, stats.peak_mass));
+                ui.text(format!("eaten: {} cells, {} players", stats.cells_eaten, stats.players_eaten));
+                ui.text(format!("deaths: {}, alive: {} ticks", stats.deaths, stats.ticks_alive));
+                ui.text(format!("lost to decay: {}", stats.mass_decayed));
+                update_hovered(ui);
+            });
+    }
+
+    // Hill, round and time left while playing, results at the end of the round
+    fn update_session(&self, ui: &imgui::Ui, size_window: Size2D<i32>, game: &Game) {
+        use crate::game::mode::{ModeKind, Session, Side};
+        // Shown once, the error stays in the game until the next session
+        if game.step.session_error != *self.session_error.borrow() {
+            *self.session_error.borrow_mut() = game.step.session_error.clone();
+            if let Some(err) = game.step.session_error.clone() { self.show_message(err); }
+        }
+        let session = match game.session.as_ref() {
+            Some(session) => session,
+            None => return,
+        };
+        let round = if session.mode.rounds == 0 { format!("round {}", session.round) } else { format!("round {}/{}", session.round, session.mode.rounds) };
+
+        if let ModeKind::KingOfTheHill(hill) = &session.mode.kind {
+            let camera = crate::APP.get().renderer.camera.clone();
+            let dpi = crate::APP.get().window.window.scale_factor() as f32;
+            let center = hill.center(game) - euclid::default::Vector2D::new(camera.x, camera.y);
+            let center = center.to_f32() / camera.size as f32 / dpi + (size_window.to_vector() / 2).to_f32();
+            let radius = hill.radius as f32 / camera.size as f32 / dpi;
+            ui.get_background_draw_list().add_circle([center.x, center.y], radius, [1.0, 0.85, 0.0, 0.8]).thickness(2.0).build();
+        }
+
+        if game.state != GameState::Results {
+            let window = imgui::Window::new(imgui::im_str!("Session"));
+            window
+                .position([10.0, 10.0], imgui::Condition::Always)
+                .title_bar(false)
+                .resizable(false)
+                .movable(false)
+                .always_auto_resize(true)
+                .build(&ui, || {
+                    ui.text(format!("{}, {}", session.mode.name(), round));
+                    if let Some(time_left) = session.time_left(game) { ui.text(format!("time left: {} ticks", time_left)); }
+                    let local_player = game.settings.local_player.min(game.players.len().saturating_sub(1));
+                    if !game.players.is_empty() {
+                        let side = Side::of(game, local_player);
+                        match &session.mode.kind {
+                            ModeKind::TargetMass(mass) => { ui.text(format!("target mass: {}", mass)); }
+                            ModeKind::KingOfTheHill(hill) => { ui.text(format!("hill: {}/{}", session.score(side), hill.score)); }
+                            _ => {}
+                        }
+                    }
+                    update_hovered(ui);
+                });
+            return
+        }
+
+        let result = match session.results.last() {
+            Some(result) => result,
+            None => return,
+        };
+        enum ResultsAction { Next, Again, Sandbox }
+        let mut action = None;
+        let window = imgui::Window::new(imgui::im_str!("Results"));
+        window
+            .position([size_window.width as f32 / 2.0 - 175.0, size_window.height as f32 / 4.0], imgui::Condition::Always)
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .always_auto_resize(true)
+            .build(&ui, || {
+                ui.text(format!("{}, {} over after {} ticks", session.mode.name(), round, result.ticks));
+                match result.winner {
+                    Some(winner) => ui.text(format!("winner: {}", winner.name(game))),
+                    None => ui.text("draw"),
+                }
+                ui.separator();
+                for (rank, standing) in result.standings.iter().enumerate() {
+                    let score = if let ModeKind::KingOfTheHill(_) = session.mode.kind { format!(", hill {}", standing.score) } else { String::new() };
+                    ui.text(format!("{}. {}: mass {}{}, {} wins{}", rank + 1, standing.side.name(game), standing.mass, score, session.wins(standing.side), if standing.alive { "" } else { " (dead)" }));
+                }
+                ui.separator();
+                if session.finished() {
+                    if ui.button(imgui::im_str!("Play again"), [160.0, 20.0]) { action = Some(ResultsAction::Again) }
+                } else if ui.button(imgui::im_str!("Next round"), [160.0, 20.0]) {
+                    action = Some(ResultsAction::Next)
+                }
+                ui.same_line(180.0);
+                if ui.button(imgui::im_str!("Sandbox"), [160.0, 20.0]) { action = Some(ResultsAction::Sandbox) }
+                update_hovered(ui);
+            });
+
+        let (mode, map, session_bots) = (session.mode.clone(), session.map.clone(), session.bots.clone());
+        let game = &mut APP.get_mut().game;
+        match action {
+            Some(ResultsAction::Next) => {
+                if let Some(session) = game.session.as_mut() { session.ended = None; } // Started by the game solver
+            }
+            Some(ResultsAction::Again) => {
+                if let Err(err) = Session::start(game, mode, &map, session_bots) { self.show_message(err); }
+                crate::APP.get_mut().renderer.camera_future.size = game.settings.camera_initial;
+            }
+            Some(ResultsAction::Sandbox) => {
+                game.session = None;
+                game.state = GameState::Playing;
+            }
+            None => {}
+        }
+    }
+
     #[cfg(not(feature = "shipping"))]
     pub fn update_debug_menu(&self, ui: &imgui::Ui, size_window: Size2D<i32>, game: &Game) {
-        new_timer_monothread!(_t, "imgui_debug");
+        new_timer_monothread!(crate::APP.get().game, _t, "imgui_debug");
         if *crate::DEBUG.get() == false { return }
         let mut debug_info = self.debug_info.borrow_mut();
         let debug_settings = crate::DEBUG_SETTINGS.get_mut();
@@ -300,7 +476,80 @@ impl Gui {
                 let mut full_speed = game.step.full_speed;
                 ui.checkbox(imgui::im_str!("full_speed"), &mut full_speed );
                 crate::APP.get_mut().game.step.full_speed = full_speed;
+
+                let mut thread_independent = game.step.thread_independent;
+                ui.checkbox(imgui::im_str!("thread_independent"), &mut thread_independent );
+                crate::APP.get_mut().game.step.thread_independent = thread_independent;
+
+                let mut rewind_enabled = game.rewind.enabled;
+                ui.checkbox(imgui::im_str!("rewind"), &mut rewind_enabled );
+                crate::APP.get_mut().game.rewind.enabled = rewind_enabled;
+                if rewind_enabled {
+                    let mut interval = game.rewind.interval as i32;
+                    if ui.input_int(imgui::im_str!("rewind_interval"), &mut interval).enter_returns_true(true).build() {
+                        crate::APP.get_mut().game.rewind.interval = interval.max(1) as u64;
+                    }
+                    let mut capacity = game.rewind.capacity as i32;
+                    if ui.input_int(imgui::im_str!("rewind_capacity"), &mut capacity).enter_returns_true(true).build() {
+                        crate::APP.get_mut().game.rewind.capacity = capacity.max(1) as usize;
+                    }
+                    let snapshots = game.rewind.snapshots.len();
+                    if snapshots > 0 {
+                        ui.text("snapshots: ".to_owned() + &snapshots.to_string() + " (ticks " + &game.rewind.snapshots.front().unwrap().tick.to_string() + " to " + &game.rewind.snapshots.back().unwrap().tick.to_string() + ")");
+                        let mut selected = game.rewind.selected.unwrap_or(snapshots - 1) as i32;
+                        if ui.input_int(imgui::im_str!("rewind_to"), &mut selected).build() {
+                            crate::APP.get_mut().game.rewind_to(selected.max(0).min(snapshots as i32 - 1) as usize);
+                        }
+                        if game.rewind.selected.is_some() {
+                            if ui.button(imgui::im_str!("Resume"), [150.0, 20.0]) {
+                                crate::APP.get_mut().game.rewind_resume();
+                            }
+                        }
+                    }
+                }
+
+                #[cfg(feature = "serialize")]
+                (|| {
+                    use crate::game::replay::{Replay, REPLAY_PATH};
+                    if game.replay.is_recording() {
+                        if ui.button(imgui::im_str!("Stop recording"), [150.0, 20.0]) {
+                            if let Err(err) = crate::APP.get_mut().game.stop_recording(REPLAY_PATH) { eprintln!("{}", err); }
+                        }
+                    } else if ui.button(imgui::im_str!("Record replay"), [150.0, 20.0]) {
+                        crate::APP.get_mut().game.start_recording();
+                    }
+                    ui.same_line(170.0);
+                    if let crate::game::replay::ReplayState::Playing(replay, index) = &game.replay {
+                        match replay.divergence {
+                            Some(tick) => ui.text("Replay diverged at tick ".to_owned() + &tick.to_string()),
+                            None => ui.text("Playing replay: ".to_owned() + &index.to_string() + "/" + &replay.inputs.len().to_string()),
+                        }
+                    } else if game.replay.is_playing() {
+                        ui.text("Playing replay");
+                    } else if ui.button(imgui::im_str!("Play replay"), [150.0, 20.0]) {
+                        match Replay::from_file(REPLAY_PATH, &game.textures) {
+                            Ok(replay) => crate::APP.get_mut().game.play_replay(replay),
+                            Err(err) => eprintln!("{}", err),
+                        }
+                    }
+                })();
                 
+                let step_mode = match game.step.mode { crate::game::StepMode::Adaptive => imgui::im_str!("Adaptive"), crate::game::StepMode::Fixed(_) => imgui::im_str!("Fixed") };
+                imgui::ComboBox::new(imgui::im_str!("step_mode")).preview_value(step_mode).build(ui, || {
+                    if imgui::Selectable::new(imgui::im_str!("Adaptive")).build(ui) { crate::APP.get_mut().game.step.mode = crate::game::StepMode::Adaptive };
+                    if imgui::Selectable::new(imgui::im_str!("Fixed")).build(ui) { crate::APP.get_mut().game.step.mode = crate::game::StepMode::Fixed(game.step.last_duration) };
+                });
+                if let crate::game::StepMode::Fixed(duration) = game.step.mode {
+                    let mut tick_ms = duration.as_millis() as i32;
+                    if ui.input_int(imgui::im_str!("fixed_tick_ms"), &mut tick_ms).enter_returns_true(true).build() {
+                        crate::APP.get_mut().game.step.mode = crate::game::StepMode::Fixed(std::time::Duration::from_millis(tick_ms.max(1) as u64));
+                    }
+                    let last_overrun = game.step.last_overrun.as_millis() as f32;
+                    ui.text("overruns: ".to_owned() + &game.step.overruns.to_string() + " (last: +" + &last_overrun.to_string() + " ms)");
+                }
+
+                ui.text(format!("world_hash: {:016x}", game.step.world_hash));
+
                 let update_time = game.step.last_duration.as_millis() as f32;
                 ui.text("tick_duration: ".to_owned() + &update_time.to_string() + " ms");
                 /*
@@ -345,11 +594,13 @@ impl Gui {
                             crate::APP.get_mut().game.map.delete_entity(&game.entities, entity);
                         }
                         crate::APP.get_mut().game.map = crate::game::map::Map::new(crate::game::MapInfo {
-                            size: Size2D::new(size_map_width, size_map_height)
+                            size: Size2D::new(size_map_width, size_map_height),
+                            walls: crate::APP.get().game.map.walls.list.clone(),
+                            topology: crate::APP.get().game.map.topology,
                         });
                         for entity in crate::APP.get_mut().game.entities.core.iter_mut() {
                             let entity_position = &mut crate::APP.get_mut().game.entities.position[entity.index.main];
-                            *entity_position = entity_position.min(crate::APP.get().game.map.max().to_vector().to_point());
+                            *entity_position = crate::APP.get().game.map.confine(*entity_position);
                             crate::APP.get_mut().game.map.add_entity(&game.entities, entity);
                             crate::APP.get_mut().game.entities.update_drawable(entity.index.main);
                         }
@@ -366,11 +617,41 @@ impl Gui {
                             crate::APP.get_mut().game.map.delete_entity(&game.entities, entity);
                         }
                         crate::APP.get_mut().game.map = crate::game::map::Map::new(crate::game::MapInfo {
-                            size: Size2D::new(size_map_width, size_map_height)
+                            size: Size2D::new(size_map_width, size_map_height),
+                            walls: crate::APP.get().game.map.walls.list.clone(),
+                            topology: crate::APP.get().game.map.topology,
                         });
                         for entity in crate::APP.get_mut().game.entities.core.iter_mut() {
                             let entity_position = &mut crate::APP.get_mut().game.entities.position[entity.index.main];
-                            *entity_position = entity_position.min(crate::APP.get().game.map.max().to_vector().to_point());
+                            *entity_position = crate::APP.get().game.map.confine(*entity_position);
+                            crate::APP.get_mut().game.map.add_entity(&game.entities, entity);
+                            crate::APP.get_mut().game.entities.update_drawable(entity.index.main);
+                        }
+                    }
+
+                    let topology = match game.map.topology {
+                        crate::game::map::MapTopology::Rectangle => imgui::im_str!("Rectangle"),
+                        crate::game::map::MapTopology::Torus => imgui::im_str!("Torus"),
+                        crate::game::map::MapTopology::Circle => imgui::im_str!("Circle"),
+                    };
+                    let mut new_topology = None;
+                    imgui::ComboBox::new(imgui::im_str!("topology")).preview_value(topology).build(&ui, || {
+                        if imgui::Selectable::new(imgui::im_str!("Rectangle")).build(&ui) { new_topology = Some(crate::game::map::MapTopology::Rectangle) };
+                        if imgui::Selectable::new(imgui::im_str!("Torus")).build(&ui) { new_topology = Some(crate::game::map::MapTopology::Torus) };
+                        if imgui::Selectable::new(imgui::im_str!("Circle")).build(&ui) { new_topology = Some(crate::game::map::MapTopology::Circle) };
+                    });
+                    if let Some(topology) = new_topology.filter(|topology| *topology != game.map.topology) {
+                        for entity in crate::APP.get_mut().game.entities.core.iter_mut() {
+                            crate::APP.get_mut().game.map.delete_entity(&game.entities, entity);
+                        }
+                        crate::APP.get_mut().game.map = crate::game::map::Map::new(crate::game::MapInfo {
+                            size: crate::APP.get().game.map.size,
+                            walls: crate::APP.get().game.map.walls.list.clone(),
+                            topology,
+                        });
+                        for entity in crate::APP.get_mut().game.entities.core.iter_mut() {
+                            let entity_position = &mut crate::APP.get_mut().game.entities.position[entity.index.main];
+                            *entity_position = crate::APP.get().game.map.confine(*entity_position);
                             crate::APP.get_mut().game.map.add_entity(&game.entities, entity);
                             crate::APP.get_mut().game.entities.update_drawable(entity.index.main);
                         }
@@ -399,6 +680,14 @@ impl Gui {
                     ui.checkbox(imgui::im_str!("draw_matrix_simple"), &mut debug_settings.draw_matrix_simple );
                     ui.checkbox(imgui::im_str!("draw_matrix_physics"), &mut debug_settings.draw_matrix_physics );
                     ui.checkbox(imgui::im_str!("draw_color_pression"), &mut debug_settings.draw_color_pression );
+                    ui.checkbox(imgui::im_str!("check_invariants"), &mut debug_settings.check_invariants );
+                    if let Some(err) = game.step.invariant_error.clone() {
+                        ui.text("Invariant broken ".to_owned() + &err);
+                        if ui.button(imgui::im_str!("Clear and resume"), [150.0, 20.0]) {
+                            crate::APP.get_mut().game.step.invariant_error = None;
+                            crate::APP.get_mut().game.step.paused = false;
+                        }
+                    }
                 }
             );
             
@@ -410,7 +699,7 @@ impl Gui {
     }
 
     fn update_ingame_menu(&self, ui: &imgui::Ui, size_window: Size2D<i32>) {
-        new_timer_monothread!(_t, "imgui_menu");
+        new_timer_monothread!(crate::APP.get().game, _t, "imgui_menu");
         let game = &mut APP.get_mut().game;
         let state = self.state.borrow().clone();
         match state {
@@ -439,7 +728,7 @@ impl Gui {
                     .resizable(false)
                     .movable(false)
                     .draw_background(true)
-                    .size([250.0, 350.0], imgui::Condition::Always)
+                    .size([250.0, 410.0], imgui::Condition::Always)
                     .build(&ui, || {
                         ui.indent_by(115.0);
                         if ui.arrow_button(imgui::im_str!("arrow_button_menu"), imgui::Direction::Down) {
@@ -479,6 +768,20 @@ impl Gui {
 
                         ui.spacing();
 
+                        ui.indent_by(50.0);
+                        if ui.button(imgui::im_str!("Modes"), [150.0, 50.0]) { *action.borrow_mut() = Action::Modes }
+                        ui.unindent_by(50.0);
+
+                        #[cfg(feature = "serialize")]
+                        (|| {
+                            ui.spacing();
+                            ui.indent_by(50.0);
+                            if ui.button(imgui::im_str!("Save / Load"), [150.0, 50.0]) { *action.borrow_mut() = Action::Files }
+                            ui.unindent_by(50.0);
+                        })();
+
+                        ui.spacing();
+
                         ui.indent_by(50.0);
                         if ui.button(imgui::im_str!("Quit"), [150.0, 50.0]) { *action.borrow_mut() = Action::Quit };
                         ui.unindent_by(50.0);
@@ -622,9 +925,9 @@ impl Gui {
                                     ui.spacing();
 
                                     ui.indent_by(20.0);
-                                    let maps = crate::game::map::premade::PREMADE_MAPS;
+                                    let maps = crate::game::map::premade::premade_maps();
                                     for map in maps.iter() {
-                                        if ui.button(imgui::ImString::new(*map).as_ref(), [150.0, 20.0]) { *action.borrow_mut() = Action::Map(Some(map.to_string())) };
+                                        if ui.button(imgui::ImString::new(map.as_str()).as_ref(), [150.0, 20.0]) { *action.borrow_mut() = Action::Map(Some(map.to_string())) };
                                     }
                                     ui.unindent_by(20.0);
 
@@ -636,7 +939,12 @@ impl Gui {
                                         ui.open_popup(imgui::im_str!("Confirmation map"));
                                         ui.popup(imgui::im_str!("Confirmation map"), || {
                                             if ui.button(imgui::im_str!("Confirmation"), [150.0, 20.0]) {
-                                                crate::game::map::premade::create_premade_map(game, &map_chosed);
+                                                game.session = None;
+                                                game.state = crate::game::GameState::Playing;
+                                                if let Err(err) = crate::game::map::premade::create_premade_map(game, &map_chosed) {
+                                                    self.show_message(err);
+                                                }
+                                                crate::APP.get_mut().renderer.camera_future.size = game.settings.camera_initial;
                                                 game.step.changed_map = true;
                                                 *self.state.borrow_mut() = GUIState::Closed;
                                             }
@@ -647,6 +955,86 @@ impl Gui {
                                 });
                             }
                             
+                            Action::Modes => {
+                                ui.open_popup(imgui::im_str!("Modes"));
+                                ui.popup_modal(imgui::im_str!("Modes")).always_auto_resize(true).resizable(false).movable(false).build(|| {
+                                    ui.separator();
+                                    ui.spacing();
+                                    ui.indent_by(20.0);
+
+                                    let mut mode = self.mode.borrow_mut();
+                                    for name in crate::game::mode::MODES.iter() {
+                                        if ui.radio_button_bool(&imgui::ImString::new(*name), mode.name() == *name) {
+                                            *mode = crate::game::mode::ModeSettings::from_name(name).unwrap();
+                                        }
+                                    }
+                                    let mut rounds = mode.rounds as i32;
+                                    ui.set_next_item_width(80.0);
+                                    ui.input_int(imgui::im_str!("Rounds (0: endless)"), &mut rounds).step(1).build();
+                                    mode.rounds = rounds.max(0) as usize;
+                                    let mut time_limit = mode.time_limit.unwrap_or(0) as i32;
+                                    ui.set_next_item_width(80.0);
+                                    ui.input_int(imgui::im_str!("Time limit in ticks (0: none)"), &mut time_limit).step(500).build();
+                                    mode.time_limit = if time_limit > 0 { Some(time_limit as u64) } else { None };
+                                    let mode = mode.clone();
+
+                                    ui.spacing();
+                                    ui.text("Map:");
+                                    for map in crate::game::map::premade::premade_maps().iter() {
+                                        if ui.button(imgui::ImString::new(map.as_str()).as_ref(), [150.0, 20.0]) {
+                                            match crate::game::mode::Session::start(game, mode.clone(), map, Vec::new()) {
+                                                Ok(()) => {
+                                                    crate::APP.get_mut().renderer.camera_future.size = game.settings.camera_initial;
+                                                    *self.state.borrow_mut() = GUIState::Closed;
+                                                }
+                                                Err(err) => self.show_message(err),
+                                            }
+                                        }
+                                    }
+
+                                    ui.unindent_by(20.0);
+                                    ui.spacing();
+                                    if ui.button(imgui::im_str!("Return"), [190.0, 20.0]) { *action.borrow_mut() = Action::None };
+                                });
+                            }
+
+                            #[cfg(feature = "serialize")]
+                            Action::Files => {
+                                ui.open_popup(imgui::im_str!("Save / Load"));
+                                ui.popup_modal(imgui::im_str!("Save / Load")).always_auto_resize(true).resizable(false).movable(false).build(|| {
+                                    ui.separator();
+                                    ui.spacing();
+                                    ui.indent_by(20.0);
+
+                                    let mut file_path = self.file_path.borrow_mut();
+                                    ui.input_text(imgui::im_str!("Path"), &mut file_path).build();
+                                    ui.text("*.binmap, *.ronmap: map");
+                                    ui.text("*".to_owned() + crate::game::SNAPSHOT_EXTENSION + ": game in progress");
+                                    let path = file_path.to_str().to_owned();
+                                    drop(file_path);
+
+                                    if ui.button(imgui::im_str!("Save"), [140.0, 20.0]) {
+                                        match game.save_file(&path) {
+                                            Ok(()) => self.show_message(format!("saved {}", path)),
+                                            Err(err) => self.show_message(err),
+                                        }
+                                    }
+                                    ui.same_line(160.0);
+                                    if ui.button(imgui::im_str!("Load"), [140.0, 20.0]) {
+                                        match game.load_file(&path) {
+                                            Ok(()) => {
+                                                crate::APP.get_mut().renderer.camera_future.size = game.settings.camera_initial;
+                                                *self.state.borrow_mut() = GUIState::Closed;
+                                            }
+                                            Err(err) => self.show_message(err),
+                                        }
+                                    }
+
+                                    ui.unindent_by(20.0);
+                                    if ui.button(imgui::im_str!("Return"), [300.0, 20.0]) { *action.borrow_mut() = Action::None };
+                                });
+                            }
+
                             Action::Quit => {
                                 ui.open_popup(imgui::im_str!("Confirmation quit"));
                                 ui.popup(imgui::im_str!("Confirmation quit"), || {
@@ -717,7 +1105,7 @@ impl Gui {
 
     #[cfg(all(feature = "serialize"))]
     fn editor(&self, ui: &imgui::Ui) {
-        new_timer_monothread!(_t, "imgui_2");
+        new_timer_monothread!(crate::APP.get().game, _t, "imgui_2");
 
         let mut debug_info = self.debug_info.borrow_mut();
         let game = &mut crate::APP.get_mut().game;
@@ -770,11 +1158,65 @@ impl Gui {
                             let mut new_entity_info: EntityInfo = serde::de::Deserialize::deserialize(entity_info).unwrap();
                             //game_bis.entities.update_from_factory(entity.index.main, new_entity_info);
                             new_entity_info.validate(game_bis);
-                            game_bis.entities.update(entity.index.main, new_entity_info);
+                            game_bis.update_entity(entity.index.main, new_entity_info);
                         } else {
                             game.editor_state.entity_selected = None;
                         }
                     });
+                    imgui::TabItem::new(&imgui::ImString::new("Import")).build(ui, || {
+                        game.editor_state.tab = 3;
+                        ui.text_wrapped(imgui::im_str!("Click on the map to place the image. Entities are created from the New tab."));
+                        use reflect::Reflect;
+                        let mut import = game.editor_state.image_import.to_value();
+                        reflect::imgui_impl::inspect(ui, &mut import, None, Some(settings_editor()));
+                        let mut new_import: crate::game::map::image_import::ImageImport = serde::de::Deserialize::deserialize(import).unwrap();
+                        new_import.validate();
+                        game.editor_state.image_import = new_import;
+
+                        if ui.button(imgui::im_str!("Import image"), [150.0, 19.0]) {
+                            let import = &game.editor_state.image_import;
+                            match import.open() {
+                                Ok(image) => {
+                                    let added = import.add_entities(game_bis, &image, &game.editor_state.new_entity);
+                                    self.show_message(format!("{} entities imported from {}", added, import.path));
+                                }
+                                Err(err) => self.show_message(err),
+                            }
+                        }
+                    });
+                    imgui::TabItem::new(&imgui::ImString::new("Walls")).build(ui, || {
+                        game.editor_state.tab = 4;
+                        use crate::game::map::walls::{Wall, Walls};
+                        ui.text_wrapped(imgui::im_str!("Click on the map to add points, then add them as segments or as a convex polygon."));
+                        let state = &mut game.editor_state;
+                        ui.input_int(imgui::im_str!("Thickness"), &mut state.wall_thickness).build();
+                        state.wall_thickness = state.wall_thickness.max(crate::game::map::RATIO_POSITION / 2);
+                        ui.text(format!("{} points", state.wall_points.len()));
+
+                        let mut list = game_bis.map.walls.list.clone();
+                        if ui.button(imgui::im_str!("Add segments"), [150.0, 19.0]) {
+                            for points in state.wall_points.windows(2) {
+                                list.push(Wall::Segment(points[0], points[1], state.wall_thickness));
+                            }
+                            state.wall_points.clear();
+                        }
+                        if ui.button(imgui::im_str!("Add polygon"), [150.0, 19.0]) {
+                            list.push(Wall::Polygon(state.wall_points.clone()));
+                            state.wall_points.clear();
+                        }
+                        if ui.button(imgui::im_str!("Clear points"), [150.0, 19.0]) {
+                            state.wall_points.clear();
+                        }
+                        ui.spacing();
+
+                        use reflect::Reflect;
+                        let mut walls = list.to_value();
+                        reflect::imgui_impl::inspect(ui, &mut walls, None, Some(settings_editor()));
+                        let new_list: Vec<Wall> = serde::de::Deserialize::deserialize(walls).unwrap();
+                        if new_list != game_bis.map.walls.list {
+                            game_bis.map.walls = Walls::new(new_list, game_bis.map.size);
+                        }
+                    });
                     imgui::TabItem::new(&imgui::ImString::new("Selection")).build(ui, || {
                         game.editor_state.tab = 2;
                         let selected = game.editor_state.selected.clone().unwrap_or_default();
@@ -823,11 +1265,37 @@ impl Gui {
                 */
 
                 use reflect::Reflect;
+                let old_rules: Vec<reflect::Value> = game.settings.auto_spawn.iter().map(|rule| rule.to_value()).collect();
                 let mut settings_value = game.settings.to_value();
                 reflect::imgui_impl::inspect(ui, &mut settings_value, None, Some(settings_editor()));
                 let new_settings = serde::de::Deserialize::deserialize(settings_value).unwrap();
                 game.settings = new_settings;
                 game.settings.validate(game_bis);
+                let new_rules: Vec<reflect::Value> = game.settings.auto_spawn.iter().map(|rule| rule.to_value()).collect();
+                if old_rules != new_rules {
+                    // Entities keep the rule that added them when rules are moved or removed, a rule changed in place stays where it is
+                    let mut used = vec![false; new_rules.len()];
+                    let mut moved: Vec<Option<usize>> = old_rules.iter().map(|old| {
+                        let new = (0..new_rules.len()).find(|&index| !used[index] && new_rules[index] == *old)?;
+                        used[new] = true;
+                        Some(new)
+                    }).collect();
+                    if old_rules.len() == new_rules.len() {
+                        for (index, new) in moved.iter_mut().enumerate() {
+                            if new.is_none() && !used[index] { *new = Some(index); used[index] = true; }
+                        }
+                    }
+                    game.spawn_rules_moved(&moved);
+                }
+
+                if let Some(local_player) = game.players.get_mut(game.settings.local_player) {
+                    ui.separator();
+                    ui.text("Local player");
+                    let mut player_settings_value = local_player.settings.to_value();
+                    reflect::imgui_impl::inspect(ui, &mut player_settings_value, None, Some(settings_editor()));
+                    local_player.settings = serde::de::Deserialize::deserialize(player_settings_value).unwrap();
+                    local_player.settings.validate();
+                }
             }
         );
     }
@@ -835,7 +1303,7 @@ impl Gui {
     #[cfg(not(feature = "shipping"))]
     #[allow(dead_code)]
     fn egui_test(&self, egui_context: &egui::CtxRef) {
-        new_timer_monothread!(_t, "egui");
+        new_timer_monothread!(crate::APP.get().game, _t, "egui");
         use reflect::Reflect;
         use egui::Widget;
         let game = &mut crate::APP.get_mut().game;
@@ -849,7 +1317,7 @@ impl Gui {
                 let mut entity_info = entity_info.to_value();
                 reflect::egui_impl::inspect(ui, &mut entity_info, None, Some(settings_editor_egui()));
                 let new_entity_info = serde::de::Deserialize::deserialize(entity_info).unwrap();
-                game_bis.entities.update(entity.index.main, new_entity_info);
+                game_bis.update_entity(entity.index.main, new_entity_info);
             }
         });
         egui::Window::new("AAA").resizable(true).scroll(true).show(egui_context, |ui| {
@@ -867,7 +1335,19 @@ fn settings_editor() -> reflect::imgui_impl::InspectSettings {
     let atomic_num = std::sync::Arc::new(std::sync::atomic::AtomicI32::new(0));
     settings.add_default_struct(ThrowEntityInfo::default());
     settings.add_default_struct(crate::game::entity::EntityGravityInfo::default());
+    settings.add_default_struct(crate::game::entity::VirusInfo::default());
+    settings.add_default_struct(crate::game::entity::SpawnOnDeathInfo::default());
+    settings.add_default_struct(crate::game::entity::ExplodeOnDeathInfo::default());
+    settings.add_default_struct(crate::game::entity::TransferMassOnDeathInfo::default());
+    settings.add_default_struct(crate::game::entity::RespawnOnDeathInfo::default());
     settings.add_default_struct(crate::game::entity::EntityCharacteristics::default());
+    settings.add_default_struct(crate::game::settings::AutoSpawnSettings::default());
+    settings.add_default_struct(crate::game::player::TeamSettings::default());
+    settings.add_default_struct(crate::game::map::image_import::PixelTemplate::default());
+    settings.add_default_struct(crate::game::map::generator::NoiseField::default());
+    settings.add_default_struct(crate::game::map::generator::RingsField::default());
+    settings.add_default_struct(crate::game::map::generator::SpiralField::default());
+    settings.add_default_struct(crate::game::map::generator::GradientField::default());
     /*
     settings.add_default_struct(crate::game::entity::factory::ThrowEntityInfo::default());
     settings.add_default_struct(crate::game::entity::factory::EntityCharacteristics::default());
@@ -949,6 +1429,7 @@ fn settings_editor() -> reflect::imgui_impl::InspectSettings {
     settings.add_behaviour_field_value(0usize, "texture".to_string(), |_, _| {});
     settings.add_behaviour_field_value([0.0f32;4], "background_color".to_string(), |_, _| {});
     settings.add_behaviour_field_value([0.0f32;4], "matrix_color".to_string(), |_, _| {});
+    settings.add_behaviour_field_value([0.0f32;4], "wall_color".to_string(), |_, _| {});
     settings.add_behaviour_field_value(0.0f32, "camera_initial".to_string(), |_, _| {});
     settings.add_behaviour_field_value(vec![crate::game::entity::EntitySpecial::WASM("".to_string())], "special".to_string(), |_, _| {});
     
@@ -0,0 +1,22 @@
+#[cfg(not(feature = "shipping"))]
+pub mod debug;
+
+pub mod prelude;
+#[cfg(feature = "graphics")]
+pub mod app;
+#[cfg(feature = "graphics")]
+pub mod gui;
+#[cfg(feature = "graphics")]
+pub mod window;
+pub mod game;
+pub mod game_solver;
+#[cfg(feature = "graphics")]
+pub mod renderer;
+pub mod utils;
+
+#[cfg(feature = "graphics")]
+pub static APP: static_data::StaticDataPtr<app::App> = static_data::StaticDataPtr::new();
+#[cfg(not(feature = "shipping"))]
+pub static DEBUG: static_data::StaticData<bool> = static_data::StaticData::new(false);
+#[cfg(not(feature = "shipping"))]
+pub static DEBUG_SETTINGS: static_data::StaticData<debug::DebugSettings> = static_data::StaticData::new(debug::DebugSettings::new());
@@ -1,6 +1,8 @@
+#[cfg(feature = "graphics")]
 pub use crate::APP;
 pub use crate::game::{GameAction, DrawableGame, Game, Settings, GameState};
 pub use crate::game::entity::{EntityAction, DrawableEntity, EntityCore, EntityCharacteristics, EntityInfo, EntityTimer, OnDeathEffect, ThrowEntityInfo, ThrownEntityCharacteristics, ThrownEntityTexture};
 pub use crate::game::entity::entities::{EntityRef, EntityRefMut};
 pub use crate::game::map::{Map, MapInfo};
-pub use crate::game::player::{Player, PlayerInfo, PlayerKind};
\ No newline at end of file
+pub use crate::game::player::{Player, PlayerInfo, PlayerKind};
+pub use crate::game::rng::RngStream;
\ No newline at end of file
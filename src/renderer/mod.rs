@@ -3,6 +3,7 @@ use solver::RendererSolver;
 
 use crate::{utils, window::Window};
 use crate::game::Game;
+use crate::gui::Gui;
 
 use euclid::default::Size2D;
 use parking_lot::Mutex;
@@ -58,7 +59,7 @@ pub struct Renderer {
 }
 
 impl Renderer {
-    pub fn new(window: &winit::window::Window, game: &Game) -> Renderer {
+    pub fn new(window: &winit::window::Window, game: &Game, gui: &Gui) -> Renderer {
         let camera = Camera{
             x: 0,
             y: 0,
@@ -77,7 +78,7 @@ impl Renderer {
         pipeline.set_ssaa(&core, wgpu_renderer::pipelines::ssaa::SSAAFactor::Disabled);
 
         let egui_renderer = {
-            let mut egui = game.gui.egui.lock();
+            let mut egui = gui.egui.lock();
             egui.context.begin_frame(Default::default());
             let _ = egui.context.end_frame();
 
@@ -87,7 +88,7 @@ impl Renderer {
         };
 
         let imgui_renderer = {
-            let mut imgui = game.gui.imgui.lock();
+            let mut imgui = gui.imgui.lock();
             let mut imgui_context = &mut imgui.context;
             let mut render_config = imgui_wgpu::RendererConfig::new_srgb();
             render_config.texture_format = wgpu::TextureFormat::Bgra8Unorm;
@@ -113,8 +114,8 @@ impl Renderer {
         }
     }
 
-    pub fn draw(&mut self, game: &Game, window: &Window) {
+    pub fn draw(&mut self, game: &Game, gui: &Gui, window: &Window) {
         let _lock = unsafe { (*(self as *mut Renderer)).lock_draw.lock() };
-        RendererSolver::new(self, game, window).draw();
+        RendererSolver::new(self, game, gui, window).draw();
     }
 }
\ No newline at end of file
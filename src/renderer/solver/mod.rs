@@ -11,7 +11,7 @@ use super::Renderer;
 
 use crate::{game::map::RATIO_POSITION, prelude::*};
 use crate::window::Window;
-use crate::game::gui::Gui;
+use crate::gui::Gui;
 use crate::new_timer_monothread_renderer;
 
 use parking_lot::MutexGuard;
@@ -33,7 +33,7 @@ pub struct RendererSolver<'a> {
 }
 
 impl<'a> RendererSolver<'a> {
-    pub fn new(renderer: &'a mut Renderer, game: &'a Game, window: &'a Window) -> RendererSolver<'a> {
+    pub fn new(renderer: &'a mut Renderer, game: &'a Game, gui: &'a Gui, window: &'a Window) -> RendererSolver<'a> {
         let (pre_drawable_game, drawable_game) = game.drawable.get_pre_last_and_last();
         let instant = std::time::Instant::now();
         let update_duration = drawable_game.update_duration;
@@ -43,7 +43,7 @@ impl<'a> RendererSolver<'a> {
             renderer,
             pre_drawable_game,
             drawable_game,
-            gui: &game.gui,
+            gui,
             window: window,
         }
     }
@@ -62,6 +62,7 @@ impl<'a> RendererSolver<'a> {
         if self.renderer.settings.draw_matrix {
             self.draw_matrix();
         }
+        self.draw_walls();
 
         #[cfg(not(feature = "shipping"))]
         self.draw_debug();
@@ -163,6 +164,67 @@ impl<'a> RendererSolver<'a> {
         self.renderer.pipeline.draw_colored_i32(&self.renderer.core, &vertex_data, &Camera2D { x: self.renderer.camera.x, y: self.renderer.camera.y, size: self.renderer.camera.size });
     }
 
+    fn draw_walls(&mut self) {
+        use crate::game::map::walls::Wall;
+        use wgpu_renderer::vertex_data::Vertex2DColoredi32;
+
+        let map = &crate::APP.get().game.map;
+        let walls = &map.walls;
+        let points = &crate::APP.get().game.editor_state.wall_points;
+        let circle = map.topology == crate::game::map::MapTopology::Circle;
+        if walls.is_empty() && points.is_empty() && !circle { return }
+        let color = APP.get().game.settings.wall_color;
+        let color = (color[0], color[1], color[2], color[3]);
+        let vertex = |point: Point2D<f32>| Vertex2DColoredi32 { position: Point3D::new(point.x as i32, point.y as i32, 1), color };
+        let mut vertex_data = wgpu_renderer::vertex_data::Vertex2DColoredi32Buffer::new();
+        for wall in walls.list.iter() {
+            match wall {
+                Wall::Segment(a, b, thickness) => {
+                    // Rectangle around the segment, the ends are squared
+                    let (a, b) = (a.to_f32(), b.to_f32());
+                    let direction = if a != b { (b - a).normalize() } else { Vector2D::new(1.0, 0.0) };
+                    let side = Vector2D::new(-direction.y, direction.x) * (*thickness as f32 / 2.0);
+                    let along = direction * (*thickness as f32 / 2.0);
+                    let corners = [a - along + side, b + along + side, b + along - side, a - along - side];
+                    for index in [0, 1, 2, 0, 2, 3].iter() {
+                        vertex_data.data.push(vertex(corners[*index]));
+                    }
+                }
+                Wall::Polygon(points) => {
+                    for index in 1..points.len().saturating_sub(1) {
+                        vertex_data.data.push(vertex(points[0].to_f32()));
+                        vertex_data.data.push(vertex(points[index].to_f32()));
+                        vertex_data.data.push(vertex(points[index + 1].to_f32()));
+                    }
+                }
+            }
+        }
+        // Border of a circular map, as a ring of quads
+        if circle {
+            let (center, radius) = map.circle();
+            let center = center.to_f32();
+            let width = self.renderer.camera.size * 5.0;
+            let corner = |index: usize, radius: f32| center + Vector2D::from_angle_and_length(euclid::Angle::degrees(index as f32 * 360.0 / 128.0), radius);
+            for index in 0..128 {
+                let corners = [corner(index, radius), corner(index + 1, radius), corner(index + 1, radius + width), corner(index, radius + width)];
+                for index in [0, 1, 2, 0, 2, 3].iter() {
+                    vertex_data.data.push(vertex(corners[*index]));
+                }
+            }
+        }
+        // Points clicked in the editor, not yet added as a wall
+        let half = (self.renderer.camera.size * 5.0) as i32 + 1;
+        for point in points.iter() {
+            vertex_data.data.extend_from_slice(&wgpu_renderer::vertex_data::colored_2d_i32::triangulate_colored_2d_i32(
+                *point - Vector2D::new(half, half),
+                *point + Vector2D::new(half, half),
+                1,
+                color
+            ));
+        }
+        self.renderer.pipeline.draw_colored_i32(&self.renderer.core, &vertex_data, &Camera2D { x: self.renderer.camera.x, y: self.renderer.camera.y, size: self.renderer.camera.size });
+    }
+
     #[allow(dead_code)]
     fn draw_matrix_simple(&mut self) {
         let size_field = APP.get().game.map.matrix_simple.size_field;
@@ -422,7 +484,7 @@ impl<'a> RendererSolver<'a> {
     
     fn draw_gui(&mut self) {
         match self.drawable_game.state {
-            GameState::Editor | GameState::Playing => {
+            GameState::Editor | GameState::Playing | GameState::Results => {
                 let mut imgui = self.gui.imgui.lock();
                 let mut image_view = &self.renderer.core.get_actual_frame().unwrap().output.view;
 
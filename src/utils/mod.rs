@@ -1,5 +1,6 @@
 //pub mod vec_bool;
 pub mod vec_chunk;
+#[cfg(feature = "graphics")]
 pub mod message_box;
 
 pub fn i32_mul_f32_2(number: i32, mul: f32) -> i32 {
@@ -394,6 +395,8 @@ impl<'a, T> Iterator for VecBoxIterMut<'a, T> {
 }
 */
 
+#[cfg_attr(feature = "serialize", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone)]
 pub struct VecUniqueIndex {
     inner: usize,
     free: Vec<usize>,
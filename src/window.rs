@@ -1,4 +1,5 @@
 use crate::game::Game;
+use crate::gui::Gui;
 use crate::renderer::{Camera, Renderer};
 
 use crossbeam_utils::sync::Parker;
@@ -88,7 +89,7 @@ impl Window {
         }
     }
 
-    pub fn poll_events(&mut self, game: &Game, renderer: &mut Renderer) {
+    pub fn poll_events(&mut self, game: &Game, gui: &Gui, renderer: &mut Renderer) {
         let mut last_event_time = std::time::Instant::now();
         let mut last_camera_move_time = std::time::Instant::now();
         let mut event_loop = self.event_loop.take().unwrap();
@@ -120,7 +121,7 @@ impl Window {
                         WindowEvent::CursorMoved{position, ..} => {
                             let position = Point2D::new(position.x as i32, position.y as i32);
                             self.events.mouse_events.mouse_position = position.clone();
-                            if game.gui.is_on_gui(position) {
+                            if gui.is_on_gui(position) {
                                 self.events.mouse_events.mouse_on_gui = true;
                             } else {
                                 self.events.mouse_events.mouse_on_gui = false;
@@ -212,7 +213,7 @@ impl Window {
             }
 
             // Handle event on imgui
-            game.gui.handle_events(&self.window, event);
+            gui.handle_events(&self.window, event);
 
             if !crate::APP.get().app_runner_infos.running() {
                 *control_flow = ControlFlow::Exit;
@@ -0,0 +1,249 @@
+// Same seed and inputs give the same world (see GameRng), at any thread count in thread independent mode.
+
+use cells::game::{Game, GameInfo};
+use cells::game::entity::{DeathSpeed, EntityCharacteristics, EntityInfo, EntityTimer, OnDeathEffect, SpawnOnDeathInfo, RATIO_MASS};
+use cells::game::map::MapInfo;
+use cells::game::map::premade::create_premade_map;
+use cells::game::player::{PlayerInfo, PlayerKind};
+use cells::game_solver::GameSolver;
+
+use euclid::default::{Point2D, Size2D};
+
+// World hash after each tick of a premade map
+fn hashes(map: &str, seed: u64, threads: usize, thread_independent: bool, ticks: usize) -> Vec<u64> {
+    let mut game = Game::new(GameInfo { threads });
+    game.rng.reseed(seed);
+    create_premade_map(&mut game, map).unwrap();
+    game.step.thread_independent = thread_independent;
+    (0..ticks).map(|_| {
+        GameSolver::new(&mut game).solve();
+        game.step.world_hash
+    }).collect()
+}
+
+#[test]
+fn same_seed_gives_the_same_world() {
+    // Bots, spawn rules, throwing cells and splits on death all draw from the rng
+    let first = hashes("bots", 42, 0, false, 60);
+    let second = hashes("bots", 42, 0, false, 60);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn other_seed_gives_another_world() {
+    let first = hashes("classic", 42, 0, false, 10);
+    let second = hashes("classic", 43, 0, false, 10);
+    assert_ne!(first.last(), second.last());
+}
+
+#[test]
+fn thread_count_doesnt_change_the_world() {
+    let two = hashes("bots", 42, 2, true, 60);
+    let eight = hashes("bots", 42, 8, true, 60);
+    assert_eq!(two, eight);
+}
+
+// Pairs of entities dying at the same place, each one adds an entity that only differs by its timer from the other one
+fn tied_additions(threads: usize) -> Vec<u64> {
+    let mut game = Game::from_map_info(GameInfo { threads }, MapInfo {
+        size: Size2D::new(100, 100),
+        walls: Vec::new(),
+        topology: Default::default(),
+    });
+    game.rng.reseed(1);
+    game.settings.auto_spawn.clear();
+    game.step.thread_independent = true;
+    game.new_player(PlayerInfo { kind: PlayerKind::Neutral, ..Default::default() });
+    for i in 0..2_000 {
+        let loot = EntityInfo {
+            mass: RATIO_MASS * 2,
+            timer: EntityTimer { lifetime_left: Some(10 + i % 2 * 10), ..Default::default() },
+            ..Default::default()
+        };
+        game.new_entity(EntityInfo {
+            position: Point2D::new(10_000 + i / 2 * 900, 500_000),
+            mass: RATIO_MASS * 2,
+            timer: EntityTimer { lifetime_left: Some(1), ..Default::default() },
+            characteristics: EntityCharacteristics {
+                on_death: Some(OnDeathEffect::Spawn(SpawnOnDeathInfo { entities: vec![loot], amount: 1, speed: DeathSpeed::Same })),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+    }
+    (0..30).map(|_| {
+        GameSolver::new(&mut game).solve();
+        game.step.world_hash
+    }).collect()
+}
+
+#[test]
+fn tied_additions_dont_depend_on_the_thread_count() {
+    assert_eq!(tied_additions(2), tied_additions(8));
+}
+
+// Cells split by their player and popped by a virus in the same tick: the pop is applied in a later pass of the buffers
+fn split_and_pop(threads: usize) -> Game {
+    use cells::game::entity::{EntityAction, VirusInfo};
+
+    let mut game = Game::from_map_info(GameInfo { threads }, MapInfo {
+        size: Size2D::new(100, 100),
+        walls: Vec::new(),
+        topology: Default::default(),
+    });
+    game.rng.reseed(3);
+    game.settings.auto_spawn.clear();
+    game.step.thread_independent = true;
+    game.new_player(PlayerInfo { kind: PlayerKind::Neutral, ..Default::default() });
+    let virus = EntityCharacteristics { virus: Some(VirusInfo { pieces: 3, ..Default::default() }), ..Default::default() };
+    for i in 0..20 {
+        let position = Point2D::new(100_000 + i * 40_000, 500_000);
+        game.new_player(PlayerInfo { kind: PlayerKind::Player, ..Default::default() });
+        game.new_entity(EntityInfo {
+            player: i as usize + 1,
+            position,
+            mass: RATIO_MASS * 200,
+            characteristics: EntityCharacteristics { killer: true, ..Default::default() },
+            ..Default::default()
+        });
+        game.new_entity(EntityInfo { position: position + euclid::default::Vector2D::new(500, 0), mass: RATIO_MASS * 10, characteristics: virus.clone(), ..Default::default() });
+        let index = game.entities.len() - 2;
+        game.entities.send_buffer(index, index, EntityAction::Split(1));
+    }
+    GameSolver::new(&mut game).solve();
+    game
+}
+
+#[test]
+fn splits_of_several_passes_in_one_tick_dont_stack_the_pieces() {
+    let game = split_and_pop(2);
+    assert!(game.players[0].entities.is_empty());
+    for player in game.players.iter().skip(1) {
+        assert_eq!(player.entities.len(), 5);
+        // The pieces are thrown away from the cell: never twice in the same direction
+        let directions: Vec<_> = player.entities.iter().map(|index| game.entities.speed[*index]).filter(|speed| speed.length() > 0.0).map(|speed| speed.normalize()).collect();
+        assert_eq!(directions.len(), 4);
+        for (i, first) in directions.iter().enumerate() {
+            for second in directions.iter().skip(i + 1) {
+                assert!(first.cross(*second).abs() > 0.001 || first.dot(*second) < 0.0, "{:?} and {:?}", first, second);
+            }
+        }
+    }
+}
+
+#[test]
+fn splits_of_several_passes_in_one_tick_dont_depend_on_the_thread_count() {
+    let (mut two, mut eight) = (split_and_pop(2), split_and_pop(8));
+    for _ in 0..10 {
+        assert_eq!(two.step.world_hash, eight.step.world_hash);
+        GameSolver::new(&mut two).solve();
+        GameSolver::new(&mut eight).solve();
+    }
+}
+
+#[test]
+fn actions_are_ordered_by_their_value() {
+    use cells::game::entity::EntityAction;
+
+    let masses = [i64::MIN, -RATIO_MASS, -1, 0, 1, RATIO_MASS, i64::MAX];
+    for pair in masses.windows(2) {
+        assert!(EntityAction::AddMass(pair[0]).canonical_key() < EntityAction::AddMass(pair[1]).canonical_key());
+    }
+    assert!(EntityAction::AddPosition(-5, 0).canonical_key() < EntityAction::AddPosition(3, -2).canonical_key());
+    assert!(EntityAction::AddPosition(3, -2).canonical_key() < EntityAction::AddPosition(3, 0).canonical_key());
+}
+
+#[test]
+fn settings_are_hashed() {
+    use cells::game::bot::{self, BotSettings};
+    use cells::game::hash::world_hash;
+
+    let mut game = Game::new(GameInfo { threads: 0 });
+    game.rng.reseed(42);
+    create_premade_map(&mut game, "classic").unwrap();
+    bot::add_bots(&mut game, 1, &BotSettings::default(), RATIO_MASS * 10, None);
+    let mut hash = world_hash(&game);
+    let mut changed = |game: &Game| {
+        let new = world_hash(game);
+        assert_ne!(new, hash);
+        hash = new;
+    };
+
+    game.settings.eat_ratio += 0.1;
+    changed(&game);
+    game.settings.auto_spawn[0].every += 1;
+    changed(&game);
+    game.players[1].settings.max_split += 1;
+    changed(&game);
+    if let PlayerKind::Bot(bot) = &mut game.players.last_mut().unwrap().kind { bot.view += 1 }
+    changed(&game);
+    // Not simulated
+    game.settings.background_color[0] += 0.5;
+    assert_eq!(world_hash(&game), hash);
+}
+
+#[test]
+fn directions_are_hashed() {
+    use cells::game::hash::world_hash;
+
+    let mut game = Game::new(GameInfo { threads: 0 });
+    game.rng.reseed(42);
+    create_premade_map(&mut game, "classic").unwrap();
+    let cell = game.players[1].entities[0];
+    game.entities.direction[cell] = None;
+    let none = world_hash(&game);
+    game.entities.direction[cell] = Some(Point2D::zero());
+    let zero = world_hash(&game);
+    game.entities.direction[cell] = Some(Point2D::new(1, 0));
+    assert_ne!(none, zero);
+    assert_ne!(world_hash(&game), zero);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn replay_plays_the_recorded_ticks_again() {
+    use cells::game::bot::{self, BotSettings};
+    use cells::game::replay::{Replay, ReplayState};
+
+    let mut game = Game::new(GameInfo { threads: 2 });
+    game.rng.reseed(42);
+    create_premade_map(&mut game, "classic").unwrap();
+    game.step.thread_independent = true;
+    bot::add_bots(&mut game, 4, &BotSettings::default(), RATIO_MASS * 10, None);
+    // Started in the middle of the game: bots are moving, cells have timers
+    for _ in 0..25 {
+        GameSolver::new(&mut game).solve();
+    }
+    game.start_recording();
+    let recorded: Vec<u64> = (0..60).map(|_| {
+        GameSolver::new(&mut game).solve();
+        game.step.world_hash
+    }).collect();
+    let replay = match std::mem::take(&mut game.replay) {
+        ReplayState::Recording(replay) => replay,
+        _ => panic!("not recording"),
+    };
+    assert_eq!(replay.hashes, recorded);
+    // Headless: no tick has an input, InputSolver doesn't run when playing it either
+    assert!(replay.inputs.iter().all(Option::is_none));
+    let path = std::env::temp_dir().join(format!("cells_determinism_{}.binreplay", std::process::id()));
+    let path = path.to_str().unwrap();
+    replay.to_file(path).unwrap();
+
+    let mut played = Game::new(GameInfo { threads: 2 });
+    let replay = Replay::from_file(path, &played.textures).unwrap();
+    std::fs::remove_file(path).unwrap();
+    played.play_replay(replay);
+    let hashes: Vec<u64> = (0..60).map(|_| {
+        GameSolver::new(&mut played).solve();
+        played.step.world_hash
+    }).collect();
+    assert_eq!(hashes, recorded);
+    match &played.replay {
+        ReplayState::Playing(replay, index) => {
+            assert_eq!(*index, 60);
+            assert_eq!(replay.divergence, None);
+        }
+        _ => panic!("not playing"),
+    }
+}
@@ -0,0 +1,1031 @@
+// Physics of the headless simulation: games built from a MapInfo or a premade map, stepped with GameSolver.
+
+use cells::game::{Game, GameInfo};
+use cells::game::entity::{EntityCharacteristics, EntityInfo, EntityTimer, OnDeathEffect, RATIO_MASS};
+use cells::game::map::{MapInfo, MapTopology, RATIO_POSITION};
+use cells::game::map::walls::Wall;
+use cells::game::map::premade::create_premade_map;
+use cells::game::player::{PlayerInfo, PlayerKind};
+use cells::game_solver::GameSolver;
+
+use euclid::default::{Point2D, Size2D, Vector2D};
+
+// A neutral player and the local player on an empty map, without spawn rules
+fn new_game(threads: usize) -> Game {
+    new_game_with_walls(threads, Vec::new())
+}
+
+fn new_game_with_walls(threads: usize, walls: Vec<Wall>) -> Game {
+    new_game_with_map(threads, walls, MapTopology::Rectangle)
+}
+
+fn new_game_with_map(threads: usize, walls: Vec<Wall>, topology: MapTopology) -> Game {
+    let mut game = Game::from_map_info(GameInfo { threads }, MapInfo {
+        size: Size2D::new(100, 100),
+        walls,
+        topology,
+    });
+    game.rng.reseed(42);
+    game.settings.auto_spawn.clear();
+    game.new_player(PlayerInfo { kind: PlayerKind::Neutral, ..Default::default() });
+    game.new_player(PlayerInfo { kind: PlayerKind::Player, ..Default::default() });
+    game
+}
+
+fn cell(player: usize, position: Point2D<i32>, mass: i64, characteristics: EntityCharacteristics) -> EntityInfo {
+    EntityInfo {
+        player,
+        position,
+        mass,
+        characteristics,
+        ..Default::default()
+    }
+}
+
+// Dies at its first tick, at the end of its lifetime (without killer)
+fn dying(player: usize, position: Point2D<i32>, mass: i64, on_death: OnDeathEffect) -> EntityInfo {
+    EntityInfo {
+        timer: EntityTimer { lifetime_left: Some(0), ..Default::default() },
+        ..cell(player, position, mass, EntityCharacteristics { on_death: Some(on_death), ..Default::default() })
+    }
+}
+
+fn run(game: &mut Game, ticks: usize) {
+    for _ in 0..ticks {
+        GameSolver::new(game).solve();
+    }
+}
+
+fn center(game: &Game) -> Point2D<i32> {
+    Point2D::new(game.map.max().width / 2, game.map.max().height / 2)
+}
+
+#[test]
+fn eating_keeps_the_total_mass() {
+    let mut game = new_game(2);
+    let center = center(&game);
+    let killer = EntityCharacteristics { killer: true, ..Default::default() };
+    game.new_entity(cell(1, center, RATIO_MASS * 100, killer));
+    for i in 0..20 {
+        game.new_entity(cell(0, center + Vector2D::new(i * 100 - 1_000, 500), RATIO_MASS * 2, Default::default()));
+    }
+    let total_mass = game.total_mass();
+
+    run(&mut game, 5);
+
+    assert_eq!(game.entities.len(), 1);
+    assert_eq!(game.players[0].entities.len(), 0);
+    assert_eq!(game.total_mass(), total_mass);
+    assert_eq!(game.entities.mass[game.players[1].entities[0]], total_mass);
+}
+
+#[test]
+fn smaller_cells_of_other_players_are_not_eaten_by_a_non_killer() {
+    let mut game = new_game(2);
+    let center = center(&game);
+    game.new_entity(cell(1, center, RATIO_MASS * 100, Default::default()));
+    game.new_entity(cell(0, center + Vector2D::new(500, 0), RATIO_MASS * 2, Default::default()));
+    let total_mass = game.total_mass();
+
+    run(&mut game, 5);
+
+    assert_eq!(game.entities.len(), 2);
+    assert_eq!(game.total_mass(), total_mass);
+}
+
+#[test]
+fn colliding_cells_are_pushed_apart() {
+    let mut game = new_game(2);
+    let center = center(&game);
+    // Cells of one player collide, mergeable ones only with collide_when_mergeable
+    let collide = EntityCharacteristics { collide: true, collide_when_mergeable: true, ..Default::default() };
+    game.new_entity(cell(1, center, RATIO_MASS * 50, collide.clone()));
+    game.new_entity(cell(1, center + Vector2D::new(1_000, 0), RATIO_MASS * 50, collide));
+    let distance = |game: &Game| (game.entities.position[1] - game.entities.position[0]).to_f32().length();
+    let start = distance(&game);
+    let total_mass = game.total_mass();
+
+    run(&mut game, 20);
+
+    assert_eq!(game.entities.len(), 2);
+    assert_eq!(game.total_mass(), total_mass);
+    assert!(distance(&game) > start, "distance {} -> {}", start, distance(&game));
+}
+
+#[test]
+fn speed_moves_a_cell_and_slows_down_with_inertia() {
+    let mut game = new_game(2);
+    let start = center(&game);
+    game.new_entity(EntityInfo {
+        speed: Vector2D::new(1_000.0, 0.0),
+        ..cell(0, start, RATIO_MASS * 10, Default::default())
+    });
+
+    run(&mut game, 1);
+    let position = game.entities.position[0];
+    assert!(position.x > start.x);
+    assert_eq!(position.y, start.y);
+
+    // Inertia 10 (+ 1 without inertia timer): the speed loses 1/11 each tick, 1_000 * 10 in total
+    run(&mut game, 500);
+    assert_eq!(game.entities.speed[0], Vector2D::zero());
+    let moved = game.entities.position[0].x - start.x;
+    assert!((9_800..=10_000).contains(&moved), "moved {}", moved);
+    assert_eq!(game.entities.position[0].y, start.y);
+}
+
+#[test]
+fn splits_of_one_tick_dont_stack_the_pieces() {
+    use cells::game::entity::EntityAction;
+
+    let mut game = new_game(2);
+    let center = center(&game);
+    // Cells of their own players, each one popped by a virus and split by its player in the same tick
+    for i in 0..20 {
+        game.new_player(PlayerInfo { kind: PlayerKind::Player, ..Default::default() });
+        game.new_entity(cell(i + 2, center + Vector2D::new(i as i32 * 10_000 - 100_000, 0), RATIO_MASS * 100, Default::default()));
+        game.entities.send_buffer(i, i, EntityAction::Split(1));
+        game.entities.send_buffer(i, i, EntityAction::Split(1));
+    }
+
+    run(&mut game, 1);
+
+    // The pieces are thrown away from the cell: never twice in the same direction
+    for player in game.players.iter().skip(2) {
+        assert_eq!(player.entities.len(), 3);
+        let direction = |index: usize| game.entities.speed[player.entities[index]].normalize();
+        let (first, second) = (direction(1), direction(2));
+        assert!(first.cross(second).abs() > 0.001, "{:?} and {:?}", first, second);
+    }
+}
+
+#[test]
+fn stats_count_the_cells_and_players_eaten_and_the_deaths() {
+    let mut game = new_game(2);
+    let center = center(&game);
+    game.new_player(PlayerInfo { kind: PlayerKind::Player, ..Default::default() });
+    let killer = EntityCharacteristics { killer: true, ..Default::default() };
+    game.new_entity(cell(1, center, RATIO_MASS * 100, killer));
+    for i in 0..3 {
+        game.new_entity(cell(0, center + Vector2D::new(i * 500 - 500, 500), RATIO_MASS, Default::default()));
+    }
+    // Both cells of player 2 are eaten in the same tick: one player eaten
+    game.new_entity(cell(2, center + Vector2D::new(500, 0), RATIO_MASS * 2, Default::default()));
+    game.new_entity(cell(2, center - Vector2D::new(500, 0), RATIO_MASS * 2, Default::default()));
+
+    run(&mut game, 5);
+
+    let (stats, victim) = (&game.players[1].stats, &game.players[2].stats);
+    assert_eq!((stats.cells_eaten, stats.players_eaten, stats.deaths, stats.ticks_alive), (5, 1, 0, 5));
+    assert_eq!((stats.mass, stats.peak_mass), (RATIO_MASS * 107, RATIO_MASS * 107));
+    assert_eq!((victim.cells_eaten, victim.deaths, victim.ticks_alive, victim.alive), (0, 1, 0, false));
+    assert_eq!(victim.peak_mass, 0);
+}
+
+#[test]
+fn stats_count_the_mass_lost_to_decay() {
+    let mut game = new_game(2);
+    let center = center(&game);
+    let decaying = EntityCharacteristics { mass_evolution: Some(0.9), ..Default::default() };
+    game.new_entity(cell(1, center, RATIO_MASS * 100, decaying.clone()));
+    // Neutral cells don't count
+    game.new_entity(cell(0, center + Vector2D::new(100_000, 0), RATIO_MASS * 100, decaying));
+
+    run(&mut game, 2);
+
+    let stats = &game.players[1].stats;
+    assert_eq!(stats.mass_decayed, RATIO_MASS * 19);
+    assert_eq!(stats.mass, RATIO_MASS * 81);
+    assert_eq!(stats.peak_mass, RATIO_MASS * 90);
+    assert_eq!(game.players[0].stats.mass_decayed, 0);
+}
+
+#[test]
+fn leaderboard_ranks_by_mass_then_peak_mass_then_player() {
+    let mut game = new_game(2);
+    let center = center(&game);
+    for _ in 0..4 {
+        game.new_player(PlayerInfo { kind: PlayerKind::Player, ..Default::default() });
+    }
+    // Players 3 and 5 end with the same mass, player 5 was bigger before
+    let decaying = EntityCharacteristics { mass_evolution: Some(0.5), mass_min: RATIO_MASS * 10, ..Default::default() };
+    let masses = [(2, RATIO_MASS * 20, Default::default()), (3, RATIO_MASS * 10, Default::default()), (4, RATIO_MASS * 10, Default::default()), (5, RATIO_MASS * 40, decaying)];
+    for (i, (player, mass, characteristics)) in masses.iter().cloned().enumerate() {
+        game.new_entity(cell(player, center + Vector2D::new(i as i32 * 100_000 - 150_000, 0), mass, characteristics));
+    }
+    game.new_entity(cell(0, center + Vector2D::new(0, 100_000), RATIO_MASS * 100, Default::default()));
+
+    run(&mut game, 5);
+
+    let ranking: Vec<usize> = game.leaderboard(10).iter().map(|entry| entry.player).collect();
+    assert_eq!(ranking, vec![2, 5, 3, 4, 1]);
+    assert_eq!(game.leaderboard(2).len(), 2);
+}
+
+#[test]
+fn players_of_one_game_follow_their_own_settings() {
+    use cells::game::bot::BotSettings;
+    use cells::game::entity::{EntityAction, EntityFlags};
+    use cells::game::player::PlayerSettings;
+    use cells::game::settings::ThrownFoodInfo;
+
+    // Two bots fleeing a threat on their right and throwing at it, the second one faster and with heavier food
+    let mut game = new_game(2);
+    let center = center(&game);
+    let bot = BotSettings { reaction: 1, aim: 0, chase: false, split_chance: 0.0, throw_chance: 1.0, ..Default::default() };
+    let settings = |unit_speed: f32, thrown: i64| PlayerSettings {
+        unit_speed,
+        food: ThrownFoodInfo { mass_entity_thrown: thrown, ..Default::default() },
+        ..Default::default()
+    };
+    let killer = EntityCharacteristics { killer: true, ..Default::default() };
+    let mut starts = Vec::new();
+    for (i, settings) in [settings(5_000.0, RATIO_MASS * 2), settings(10_000.0, RATIO_MASS * 4)].iter().enumerate() {
+        let position = center + Vector2D::new(0, i as i32 * 100_000 - 50_000);
+        game.new_player(PlayerInfo { kind: PlayerKind::Bot(bot.clone()), settings: settings.clone(), ..Default::default() });
+        game.new_entity(cell(i + 2, position, RATIO_MASS * 20, Default::default()));
+        game.new_entity(cell(1, position + Vector2D::new(20_000, 0), RATIO_MASS * 200, killer.clone()));
+        starts.push(position);
+    }
+
+    run(&mut game, 1);
+    let mut thrown: Vec<i64> = (0..game.entities.len()).filter(|index| game.entities.flags[*index].contains(EntityFlags::THROWN)).map(|index| game.entities.mass[index]).collect();
+    thrown.sort_unstable();
+    assert_eq!(thrown, vec![RATIO_MASS * 2, RATIO_MASS * 4]);
+
+    run(&mut game, 10);
+    let moved = |player: usize| starts[player - 2].x - game.entities.position[game.players[player].entities[0]].x;
+    assert!(moved(2) > 0 && moved(3) > moved(2) * 3 / 2, "moved {} and {}", moved(2), moved(3));
+
+    // Same split, other max_split
+    let mut game = new_game(2);
+    for (i, max_split) in [2, 8].iter().enumerate() {
+        game.new_player(PlayerInfo { kind: PlayerKind::Player, settings: PlayerSettings { max_split: *max_split, ..Default::default() }, ..Default::default() });
+        game.new_entity(cell(i + 2, center + Vector2D::new(i as i32 * 100_000 - 50_000, 0), RATIO_MASS * 100, Default::default()));
+        game.entities.send_buffer(i, i, EntityAction::Split(5));
+    }
+    run(&mut game, 1);
+    assert_eq!((game.players[2].entities.len(), game.players[3].entities.len()), (2, 6));
+}
+
+#[test]
+fn cells_stay_in_the_map() {
+    let mut game = new_game(2);
+    let max = game.map.max();
+    let bounce = EntityCharacteristics { bounce: true, ..Default::default() };
+    let stop = EntityCharacteristics { bounce: false, ..Default::default() };
+    for (i, speed) in [(300_000.0, 0.0), (-300_000.0, 120_000.0), (50_000.0, -400_000.0), (-90_000.0, -90_000.0)].iter().enumerate() {
+        let position = Point2D::new(max.width / 5 * (i as i32 + 1), max.height / 2);
+        game.new_entity(EntityInfo { speed: Vector2D::new(speed.0, speed.1), ..cell(0, position, RATIO_MASS * 10, bounce.clone()) });
+        game.new_entity(EntityInfo { speed: Vector2D::new(speed.0, speed.1), ..cell(0, position, RATIO_MASS * 10, stop.clone()) });
+    }
+
+    for _ in 0..100 {
+        run(&mut game, 1);
+        for position in game.entities.position.iter() {
+            assert!(position.x >= 0 && position.x <= max.width && position.y >= 0 && position.y <= max.height, "{:?} out of the map", position);
+        }
+    }
+    assert_eq!(game.entities.len(), 8);
+}
+
+#[test]
+fn premade_map_runs_headless() {
+    let mut game = Game::new(GameInfo { threads: 2 });
+    game.rng.reseed(7);
+    create_premade_map(&mut game, "classic").unwrap();
+    let entities = game.entities.len();
+    assert!(entities > 0);
+
+    run(&mut game, 50);
+
+    assert_eq!(game.rng.tick, 50);
+    assert!(game.entities.len() > entities, "spawn rules add food: {} -> {}", entities, game.entities.len());
+    assert!(game.entities.len() <= game.settings.max_cells_spawn + game.settings.auto_spawn.iter().map(|rule| rule.amount).sum::<usize>());
+    let max = game.map.max();
+    for (index, position) in game.entities.position.iter().enumerate() {
+        assert!(position.x >= 0 && position.x <= max.width && position.y >= 0 && position.y <= max.height);
+        assert!(game.entities.mass[index] > 0);
+    }
+    let in_players: usize = game.players.iter().map(|player| player.entities.len()).sum();
+    assert_eq!(in_players, game.entities.len());
+}
+
+#[test]
+fn slow_fixed_ticks_are_counted_as_overruns() {
+    use cells::game::StepMode;
+    use std::time::Duration;
+
+    let mut game = Game::new(GameInfo { threads: 2 });
+    create_premade_map(&mut game, "classic").unwrap();
+    run(&mut game, 10);
+    assert_eq!(game.step.overruns, 0);
+
+    // No tick is done in a nanosecond
+    game.step.mode = StepMode::Fixed(Duration::from_nanos(1));
+    run(&mut game, 10);
+    assert_eq!(game.step.overruns, 10);
+    assert!(game.step.last_overrun > Duration::from_nanos(0));
+}
+
+#[test]
+fn broken_invariants_pause_the_game() {
+    cells::DEBUG_SETTINGS.get_mut().check_invariants = true;
+    let mut game = new_game(2);
+    game.new_entity(cell(1, center(&game), RATIO_MASS * 10, Default::default()));
+    run(&mut game, 5);
+    assert_eq!(game.step.invariant_error, None);
+
+    // Listed by a player it doesn't belong to
+    game.players[0].entities.push(0);
+    run(&mut game, 2);
+
+    // The tick where it broke ends, the next one is paused
+    assert!(game.step.paused);
+    assert_eq!(game.rng.tick, 6);
+    let err = game.step.invariant_error.clone().unwrap();
+    assert!(err.starts_with("at tick 5, after apply_cache: entity 0"), "{}", err);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn save_files_keep_the_game_in_progress() {
+    use cells::game::mode::{ModeSettings, Session};
+
+    let mut game = Game::new(GameInfo { threads: 2 });
+    game.rng.reseed(7);
+    Session::start(&mut game, ModeSettings::from_name("king-of-the-hill").unwrap(), "classic", Vec::new()).unwrap();
+    run(&mut game, 30);
+    let path = std::env::temp_dir().join(format!("cells_headless_{}.binsave", std::process::id()));
+    let path = path.to_str().unwrap();
+    game.save_file(path).unwrap();
+
+    let mut loaded = Game::new(GameInfo { threads: 2 });
+    loaded.load_file(path).unwrap();
+    assert_eq!(loaded.rng.tick, game.rng.tick);
+    for (player, other) in game.players.iter().zip(loaded.players.iter()) {
+        assert_eq!(player.stats.peak_mass, other.stats.peak_mass);
+        assert_eq!(player.stats.ticks_alive, other.stats.ticks_alive);
+    }
+    let (session, other) = (game.session.as_ref().unwrap(), loaded.session.as_ref().unwrap());
+    assert_eq!((session.round, session.round_start, &session.scores), (other.round, other.round_start, &other.scores));
+    run(&mut game, 10);
+    run(&mut loaded, 10);
+    assert_eq!(game.step.world_hash, loaded.step.world_hash);
+
+    // Without header (older save files) or with another version
+    std::fs::write(path, b"not a save file").unwrap();
+    assert!(loaded.load_file(path).is_err());
+    let mut data = b"CELLSAVE".to_vec();
+    data.extend_from_slice(&u32::MAX.to_le_bytes());
+    std::fs::write(path, &data).unwrap();
+    let err = loaded.load_file(path).unwrap_err();
+    assert!(err.contains("newer"), "{}", err);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn textures_are_remapped_by_name_when_loaded() {
+    let mut game = new_game(2);
+    let custom = game.textures.register("custom");
+    let skin = game.textures.index("skin_3").unwrap();
+    game.new_entity(EntityInfo { texture: custom, ..cell(0, center(&game), RATIO_MASS, Default::default()) });
+    game.new_entity(EntityInfo { texture: skin, ..cell(0, center(&game) + Vector2D::new(1_000, 0), RATIO_MASS, Default::default()) });
+
+    for extension in ["binmap", "binsave"].iter() {
+        let path = std::env::temp_dir().join(format!("cells_headless_textures_{}.{}", std::process::id(), extension));
+        let path = path.to_str().unwrap();
+        game.save_file(path).unwrap();
+
+        // Another texture registered before: "custom" has another index
+        let mut other = Game::new(GameInfo { threads: 2 });
+        other.textures.register("other");
+        let moved = other.textures.register("custom");
+        assert_ne!(moved, custom);
+        other.load_file(path).unwrap();
+        let textures: Vec<usize> = other.entities.core.iter().map(|entity| entity.index.texture).collect();
+        assert_eq!(textures, vec![moved, skin], "{}", extension);
+
+        let mut missing = Game::new(GameInfo { threads: 2 });
+        let err = missing.load_file(path).unwrap_err();
+        assert!(err.contains("missing textures: 'custom'"), "{}", err);
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn rewind_restores_the_round() {
+    use cells::game::GameState;
+    use cells::game::mode::{ModeSettings, Session};
+
+    let mut game = Game::new(GameInfo { threads: 2 });
+    game.rng.reseed(7);
+    let mode = ModeSettings { time_limit: Some(20), rounds: 2, ..ModeSettings::from_name("free-for-all").unwrap() };
+    Session::start(&mut game, mode, "classic", Vec::new()).unwrap();
+    game.rewind.enabled = true;
+    game.rewind.interval = 5;
+    run(&mut game, 20);
+    assert!(game.state == GameState::Results);
+    let last = game.rewind.snapshots.len() - 1;
+
+    // Before the end of the round its result isn't known yet
+    game.rewind_to(0);
+    assert!(game.state == GameState::Playing);
+    assert!(game.session.as_ref().unwrap().results.is_empty());
+    game.rewind_to(last);
+    assert!(game.state == GameState::Results);
+    assert_eq!(game.session.as_ref().unwrap().results.len(), 1);
+
+    game.rewind_to(0);
+    game.rewind_resume();
+    run(&mut game, 15);
+    assert!(game.state == GameState::Results);
+    let session = game.session.as_ref().unwrap();
+    assert_eq!((session.round, session.results.len()), (1, 1));
+}
+
+#[test]
+fn rounds_that_cant_start_stop_the_session() {
+    use cells::game::GameState;
+    use cells::game::mode::{ModeSettings, Session};
+
+    let mut game = Game::new(GameInfo { threads: 2 });
+    let mode = ModeSettings { time_limit: Some(5), rounds: 2, ..ModeSettings::from_name("free-for-all").unwrap() };
+    Session::start(&mut game, mode, "classic", Vec::new()).unwrap();
+    // The map of the session was removed during the first round
+    game.session.as_mut().unwrap().map = "no such map".to_owned();
+    run(&mut game, 10);
+    assert!(game.session.is_none());
+    assert!(game.state == GameState::Playing);
+    let err = game.step.session_error.as_ref().unwrap();
+    assert!(err.starts_with("round 2 not started: unknown map"), "{}", err);
+    Session::start(&mut game, ModeSettings::from_name("free-for-all").unwrap(), "classic", Vec::new()).unwrap();
+    assert!(game.step.session_error.is_none());
+}
+
+#[test]
+fn spawn_rules_count_their_own_entities() {
+    use cells::game::settings::AutoSpawnSettings;
+
+    let mut game = new_game(2);
+    let food = AutoSpawnSettings::default();
+    // Same characteristics for both rules and for the entity of the map, only the entities of each rule are counted
+    game.new_entity(cell(0, center(&game), RATIO_MASS, food.characteristics.clone()));
+    game.settings.auto_spawn = vec![
+        AutoSpawnSettings { max: Some(5), timer: EntityTimer { lifetime_left: Some(4), ..Default::default() }, ..food.clone() },
+        AutoSpawnSettings { max: Some(10), ..food },
+    ];
+
+    for _ in 0..20 {
+        run(&mut game, 1);
+        for rule in 0..2 {
+            let alive = game.entities.core.iter().filter(|entity| entity.spawn_rule == Some(rule)).count();
+            assert_eq!(game.spawned[rule], alive);
+        }
+        assert!(game.spawned[0] <= 5);
+        assert_eq!(game.spawned[1], 10);
+        assert_eq!(game.entities.len(), 1 + game.spawned[0] + game.spawned[1]);
+    }
+
+    // The first rule removed in the editor: the entities of the second one keep their count and its max
+    game.settings.auto_spawn.remove(0);
+    game.spawn_rules_moved(&[None, Some(0)]);
+    assert_eq!(game.spawned, vec![10]);
+    run(&mut game, 10);
+    assert_eq!(game.spawned, vec![10]);
+    assert_eq!(game.entities.core.iter().filter(|entity| entity.spawn_rule == Some(0)).count(), 10);
+    assert!(game.entities.core.iter().all(|entity| entity.spawn_rule.map_or(true, |rule| rule == 0)));
+}
+
+#[test]
+fn spawn_rules_with_empty_random_lists_are_validated() {
+    use cells::game::settings::{AutoSpawnEntityColor, AutoSpawnEntityTexture, AutoSpawnMass, AutoSpawnSettings};
+
+    // Lists emptied in the settings editor
+    let mut game = new_game(2);
+    game.settings.auto_spawn = vec![AutoSpawnSettings {
+        mass: AutoSpawnMass::Random(Vec::new()),
+        color: AutoSpawnEntityColor::Random(Vec::new()),
+        texture: AutoSpawnEntityTexture::Random(Vec::new()),
+        max: Some(10),
+        ..Default::default()
+    }];
+    let mut settings = game.settings.clone();
+    settings.validate(&game);
+    game.settings = settings;
+
+    run(&mut game, 5);
+    assert_eq!(game.spawned, vec![10]);
+    assert!(game.entities.core.iter().all(|entity| entity.index.texture == 0));
+}
+
+#[test]
+fn fallback_map_runs_headless() {
+    use cells::game::map::premade::FALLBACK_MAP;
+
+    let mut game = Game::new(GameInfo { threads: 2 });
+    create_premade_map(&mut game, FALLBACK_MAP).unwrap();
+    assert_eq!(game.players[1].entities.len(), 1);
+    run(&mut game, 10);
+    assert!(game.entities.len() > 1);
+    assert!(create_premade_map(&mut game, "no such map").is_err());
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn map_definitions_that_fail_leave_the_game_as_it_was() {
+    let mut game = new_game(2);
+    game.new_entity(cell(1, center(&game), RATIO_MASS * 10, Default::default()));
+    let path = std::env::temp_dir().join(format!("cells_headless_{}.ron", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    // Valid until the image is opened
+    std::fs::write(path, "(players: [(kind: Neutral)], entities: [(player: 0, positions: [Random(10), Image((path: \"no such image.png\"))])])").unwrap();
+    let err = game.load_map(path).unwrap_err();
+    std::fs::remove_file(path).unwrap();
+    assert!(err.contains("no such image"), "{}", err);
+    assert_eq!(game.players.len(), 2);
+    assert_eq!(game.entities.len(), 1);
+    assert_eq!(game.total_mass(), RATIO_MASS * 10);
+}
+
+#[test]
+fn bots_chase_smaller_food_and_flee_bigger_cells() {
+    use cells::game::bot::BotSettings;
+
+    let killer = EntityCharacteristics { killer: true, ..Default::default() };
+    let bot = BotSettings { reaction: 1, aim: 0, split_chance: 0.0, throw_chance: 0.0, ..Default::default() };
+    // The bot in the center, the other cell on its right
+    let bot_x_after = |other: EntityInfo| {
+        let mut game = new_game(2);
+        let center = center(&game);
+        game.new_player(PlayerInfo { kind: PlayerKind::Bot(bot.clone()), ..Default::default() });
+        game.new_entity(cell(2, center, RATIO_MASS * 10, killer.clone()));
+        game.new_entity(EntityInfo { position: center + Vector2D::new(20_000, 0), ..other });
+        run(&mut game, 10);
+        game.entities.position[game.players[2].entities[0]].x - center.x
+    };
+
+    let food = bot_x_after(cell(0, Point2D::zero(), RATIO_MASS, Default::default()));
+    assert!(food > 0, "moved {} toward the food", food);
+    let threat = bot_x_after(cell(1, Point2D::zero(), RATIO_MASS * 100, killer.clone()));
+    assert!(threat < 0, "moved {} away from the threat", threat);
+}
+
+#[test]
+fn images_are_imported_pixel_by_pixel() {
+    use cells::game::entity::EntityColor;
+    use cells::game::map::image_import::{ImageImport, ImageMass, PixelMatch, PixelTemplate};
+    use image::{DynamicImage, Rgba, RgbaImage};
+
+    // 4x4 shades of gray from black (top left) to white, the last pixel transparent
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 4, |x, y| {
+        let gray = ((x + 4 * y) * 17) as u8;
+        Rgba([gray, gray, gray, if (x, y) == (3, 3) { 0 } else { 255 }])
+    }));
+    let base = cell(0, Point2D::zero(), RATIO_MASS, Default::default());
+    let import = ImageImport { origin: Point2D::new(100, 200), ..Default::default() };
+    let at = |entities: &[EntityInfo], x: i32, y: i32| entities.iter().find(|entity| entity.position == import.origin + Vector2D::new(x, y) * import.spacing).cloned();
+
+    let entities = import.entities_from_image(&image, &base);
+    assert_eq!(entities.len(), 15);
+    let pixel = at(&entities, 2, 1).unwrap();
+    assert_eq!(pixel.mass, RATIO_MASS);
+    assert_eq!(pixel.color.center, [102, 102, 102, 255]);
+    assert!(at(&entities, 3, 3).is_none());
+
+    // The kept pixels stay at their place
+    let entities = ImageImport { skip: 1, ..import.clone() }.entities_from_image(&image, &base);
+    assert_eq!(entities.len(), 4);
+    assert!(at(&entities, 2, 2).is_some() && at(&entities, 1, 0).is_none());
+
+    let entities = ImageImport { mass: ImageMass::Brightness(100, 355), ..import.clone() }.entities_from_image(&image, &base);
+    assert_eq!(at(&entities, 0, 0).unwrap().mass, 100);
+    assert_eq!(at(&entities, 1, 0).unwrap().mass, 117);
+    assert_eq!(at(&entities, 2, 3).unwrap().mass, 338);
+
+    // Only the darkest pixels, with the mass and the color of the template
+    let color = EntityColor { center: [255, 0, 0, 255], edge: [0, 0, 0, 255] };
+    let template = PixelTemplate { pixels: PixelMatch::Brightness(0, 50), mass: Some(7), color: Some(color), ..Default::default() };
+    let entities = ImageImport { templates: vec![template], only_templates: true, ..import.clone() }.entities_from_image(&image, &base);
+    assert_eq!(entities.len(), 3);
+    assert!(entities.iter().all(|entity| entity.mass == 7 && entity.color.center == color.center));
+    assert!(at(&entities, 2, 0).is_some() && at(&entities, 3, 0).is_none());
+}
+
+// Players 2 and 3 in one team with its rules, player 1 alone
+fn new_game_with_team(team: cells::game::player::TeamSettings) -> Game {
+    let mut game = new_game(2);
+    game.settings.teams = vec![team];
+    for _ in 0..2 {
+        game.new_player(PlayerInfo { kind: PlayerKind::Player, team: Some(0), ..Default::default() });
+    }
+    game
+}
+
+#[test]
+fn teammates_eat_each_other_only_with_feed() {
+    use cells::game::player::TeamSettings;
+
+    let killer = EntityCharacteristics { killer: true, ..Default::default() };
+    // A big cell of player 2, a small one of its teammate on one side and of the other player on the other side
+    let alive_after = |team: TeamSettings| {
+        let mut game = new_game_with_team(team);
+        let center = center(&game);
+        game.new_entity(cell(2, center, RATIO_MASS * 100, killer.clone()));
+        game.new_entity(cell(3, center + Vector2D::new(500, 0), RATIO_MASS * 2, Default::default()));
+        game.new_entity(cell(1, center - Vector2D::new(500, 0), RATIO_MASS * 2, Default::default()));
+        run(&mut game, 5);
+        (game.players[3].entities.len(), game.players[1].entities.len())
+    };
+
+    assert_eq!(alive_after(TeamSettings::default()), (1, 0));
+    assert_eq!(alive_after(TeamSettings { feed: true, ..Default::default() }), (0, 0));
+}
+
+#[test]
+fn teammates_merge_and_collide_only_with_merge() {
+    use cells::game::player::TeamSettings;
+
+    // Too close in mass to be eaten (eat_ratio), a merge doesn't need it
+    let mergeable = EntityCharacteristics { killer: true, mergeable: true, ..Default::default() };
+    let cells_after = |team: TeamSettings| {
+        let mut game = new_game_with_team(team);
+        let center = center(&game);
+        game.new_entity(cell(2, center, RATIO_MASS * 50, mergeable.clone()));
+        game.new_entity(cell(3, center + Vector2D::new(500, 0), RATIO_MASS * 40, mergeable.clone()));
+        run(&mut game, 5);
+        game.entities.len()
+    };
+    assert_eq!(cells_after(TeamSettings { feed: true, ..Default::default() }), 2);
+    assert_eq!(cells_after(TeamSettings { merge: true, ..Default::default() }), 1);
+
+    // Cells of teammates that can't merge are pushed apart like cells of one player
+    let collide = EntityCharacteristics { collide: true, collide_when_mergeable: true, ..Default::default() };
+    let distance_after = |team: TeamSettings| {
+        let mut game = new_game_with_team(team);
+        let center = center(&game);
+        game.new_entity(cell(2, center, RATIO_MASS * 50, collide.clone()));
+        game.new_entity(cell(3, center + Vector2D::new(1_000, 0), RATIO_MASS * 50, collide.clone()));
+        run(&mut game, 20);
+        (game.entities.position[1] - game.entities.position[0]).to_f32().length()
+    };
+    assert_eq!(distance_after(TeamSettings::default()), 1_000.0);
+    assert!(distance_after(TeamSettings { merge: true, ..Default::default() }) > 1_000.0);
+}
+
+// A neutral virus in the center
+fn virus(game: &mut Game, mass: i64, info: cells::game::entity::VirusInfo) {
+    let characteristics = EntityCharacteristics { killer: true, virus: Some(info), ..Default::default() };
+    game.new_entity(cell(0, center(game), mass, characteristics));
+}
+
+#[test]
+fn viruses_pop_into_their_pieces_up_to_the_max_split() {
+    use cells::game::entity::VirusInfo;
+
+    let cells_after = |max_split: usize| {
+        let mut game = new_game(2);
+        game.players[1].settings.max_split = max_split;
+        virus(&mut game, RATIO_MASS * 50, VirusInfo { pieces: 4, ..Default::default() });
+        let killer = EntityCharacteristics { killer: true, ..Default::default() };
+        game.new_entity(cell(1, center(&game) + Vector2D::new(500, 0), RATIO_MASS * 200, killer));
+        run(&mut game, 5);
+        assert!(game.players[0].entities.is_empty());
+        game.players[1].entities.len()
+    };
+    assert_eq!(cells_after(64), 5);
+    assert_eq!(cells_after(3), 3);
+}
+
+#[test]
+fn viruses_are_only_fed_by_thrown_cells() {
+    use cells::game::entity::{EntityFlags, VirusInfo};
+
+    let mut game = new_game(2);
+    let center = center(&game);
+    virus(&mut game, RATIO_MASS * 50, VirusInfo::default());
+    game.new_entity(cell(0, center + Vector2D::new(500, 0), RATIO_MASS * 2, Default::default()));
+    game.new_entity(cell(0, center - Vector2D::new(500, 0), RATIO_MASS * 2, Default::default()));
+    game.entities.flags[2].insert(EntityFlags::THROWN);
+
+    run(&mut game, 5);
+
+    assert_eq!(game.entities.len(), 2);
+    assert_eq!(game.entities.position[1], center + Vector2D::new(500, 0));
+    assert_eq!(game.entities.mass[0], RATIO_MASS * 52);
+}
+
+#[test]
+fn fed_viruses_fire_a_new_virus_away_from_the_feed() {
+    use cells::game::entity::{EntityFlags, VirusInfo};
+
+    let mut game = new_game(2);
+    let center = center(&game);
+    virus(&mut game, RATIO_MASS * 55, VirusInfo { fire_mass: RATIO_MASS * 60, ..Default::default() });
+    // Thrown from the left, without speed left
+    game.new_entity(cell(0, center - Vector2D::new(500, 0), RATIO_MASS * 10, Default::default()));
+    game.entities.flags[1].insert(EntityFlags::THROWN);
+    let total_mass = game.total_mass();
+
+    run(&mut game, 5);
+
+    assert_eq!(game.entities.len(), 2);
+    assert!(game.entities.core.iter().all(|entity| entity.characteristics.virus.is_some()));
+    assert_eq!(game.total_mass(), total_mass);
+    let fired = (0..2).find(|index| game.entities.position[*index] != center).unwrap();
+    assert_eq!(game.entities.mass[fired], total_mass / 2);
+    let position = game.entities.position[fired];
+    assert!(position.x > center.x && (position.y - center.y).abs() < 100, "fired to {:?}", position - center);
+}
+
+#[test]
+fn rings_with_a_negative_gap_are_sampled() {
+    use cells::game::map::generator::{Distribution, RingsField};
+    use cells::game::rng::RngStream;
+
+    let game = new_game(0);
+    let mut rng = game.rng.stream(RngStream::AutoSpawn, 0);
+    let mut rings = Distribution::Rings(RingsField { gap: -1.0, ..Default::default() });
+    for _ in 0..100 { rings.sample(&game, &mut rng); }
+    rings.validate();
+    if let Distribution::Rings(field) = &rings { assert_eq!(field.gap, 0.0) }
+    assert!((0..100).any(|_| rings.sample(&game, &mut rng).is_some()));
+}
+
+#[test]
+fn spawn_on_death_adds_the_templates() {
+    use cells::game::entity::{DeathSpeed, SpawnOnDeathInfo};
+
+    let mut game = new_game(2);
+    let entities = vec![
+        cell(0, Point2D::new(0, 0), RATIO_MASS * 2, Default::default()),
+        cell(1, Point2D::new(100, 0), RATIO_MASS * 3, Default::default()),
+    ];
+    game.new_entity(dying(0, center(&game), RATIO_MASS * 10, OnDeathEffect::Spawn(SpawnOnDeathInfo { entities, amount: 2, speed: DeathSpeed::Radial(100.0) })));
+
+    run(&mut game, 3);
+
+    assert_eq!(game.entities.len(), 4);
+    assert_eq!((game.players[0].entities.len(), game.players[1].entities.len()), (2, 2));
+    assert_eq!(game.total_mass(), RATIO_MASS * 10);
+}
+
+#[test]
+fn explode_on_death_gives_the_particles_to_the_player_of_the_entity() {
+    use cells::game::entity::ExplodeOnDeathInfo;
+
+    let mut game = new_game(2);
+    game.new_entity(dying(1, center(&game), RATIO_MASS * 64, OnDeathEffect::Explode(ExplodeOnDeathInfo { particles: 8, mass_ratio: 0.5, ..Default::default() })));
+
+    run(&mut game, 3);
+
+    assert_eq!(game.entities.len(), 8);
+    assert_eq!(game.players[1].entities.len(), 8);
+    assert_eq!(game.total_mass(), RATIO_MASS * 32);
+}
+
+#[test]
+fn transfer_mass_on_death_goes_to_the_killer() {
+    use cells::game::entity::TransferMassOnDeathInfo;
+
+    let mut game = new_game(2);
+    let center = center(&game);
+    let killer = EntityCharacteristics { killer: true, ..Default::default() };
+    game.new_entity(cell(1, center, RATIO_MASS * 100, killer));
+    let on_death = OnDeathEffect::TransferMass(TransferMassOnDeathInfo { ratio: 0.5, ..Default::default() });
+    game.new_entity(cell(0, center + Vector2D::new(500, 0), RATIO_MASS * 10, EntityCharacteristics { on_death: Some(on_death), ..Default::default() }));
+
+    run(&mut game, 5);
+
+    // The eaten mass, and half of it on top
+    assert_eq!(game.entities.len(), 1);
+    assert_eq!(game.total_mass(), RATIO_MASS * 115);
+}
+
+#[test]
+fn transfer_mass_on_death_doesnt_go_through_walls() {
+    use cells::game::entity::{MassTransferTarget, TransferMassOnDeathInfo};
+
+    // Between the center and the cell on its left
+    let size = 100 * RATIO_POSITION;
+    let x = size / 2 - size / 8;
+    let mut game = new_game_with_walls(2, vec![Wall::Segment(Point2D::new(x, 0), Point2D::new(x, size), 100)]);
+    let center = center(&game);
+    game.new_entity(cell(1, center + Vector2D::new(size / 4, 0), RATIO_MASS * 10, Default::default()));
+    game.new_entity(cell(1, center - Vector2D::new(size / 4, 0), RATIO_MASS * 10, Default::default()));
+    let on_death = OnDeathEffect::TransferMass(TransferMassOnDeathInfo { ratio: 0.5, target: MassTransferTarget::Nearby(size / 2) });
+    game.new_entity(dying(0, center, RATIO_MASS * 10, on_death));
+
+    run(&mut game, 3);
+
+    assert_eq!(game.entities.len(), 2);
+    assert_eq!(game.entities.mass[0], RATIO_MASS * 15);
+    assert_eq!(game.entities.mass[1], RATIO_MASS * 10);
+}
+
+#[test]
+fn walls_bounce_or_slide_moving_cells() {
+    use cells::game::map::walls::Walls;
+
+    // A vertical wall in the middle of the map, the cell comes from its left
+    let x = 50 * RATIO_POSITION;
+    let walls = Walls::new(vec![Wall::Segment(Point2D::new(x, 0), Point2D::new(x, 100 * RATIO_POSITION), 1_000)], Size2D::new(100, 100));
+    let (radius, start) = (1_000.0, Point2D::new(x - 4_500, 50 * RATIO_POSITION));
+    let move_for = |speed: Vector2D<f32>, bounce: bool| {
+        let (mut position, mut speed) = (start, speed);
+        for _ in 0..10 {
+            walls.solve(&mut position, &mut speed, radius, bounce);
+            assert!(position.x <= x - 500 - 1_000, "in the wall at {:?}", position);
+        }
+        (position, speed)
+    };
+
+    let (position, speed) = move_for(Vector2D::new(2_000.0, 0.0), true);
+    assert_eq!(speed, Vector2D::new(-2_000.0, 0.0));
+    assert!(position.x < start.x);
+    let (position, speed) = move_for(Vector2D::new(2_000.0, 1_000.0), false);
+    assert_eq!(speed, Vector2D::new(0.0, 1_000.0));
+    assert_eq!(position.y, start.y + 10 * 1_000);
+
+    // Pushed out of a polygon by its nearest edge, the speed into it removed
+    let walls = Walls::new(vec![Wall::Polygon(vec![Point2D::new(x, x), Point2D::new(x + 10_000, x), Point2D::new(x, x + 10_000)])], Size2D::new(100, 100));
+    let (mut position, mut speed) = (Point2D::new(x - 500, x + 2_000), Vector2D::new(1_000.0, 0.0));
+    assert!(walls.push_out(&mut position, &mut speed, radius, false));
+    assert_eq!(position, Point2D::new(x - 1_000, x + 2_000));
+    assert_eq!(speed, Vector2D::zero());
+    assert!(!walls.push_out(&mut position, &mut speed, radius, false));
+}
+
+#[test]
+fn polygon_walls_are_counterclockwise_convex_hulls() {
+    use cells::game::map::walls::{convex_hull, Walls};
+
+    // Clockwise, with a point inside and one in the middle of an edge
+    let square = vec![Point2D::new(0, 0), Point2D::new(0, 10), Point2D::new(5, 5), Point2D::new(10, 10), Point2D::new(10, 5), Point2D::new(10, 0)];
+    let hull = convex_hull(&square);
+    assert_eq!(hull, vec![Point2D::new(0, 0), Point2D::new(10, 0), Point2D::new(10, 10), Point2D::new(0, 10)]);
+    let area: i32 = hull.iter().zip(hull.iter().cycle().skip(1)).map(|(a, b)| a.x * b.y - b.x * a.y).sum();
+    assert!(area > 0);
+
+    // Aligned points aren't a polygon, the wall is removed
+    assert!(convex_hull(&[Point2D::new(0, 0), Point2D::new(5, 5), Point2D::new(10, 10)]).is_empty());
+    let walls = Walls::new(vec![Wall::Polygon(vec![Point2D::new(0, 0), Point2D::new(5, 5), Point2D::new(10, 10)])], Size2D::new(100, 100));
+    assert!(walls.is_empty());
+}
+
+#[test]
+fn cells_are_not_eaten_through_walls() {
+    // The small cell is in reach of the big one, a wall between them. Without speed the big cell isn't pushed out of the wall
+    let eaten = |walls: Vec<Wall>| {
+        let mut game = new_game_with_walls(2, walls);
+        let center = center(&game);
+        let killer = EntityCharacteristics { killer: true, ..Default::default() };
+        game.new_entity(cell(1, center, RATIO_MASS * 1_000, killer));
+        game.new_entity(cell(0, center + Vector2D::new(10_000, 0), RATIO_MASS * 2, Default::default()));
+        run(&mut game, 5);
+        game.players[0].entities.is_empty()
+    };
+    let center = Point2D::new(50 * RATIO_POSITION, 50 * RATIO_POSITION);
+    assert!(eaten(Vec::new()));
+    assert!(!eaten(vec![Wall::Segment(center + Vector2D::new(5_000, -20_000), center + Vector2D::new(5_000, 20_000), 100)]));
+    assert!(!eaten(vec![Wall::Polygon(vec![center + Vector2D::new(4_000, -2_000), center + Vector2D::new(6_000, -2_000), center + Vector2D::new(5_000, 2_000)])]));
+}
+
+#[test]
+fn respawn_on_death_brings_the_entity_back_after_the_delay() {
+    use cells::game::entity::RespawnOnDeathInfo;
+
+    let mut game = new_game(2);
+    game.new_entity(dying(0, center(&game), RATIO_MASS * 10, OnDeathEffect::Respawn(RespawnOnDeathInfo { delay: 10, lives: Some(1), ..Default::default() })));
+
+    run(&mut game, 3);
+    assert_eq!(game.entities.len(), 0);
+    assert_eq!(game.respawns.len(), 1);
+
+    run(&mut game, 20);
+    assert_eq!(game.entities.len(), 1);
+    assert!(game.respawns.is_empty());
+    assert_eq!(game.total_mass(), RATIO_MASS * 10);
+    // Its last life: it won't come back again
+    assert!(game.entities.core[0].characteristics.on_death.is_none());
+}
+
+#[test]
+fn map_topologies_wrap_confine_and_measure() {
+    use cells::game::map::{fields_between, Map};
+
+    let map = |topology: MapTopology| Map::new(MapInfo { size: Size2D::new(100, 100), walls: Vec::new(), topology });
+    let (rectangle, torus, circle) = (map(MapTopology::Rectangle), map(MapTopology::Torus), map(MapTopology::Circle));
+    let max = torus.max();
+
+    // Through the seam on a torus
+    let (left, right) = (Point2D::new(1_000, 0), Point2D::new(max.width - 999, 0));
+    assert_eq!(torus.delta(left, right), Vector2D::new(-2_000, 0));
+    assert_eq!(torus.delta(right, left), Vector2D::new(2_000, 0));
+    assert_eq!(torus.distance(left, right), 2_000.0);
+    assert_eq!(rectangle.delta(left, right), right - left);
+
+    assert_eq!(torus.confine(Point2D::new(-1, max.height + 1)), Point2D::new(max.width, 0));
+    assert_eq!(rectangle.confine(Point2D::new(-1, max.height + 1)), Point2D::new(0, max.height));
+    let (center, radius) = circle.circle();
+    let confined = circle.confine(Point2D::new(max.width * 2, center.y));
+    assert!(circle.contains(confined) && confined.y == center.y);
+    assert!((circle.distance(center, confined) - radius).abs() < 2.0);
+    assert!(!circle.contains(Point2D::new(0, 0)) && rectangle.contains(Point2D::new(0, 0)));
+
+    // Fields of 10 on an axis of 100
+    assert_eq!(fields_between(-5, 5, 100, 10, false), vec![0]);
+    assert_eq!(fields_between(-5, 5, 100, 10, true), vec![0, 9]);
+    assert_eq!(fields_between(95, 104, 100, 10, true), vec![0, 9]);
+    assert_eq!(fields_between(-50, 60, 100, 10, true), (0..10).collect::<Vec<usize>>());
+    assert_eq!(fields_between(20, 35, 100, 10, true), vec![2, 3]);
+}
+
+#[test]
+fn cells_cross_the_seam_of_a_torus() {
+    let mut game = new_game_with_map(2, Vec::new(), MapTopology::Torus);
+    let max = game.map.max();
+    game.new_entity(EntityInfo {
+        speed: Vector2D::new(3_000.0, 0.0),
+        ..cell(0, Point2D::new(max.width - 1_000, max.height / 2), RATIO_MASS * 10, Default::default())
+    });
+
+    run(&mut game, 3);
+
+    let position = game.entities.position[0];
+    assert!(position.x > 0 && position.x < 10_000, "at {:?}", position);
+    assert_eq!(position.y, max.height / 2);
+}
+
+#[test]
+fn cells_eat_and_collide_across_the_seam_of_a_torus() {
+    // A killer on the left side, a small cell on the right side: 2_000 apart through the seam
+    let eaten = |topology: MapTopology| {
+        let mut game = new_game_with_map(2, Vec::new(), topology);
+        let max = game.map.max();
+        let killer = EntityCharacteristics { killer: true, ..Default::default() };
+        game.new_entity(cell(1, Point2D::new(1_000, max.height / 2), RATIO_MASS * 100, killer));
+        game.new_entity(cell(0, Point2D::new(max.width - 999, max.height / 2), RATIO_MASS * 2, Default::default()));
+        run(&mut game, 5);
+        game.players[0].entities.is_empty()
+    };
+    assert!(eaten(MapTopology::Torus));
+    assert!(!eaten(MapTopology::Rectangle));
+
+    let mut game = new_game_with_map(2, Vec::new(), MapTopology::Torus);
+    let max = game.map.max();
+    let collide = EntityCharacteristics { collide: true, collide_when_mergeable: true, ..Default::default() };
+    game.new_entity(cell(1, Point2D::new(500, max.height / 2), RATIO_MASS * 50, collide.clone()));
+    game.new_entity(cell(1, Point2D::new(max.width - 499, max.height / 2), RATIO_MASS * 50, collide));
+    let distance = |game: &Game| game.map.distance(game.entities.position[0], game.entities.position[1]);
+    let start = distance(&game);
+
+    run(&mut game, 20);
+
+    assert!(distance(&game) > start, "distance {} -> {}", start, distance(&game));
+    // Pushed apart away from the seam, each one on its side
+    assert!(game.entities.position[0].x > 500 && game.entities.position[1].x < max.width - 499);
+}
+
+#[test]
+fn circle_arenas_bounce_or_stop_the_cells_at_their_border() {
+    let speed_after = |bounce: bool| {
+        let mut game = new_game_with_map(2, Vec::new(), MapTopology::Circle);
+        let (center, radius) = game.map.circle();
+        let characteristics = EntityCharacteristics { bounce, ..Default::default() };
+        game.new_entity(EntityInfo {
+            speed: Vector2D::new(5_000.0, 0.0),
+            ..cell(0, center + Vector2D::new(radius as i32 - 1_000, 0), RATIO_MASS * 10, characteristics)
+        });
+        for _ in 0..5 {
+            run(&mut game, 1);
+            assert!(game.map.contains(game.entities.position[0]), "{:?} out of the arena", game.entities.position[0] - center);
+        }
+        game.entities.speed[0]
+    };
+    assert!(speed_after(true).x < 0.0);
+    assert_eq!(speed_after(false), Vector2D::zero());
+}
+
+#[test]
+fn despawning_cells_die_when_leaving_the_map() {
+    use cells::game::entity::EdgePolicy;
+
+    let alive_after = |topology: MapTopology, edge: EdgePolicy| {
+        let mut game = new_game_with_map(2, Vec::new(), topology);
+        let max = game.map.max();
+        let characteristics = EntityCharacteristics { edge, ..Default::default() };
+        game.new_entity(EntityInfo {
+            speed: Vector2D::new(3_000.0, 0.0),
+            ..cell(0, Point2D::new(max.width - 1_000, max.height / 2), RATIO_MASS * 10, characteristics)
+        });
+        run(&mut game, 3);
+        game.entities.len()
+    };
+    assert_eq!(alive_after(MapTopology::Rectangle, EdgePolicy::Despawn), 0);
+    assert_eq!(alive_after(MapTopology::Torus, EdgePolicy::Despawn), 0);
+    assert_eq!(alive_after(MapTopology::Rectangle, EdgePolicy::Map), 1);
+    assert_eq!(alive_after(MapTopology::Torus, EdgePolicy::Map), 1);
+}
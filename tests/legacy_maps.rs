@@ -0,0 +1,48 @@
+// Maps saved with the previous versions of the format (maps/legacy) are upgraded to the last one and still run, see format.
+#![cfg(feature = "serialize")]
+
+use cells::game::{read_map, Game, GameInfo, GameSerialize};
+use cells::game::format::{self, VERSION};
+use cells::game_solver::GameSolver;
+
+const LEGACY_MAPS_FOLDER: &str = "maps/legacy";
+
+#[test]
+fn legacy_maps_are_upgraded() {
+    let mut paths: Vec<String> = std::fs::read_dir(LEGACY_MAPS_FOLDER).unwrap()
+        .map(|entry| entry.unwrap().path().to_string_lossy().to_string())
+        .filter(|path| path.ends_with(".binmap") || path.ends_with(".ronmap"))
+        .collect();
+    paths.sort();
+
+    let mut versions = Vec::new();
+    for path in paths.iter() {
+        let (mut map, version): (GameSerialize, u32) = read_map(path).unwrap_or_else(|err| panic!("{}", err));
+        assert!(version < VERSION, "{}: version {} isn't older than {}", path, version, VERSION);
+        // Files are named after their version: v3.binmap, v0_without_rng.ronmap
+        let name = std::path::Path::new(path).file_name().unwrap().to_string_lossy();
+        let named: u32 = name.trim_start_matches('v').split(|c| c == '.' || c == '_').next().unwrap().parse().unwrap();
+        assert_eq!(version, named, "{}", path);
+        versions.push(version);
+
+        // Saved again, it is a map of the last version
+        let (upgraded, upgraded_version) = format::read_binary(&format::write_binary(&map).unwrap()).unwrap();
+        assert_eq!(upgraded_version, VERSION, "{}", path);
+        assert_eq!(upgraded.entities_info.len(), map.entities_info.len(), "{}", path);
+
+        let mut game = Game::new(GameInfo { threads: 0 });
+        map.resolve_textures(&game.textures).unwrap_or_else(|err| panic!("{}: {}", path, err));
+        map.to_game(&mut game);
+        assert_eq!(game.players.len(), map.players_info.len(), "{}", path);
+        assert_eq!(game.entities.len(), map.entities_info.len(), "{}", path);
+
+        GameSolver::new(&mut game).solve();
+        assert_eq!(game.rng.tick, map.rng.tick + 1, "{}", path);
+        let in_players: usize = game.players.iter().map(|player| player.entities.len()).sum();
+        assert_eq!(in_players, game.entities.len(), "{}", path);
+    }
+
+    for version in 0..VERSION {
+        assert!(versions.contains(&version), "no map of version {} in {}", version, LEGACY_MAPS_FOLDER);
+    }
+}